@@ -1,5 +1,5 @@
-use criterion::{BatchSize, Criterion, Throughput, black_box, criterion_group, criterion_main};
-use droptables::DropTable;
+use criterion::{BatchSize, Criterion, Throughput, black_box, criterion_group};
+use droptables::{DropTable, IndexSampler, SmallSampler, WeightedSampler};
 use rand::{Rng, SeedableRng};
 use rand_pcg::Pcg32;
 
@@ -59,5 +59,138 @@ fn bench_droptable_sample(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(droptable, bench_droptable_build, bench_droptable_sample);
-criterion_main!(droptable);
+#[cfg(feature = "bench-support")]
+mod compare {
+    use super::*;
+    use droptables::bench_support::{CdfSampler, RandWeightedIndex};
+    use droptables::{IndexSampler, WeightedSampler};
+    use std::time::Instant;
+
+    const SIZES: &[usize] = &[2, 8, 64, 512, 4096, 65536];
+    const DRAWS_PER_ITER: usize = 1024;
+
+    fn gen_weights(n: usize) -> Vec<f32> {
+        gen_pairs(n).into_iter().map(|(_, w)| w).collect()
+    }
+
+    fn draw_many<S: IndexSampler>(s: &S, rng: &mut Pcg32, draws: usize) -> usize {
+        let mut acc = 0usize;
+        for _ in 0..draws {
+            acc ^= s.sample_index(rng);
+        }
+        acc
+    }
+
+    fn bench_one<S: IndexSampler>(
+        group: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+        name: &str,
+        n: usize,
+        s: &S,
+    ) {
+        group.bench_function(format!("{name}_n={n}"), |b| {
+            b.iter_batched_ref(
+                || Pcg32::seed_from_u64(4242),
+                |rng| black_box(draw_many(s, rng, DRAWS_PER_ITER)),
+                BatchSize::SmallInput,
+            );
+        });
+    }
+
+    pub fn bench_compare_sample(c: &mut Criterion) {
+        let mut group = c.benchmark_group("compare_sample");
+        group.throughput(Throughput::Elements(DRAWS_PER_ITER as u64));
+        for &n in SIZES {
+            let w = gen_weights(n);
+            bench_one(&mut group, "alias", n, &WeightedSampler::new(&w).unwrap());
+            bench_one(
+                &mut group,
+                "weighted_index",
+                n,
+                &RandWeightedIndex::new(&w).unwrap(),
+            );
+            bench_one(&mut group, "cdf", n, &CdfSampler::new(&w).unwrap());
+        }
+        group.finish();
+    }
+
+    fn ns_per_draw<S: IndexSampler>(s: &S) -> f64 {
+        const DRAWS: usize = 1 << 20;
+        let mut rng = Pcg32::seed_from_u64(1);
+        let start = Instant::now();
+        black_box(draw_many(s, &mut rng, DRAWS));
+        start.elapsed().as_nanos() as f64 / DRAWS as f64
+    }
+
+    /// Quick single-pass timing, printed as a table for at-a-glance comparison.
+    pub fn print_summary() {
+        println!(
+            "\n{:>8} {:>12} {:>16} {:>12}",
+            "n", "alias ns", "weighted_idx ns", "cdf ns"
+        );
+        for &n in SIZES {
+            let w = gen_weights(n);
+            let alias = ns_per_draw(&WeightedSampler::new(&w).unwrap());
+            let widx = ns_per_draw(&RandWeightedIndex::new(&w).unwrap());
+            let cdf = ns_per_draw(&CdfSampler::new(&w).unwrap());
+            println!("{n:>8} {alias:>12.2} {widx:>16.2} {cdf:>12.2}");
+        }
+    }
+}
+
+/// Tables of up to [`SmallSampler::MAX_LEN`] entries: the opt-in
+/// [`SamplerKind::Small`](droptables::SamplerKind::Small) sampler against
+/// the alias table `from_pairs` builds by default.
+fn bench_small_tables(c: &mut Criterion) {
+    let mut group = c.benchmark_group("small_table_sample");
+    const DRAWS_PER_ITER: usize = 1024;
+    group.throughput(Throughput::Elements(DRAWS_PER_ITER as u64));
+
+    fn draw_many<S: IndexSampler>(s: &S, rng: &mut Pcg32) -> usize {
+        let mut acc = 0usize;
+        for _ in 0..DRAWS_PER_ITER {
+            acc ^= s.sample_index(rng);
+        }
+        acc
+    }
+
+    for &n in &[2usize, 3, 4] {
+        let w: Vec<f32> = gen_pairs(n).into_iter().map(|(_, w)| w).collect();
+        let alias = WeightedSampler::new(&w).unwrap();
+        let small = SmallSampler::new(&w).unwrap();
+        group.bench_function(format!("alias_n={n}"), |b| {
+            b.iter_batched_ref(
+                || Pcg32::seed_from_u64(4242),
+                |rng| black_box(draw_many(&alias, rng)),
+                BatchSize::SmallInput,
+            );
+        });
+        group.bench_function(format!("small_n={n}"), |b| {
+            b.iter_batched_ref(
+                || Pcg32::seed_from_u64(4242),
+                |rng| black_box(draw_many(&small, rng)),
+                BatchSize::SmallInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    droptable,
+    bench_droptable_build,
+    bench_droptable_sample,
+    bench_small_tables
+);
+
+#[cfg(feature = "bench-support")]
+criterion_group!(comparison, compare::bench_compare_sample);
+
+fn main() {
+    droptable();
+    #[cfg(feature = "bench-support")]
+    {
+        comparison();
+        compare::print_summary();
+    }
+    Criterion::default().configure_from_args().final_summary();
+}
@@ -0,0 +1,169 @@
+// `assert_table_matches!`: compare a config file's drop rates with a
+// `WeightedEnum` at compile time.
+
+use droptables_odds::{TomlLine, parse_odds, toml_line};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{LitStr, Token};
+
+// Relative difference tolerated between the file and `ENTRIES`, which
+// only hold `f32`s.
+const TOLERANCE: f64 = 1e-5;
+
+// `"path", Type` or `"path", Type, table = "name"`.
+pub struct Args {
+    path: LitStr,
+    ty: syn::Path,
+    table: Option<LitStr>,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let ty = input.parse()?;
+        let mut table = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            if key != "table" {
+                return Err(syn::Error::new(key.span(), "expected `table = \"name\"`"));
+            }
+            input.parse::<Token![=]>()?;
+            table = Some(input.parse()?);
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(Self { path, ty, table })
+    }
+}
+
+struct Row {
+    line: usize,
+    name: String,
+    p: f64,
+}
+
+pub fn expand(args: Args) -> syn::Result<TokenStream> {
+    let rel = args.path.value();
+    let span = args.path.span();
+    let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let full = std::path::Path::new(&dir).join(&rel);
+    let text = std::fs::read_to_string(&full)
+        .map_err(|e| syn::Error::new(span, format!("can't read {}: {e}", full.display())))?;
+    let table = args.table.as_ref().map(LitStr::value);
+    let rows = rows(&text, table.as_deref())
+        .map_err(|(line, e)| syn::Error::new(span, format!("{rel}:{line}: {e}")))?;
+    if rows.is_empty() {
+        let what = table.map_or_else(|| rel.clone(), |t| format!("[{t}] in {rel}"));
+        return Err(syn::Error::new(span, format!("no entries found in {what}")));
+    }
+
+    let ty = &args.ty;
+    let ty_name = quote!(#ty).to_string().replace(' ', "");
+    let full = full.to_string_lossy().into_owned();
+    let names = rows.iter().map(|r| &r.name);
+    let checks = rows.iter().map(|Row { line, name, p }| {
+        let lo = (p * (1.0 - TOLERANCE)) as f32;
+        let hi = (p * (1.0 + TOLERANCE)) as f32;
+        let unknown = format!("{rel}:{line}: `{name}` is not a variant of {ty_name}");
+        let differs = format!(
+            "{rel}:{line}: `{name}` is {p} in the file, but {ty_name} gives it a different weight"
+        );
+        quote! {
+            match weight_of(#name) {
+                ::core::option::Option::None => panic!("{}", #unknown),
+                ::core::option::Option::Some(w) if w < #lo || w > #hi => panic!("{}", #differs),
+                ::core::option::Option::Some(_) => {}
+            }
+        }
+    });
+    let missing = format!("{ty_name} has a variant with nonzero weight that {rel} doesn't list");
+
+    Ok(quote! {
+        const _: () = {
+            // rebuild when the file changes
+            const _: &str = include_str!(#full);
+            const NAMES: &[&str] = &[#(#names),*];
+            const ENTRIES: &[(#ty, f32)] = <#ty as droptables::WeightedEnum>::ENTRIES;
+
+            const fn same(a: &str, b: &str) -> bool {
+                let (a, b) = (a.as_bytes(), b.as_bytes());
+                if a.len() != b.len() {
+                    return false;
+                }
+                let mut i = 0;
+                while i < a.len() {
+                    if a[i] != b[i] {
+                        return false;
+                    }
+                    i += 1;
+                }
+                true
+            }
+
+            const fn weight_of(name: &str) -> ::core::option::Option<f32> {
+                let mut i = 0;
+                while i < ENTRIES.len() {
+                    if same(ENTRIES[i].0.as_str(), name) {
+                        return ::core::option::Option::Some(ENTRIES[i].1);
+                    }
+                    i += 1;
+                }
+                ::core::option::Option::None
+            }
+
+            #(#checks)*
+
+            let mut i = 0;
+            while i < ENTRIES.len() {
+                if ENTRIES[i].1 > 0.0 {
+                    let mut listed = false;
+                    let mut j = 0;
+                    while j < NAMES.len() {
+                        listed |= same(ENTRIES[i].0.as_str(), NAMES[j]);
+                        j += 1;
+                    }
+                    if !listed {
+                        panic!("{}", #missing);
+                    }
+                }
+                i += 1;
+            }
+        };
+    })
+}
+
+// Rows of the flat TOML subset (see `droptables_odds::toml_line`) under
+// `table`, where a value is a number or a string in the `Odds::parse`
+// grammar.
+fn rows(text: &str, table: Option<&str>) -> Result<Vec<Row>, (usize, String)> {
+    let mut rows: Vec<Row> = Vec::new();
+    let mut current: Option<&str> = None;
+    for (i, raw) in text.lines().enumerate() {
+        let line = i + 1;
+        let (name, value) = match toml_line(raw).map_err(|e| (line, e.to_string()))? {
+            TomlLine::Blank => continue,
+            TomlLine::Header(header) => {
+                current = Some(header);
+                continue;
+            }
+            TomlLine::Entry { name, value } => (name.to_string(), value),
+        };
+        if current != table {
+            continue;
+        }
+        if rows.iter().any(|r| r.name == name) {
+            return Err((line, format!("`{name}` is listed twice")));
+        }
+        let p = match parse_odds(droptables_odds::unquote(value).unwrap_or(value)) {
+            Ok((num, den)) => num as f64 / den as f64,
+            // TOML floats like `1e-3`
+            Err(e) => match value.parse::<f64>() {
+                Ok(p) if (0.0..=1.0).contains(&p) => p,
+                _ => return Err((line, e.to_string())),
+            },
+        };
+        rows.push(Row { line, name, p });
+    }
+    Ok(rows)
+}
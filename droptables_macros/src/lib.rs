@@ -1,3 +1,6 @@
+mod config;
+
+use droptables_odds::parse_odds;
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{
@@ -5,7 +8,20 @@ use syn::{
     spanned::Spanned,
 };
 
-#[proc_macro_derive(WeightedEnum, attributes(odds, rest))]
+#[proc_macro_derive(
+    WeightedEnum,
+    attributes(
+        weighted_enum,
+        extends,
+        odds,
+        probability,
+        rest,
+        retired,
+        bp,
+        ppm,
+        name
+    )
+)]
 pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let enum_ident = &input.ident;
@@ -19,16 +35,24 @@ pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
         .into();
     };
 
+    let opts = match EnumOpts::parse(&input.attrs) {
+        Ok(o) => o,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
     // Stage 1: parse attributes
     #[derive(Debug)]
     struct VarTmp {
         ident: syn::Ident,
         prob: Option<f64>, // from #[odds="A/B"]
         is_rest: bool,     // from #[rest]
+        is_retired: bool,  // from #[retired]
     }
 
     let mut tmp: Vec<VarTmp> = Vec::with_capacity(data_enum.variants.len());
     let mut rest_count = 0usize;
+    // (variant, text name) for as_str/FromStr
+    let mut names: Vec<(syn::Ident, String)> = Vec::with_capacity(data_enum.variants.len());
 
     for v in &data_enum.variants {
         match v.fields {
@@ -42,9 +66,31 @@ pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
 
         let mut prob: Option<f64> = None;
         let mut is_rest = false;
+        let mut is_retired = false;
+        let mut name: Option<String> = None;
 
         for Attribute { meta, .. } in &v.attrs {
-            if meta.path().is_ident("odds") {
+            if meta.path().is_ident("name") {
+                let s = match meta {
+                    Meta::NameValue(MetaNameValue {
+                        value:
+                            syn::Expr::Lit(syn::ExprLit {
+                                lit: Lit::Str(s), ..
+                            }),
+                        ..
+                    }) => s.value(),
+                    _ => {
+                        return syn::Error::new(meta.span(), r#"use #[name = "..."]"#)
+                            .to_compile_error()
+                            .into();
+                    }
+                };
+                if name.replace(s).is_some() {
+                    return syn::Error::new(meta.span(), "duplicate #[name] on variant")
+                        .to_compile_error()
+                        .into();
+                }
+            } else if meta.path().is_ident("odds") {
                 let Meta::NameValue(MetaNameValue { value, .. }) = meta else {
                     return syn::Error::new(meta.span(), r#"use #[odds = "A/B"] (string literal)"#)
                         .to_compile_error()
@@ -56,7 +102,14 @@ pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
                     syn::Expr::Lit(syn::ExprLit {
                         lit: Lit::Str(s), ..
                     }) => match parse_odds_str(&s.value()) {
-                        Ok(p) => p,
+                        Ok(p) => {
+                            if let Some(base) = opts.denominator
+                                && let Err(e) = check_denominator(&s.value(), base)
+                            {
+                                return syn::Error::new(s.span(), e).to_compile_error().into();
+                            }
+                            p
+                        }
                         Err(e) => return syn::Error::new(s.span(), e).to_compile_error().into(),
                     },
                     _ => {
@@ -78,11 +131,93 @@ pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
                     .into();
                 }
                 if prob.replace(p).is_some() {
-                    return syn::Error::new(meta.span(), "duplicate #[odds] on variant")
+                    return syn::Error::new(meta.span(), DUPLICATE_PROB)
+                        .to_compile_error()
+                        .into();
+                }
+            } else if let Some((name, denom)) = scaled_attr(meta) {
+                // #[bp = 125] => 125/10_000, #[ppm = 300] => 300/1_000_000
+                let p = match meta {
+                    Meta::NameValue(MetaNameValue {
+                        value:
+                            syn::Expr::Lit(syn::ExprLit {
+                                lit: Lit::Int(n), ..
+                            }),
+                        ..
+                    }) => match n.base10_parse::<u64>() {
+                        Ok(v) if v > 0 => {
+                            // exactly v/10_000 or v/1_000_000
+                            if let Some(base) = opts.denominator
+                                && let Err(e) = check_denominator(&format!("{v}/{denom}"), base)
+                            {
+                                return syn::Error::new(n.span(), e).to_compile_error().into();
+                            }
+                            v as f64 / denom
+                        }
+                        _ => {
+                            return syn::Error::new(
+                                n.span(),
+                                format!("{name} must be a positive integer"),
+                            )
+                            .to_compile_error()
+                            .into();
+                        }
+                    },
+                    _ => {
+                        return syn::Error::new(
+                            meta.span(),
+                            format!("use #[{name} = N] with an integer literal"),
+                        )
+                        .to_compile_error()
+                        .into();
+                    }
+                };
+                if prob.replace(p).is_some() {
+                    return syn::Error::new(meta.span(), DUPLICATE_PROB)
+                        .to_compile_error()
+                        .into();
+                }
+            } else if meta.path().is_ident("probability") {
+                // #[probability(0.25)], #[probability(1/4)], #[probability("1/4")]
+                let expr = match meta
+                    .require_list()
+                    .and_then(|l| l.parse_args::<syn::Expr>())
+                {
+                    Ok(e) => e,
+                    Err(e) => return e.to_compile_error().into(),
+                };
+                let p = match eval_probability(&expr) {
+                    Ok(p) => p,
+                    Err(e) => return e.to_compile_error().into(),
+                };
+                if let Some(base) = opts.denominator {
+                    let checked = probability_fraction(&expr)
+                        .ok_or_else(|| {
+                            format!(
+                                "can't check this against the enum's denominator {base}; write it as a fraction like #[probability(3 / {base})]"
+                            )
+                        })
+                        .and_then(|odds| check_denominator(&odds, base));
+                    if let Err(e) = checked {
+                        return syn::Error::new(expr.span(), e).to_compile_error().into();
+                    }
+                }
+                if p <= 0.0 || !p.is_finite() {
+                    return syn::Error::new(meta.span(), "probability must be positive and finite")
+                        .to_compile_error()
+                        .into();
+                }
+                if prob.replace(p).is_some() {
+                    return syn::Error::new(meta.span(), DUPLICATE_PROB)
                         .to_compile_error()
                         .into();
                 }
             } else if meta.path().is_ident("rest") {
+                if !matches!(meta, Meta::Path(_)) {
+                    return syn::Error::new(meta.span(), "#[rest] takes no arguments")
+                        .to_compile_error()
+                        .into();
+                }
                 if is_rest {
                     return syn::Error::new(meta.span(), "duplicate #[rest] on variant")
                         .to_compile_error()
@@ -90,25 +225,92 @@ pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
                 }
                 is_rest = true;
                 rest_count += 1;
+            } else if meta.path().is_ident("retired") {
+                if !matches!(meta, Meta::Path(_)) {
+                    return syn::Error::new(meta.span(), "#[retired] takes no arguments")
+                        .to_compile_error()
+                        .into();
+                }
+                if is_retired {
+                    return syn::Error::new(meta.span(), "duplicate #[retired] on variant")
+                        .to_compile_error()
+                        .into();
+                }
+                is_retired = true;
+            } else if meta.path().is_ident("weighted_enum") || meta.path().is_ident("extends") {
+                return syn::Error::new(
+                    meta.span(),
+                    "#[weighted_enum] and #[extends] go on the enum, not on a variant",
+                )
+                .to_compile_error()
+                .into();
             }
         }
 
-        if prob.is_none() && !is_rest {
+        if opts.doc_odds {
+            match doc_odds(&v.attrs, opts.denominator) {
+                Ok(None) => {}
+                Ok(Some((_, span))) if is_rest || is_retired => {
+                    return syn::Error::new(span, "@odds tag on a #[rest] or #[retired] variant")
+                        .to_compile_error()
+                        .into();
+                }
+                Ok(Some((p, span))) => match prob {
+                    None => prob = Some(p),
+                    Some(q) if (q - p).abs() > 1e-12 * p => {
+                        return syn::Error::new(
+                            span,
+                            "@odds tag disagrees with the variant's odds attribute",
+                        )
+                        .to_compile_error()
+                        .into();
+                    }
+                    Some(_) => {}
+                },
+                Err(e) => return e.to_compile_error().into(),
+            }
+        }
+
+        if is_retired && is_rest {
+            return syn::Error::new(v.span(), "the #[rest] variant can't be #[retired]")
+                .to_compile_error()
+                .into();
+        }
+        if prob.is_none() && !is_rest && !is_retired {
             return syn::Error::new(
                 v.span(),
-                "each variant must have either #[odds=\"A/B\"] or #[rest]",
+                "each variant must have either #[odds=\"A/B\"], #[probability(P)], #[bp = N], #[ppm = N] or #[rest]",
             )
             .to_compile_error()
             .into();
         }
 
+        let name = name.unwrap_or_else(|| v.ident.to_string());
+        if names.iter().any(|(_, n)| *n == name) {
+            return syn::Error::new(v.span(), format!("two variants are named \"{name}\""))
+                .to_compile_error()
+                .into();
+        }
+        names.push((v.ident.clone(), name));
         tmp.push(VarTmp {
             ident: v.ident.clone(),
             prob,
             is_rest,
+            is_retired,
         });
     }
 
+    if rest_count == 0
+        && let Some(v) = tmp.iter().find(|v| v.is_retired)
+    {
+        return syn::Error::new(
+            v.ident.span(),
+            "#[retired] needs a #[rest] variant to take over its odds",
+        )
+        .to_compile_error()
+        .into();
+    }
+
     if rest_count > 1 {
         return syn::Error::new(enum_ident.span(), "at most one variant may use #[rest]")
             .to_compile_error()
@@ -117,14 +319,30 @@ pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
 
     // Stage 2: validate and materialize probabilities
     const EPS: f64 = 1e-6;
-    let mut sum_known = 0.0f64;
-    for v in &tmp {
+    // spliced-in tables take their share of the probability mass first
+    let extends_total: f64 = opts.extends.iter().map(|e| e.scale).sum();
+    let mut sum_known = extends_total;
+    // retired variants' nominal odds are left for #[rest] to absorb
+    for v in tmp.iter().filter(|v| !v.is_retired) {
         if let Some(p) = v.prob {
             sum_known += p;
         }
     }
 
-    let finalized: Vec<(syn::Ident, f32)> = if rest_count == 1 {
+    let finalized: Vec<(syn::Ident, f64)> = if opts.relative {
+        // #[weighted_enum(relative)]: values are weights, normalized here
+        if rest_count > 0 || !opts.extends.is_empty() {
+            return syn::Error::new(
+                enum_ident.span(),
+                "#[weighted_enum(relative)] can't be combined with #[rest] or #[extends]",
+            )
+            .to_compile_error()
+            .into();
+        }
+        tmp.into_iter()
+            .map(|v| (v.ident, v.prob.unwrap() / sum_known))
+            .collect()
+    } else if rest_count == 1 {
         if sum_known > 1.0 + EPS {
             return syn::Error::new(
                 enum_ident.span(),
@@ -144,7 +362,9 @@ pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
         }
         tmp.into_iter()
             .map(|v| {
-                let p = if v.is_rest {
+                let p = if v.is_retired {
+                    0.0
+                } else if v.is_rest {
                     if rest_val < 0.0 && rest_val.abs() <= EPS {
                         0.0
                     } else {
@@ -153,7 +373,7 @@ pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
                 } else {
                     v.prob.unwrap()
                 };
-                (v.ident, p as f32)
+                (v.ident, p)
             })
             .collect()
     } else {
@@ -170,12 +390,16 @@ pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
             .into();
         }
         tmp.into_iter()
-            .map(|v| (v.ident, v.prob.unwrap() as f32))
+            .map(|v| (v.ident, v.prob.unwrap()))
             .collect()
     };
 
     // Stage 3: expand
     let entries = finalized.iter().map(|(ident, p)| {
+        let p = *p as f32;
+        quote! { (#enum_ident::#ident, #p) }
+    });
+    let resolved = finalized.iter().map(|(ident, p)| {
         quote! { (#enum_ident::#ident, #p) }
     });
 
@@ -186,17 +410,156 @@ pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
         .iter()
         .map(|(ident, _)| quote! { #enum_ident::#ident })
         .collect();
-    let var_weights: Vec<proc_macro2::TokenStream> =
-        finalized.iter().map(|(_, p)| quote! { #p }).collect();
+    let var_weights: Vec<proc_macro2::TokenStream> = finalized
+        .iter()
+        .map(|(_, p)| {
+            let p = *p as f32;
+            quote! { #p }
+        })
+        .collect();
     // Borrowed aliases used inside quote! to avoid moving the Vecs.
     let var_idents_ref = &var_idents;
     let var_weights_ref = &var_weights;
 
+    let cached = cached_droptable(
+        enum_ident,
+        quote! { droptables::WeightedSampler },
+        "an alias sampler",
+    );
+
+    let selftest = if opts.selftest {
+        let test_ident = syn::Ident::new(
+            &format!("__droptables_selftest_{enum_ident}"),
+            enum_ident.span(),
+        );
+        quote! {
+            #[cfg(test)]
+            #[test]
+            #[allow(non_snake_case)]
+            fn #test_ident() {
+                #enum_ident::sampler().expect("weights must build an alias table");
+                let sum: f64 = #enum_ident::probability_table().iter().map(|(_, p)| *p).sum::<f64>()
+                    + #extends_total;
+                assert!((sum - 1.0).abs() <= 1e-6, "probabilities sum to {sum}, not 1");
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let extended = expand_extends(&input.vis, enum_ident, &opts.extends);
+    let rates_doc = drop_rates_doc(&finalized, &opts.extends);
+    let (rolls_const, roll_fn) = match opts.rolls {
+        Some(n) => {
+            // build each drop by index so `roll` needs neither Copy nor Clone
+            let arms = var_idents
+                .iter()
+                .enumerate()
+                .map(|(i, v)| quote! { #i => #v, });
+            (
+                quote! { const ROLLS: usize = #n; },
+                quote! {
+                    /// Roll the table `ROLLS` times (from
+                    /// `#[weighted_enum(rolls = N)]`), independently.
+                    pub fn roll<R: droptables::rand::Rng + ?Sized>(rng: &mut R) -> [#enum_ident; #n] {
+                        static SAMPLER: ::std::sync::OnceLock<droptables::WeightedSampler> =
+                            ::std::sync::OnceLock::new();
+                        let sampler = SAMPLER.get_or_init(|| {
+                            #enum_ident::sampler().expect("derive-validated weights always build")
+                        });
+                        ::core::array::from_fn(|_| match sampler.sample_index(rng) {
+                            #(#arms)*
+                            _ => unreachable!("alias index out of range"),
+                        })
+                    }
+                },
+            )
+        }
+        None => (quote! {}, quote! {}),
+    };
+
+    let discriminants = match int_repr(&input.attrs) {
+        Some(repr) => {
+            let rows = finalized.iter().map(|(ident, p)| {
+                let p = *p as f32;
+                quote! { (#enum_ident::#ident as #repr, #p) }
+            });
+            quote! {
+                /// [`ENTRIES`](droptables::WeightedEnum::ENTRIES) keyed by
+                /// discriminant (from the enum's `#[repr]`), in declaration
+                /// order.
+                pub const DISCRIMINANT_ENTRIES: &'static [(#repr, f32)] = &[
+                    #(#rows),*
+                ];
+            }
+        }
+        None => quote! {},
+    };
+
+    let name_arms = names
+        .iter()
+        .map(|(ident, name)| quote! { #enum_ident::#ident => #name });
+    let weight_arms = finalized.iter().map(|(ident, p)| {
+        let p = *p as f32;
+        quote! { #enum_ident::#ident => #p }
+    });
+    let odds_arms = finalized.iter().map(|(ident, p)| {
+        let p = p.clamp(0.0, 1.0);
+        quote! { #enum_ident::#ident => #p }
+    });
+    let parse_arms = names
+        .iter()
+        .map(|(ident, name)| quote! { #name => ::core::result::Result::Ok(#enum_ident::#ident) });
+
     let expanded = quote! {
+        #selftest
+
+        #extended
+
+        impl ::core::str::FromStr for #enum_ident {
+            type Err = droptables::UnknownVariant;
+
+            /// Parse a variant from its [`as_str`](Self::as_str) name.
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                match s {
+                    #(#parse_arms,)*
+                    _ => ::core::result::Result::Err(droptables::UnknownVariant {
+                        name: ::std::string::ToString::to_string(s),
+                    }),
+                }
+            }
+        }
+
+        impl ::core::convert::TryFrom<&str> for #enum_ident {
+            type Error = droptables::UnknownVariant;
+
+            fn try_from(s: &str) -> ::core::result::Result<Self, Self::Error> {
+                ::core::str::FromStr::from_str(s)
+            }
+        }
+
         impl droptables::WeightedEnum for #enum_ident {
+            /// Variants with their weights, in declaration order.
+            ///
+            #[doc = #rates_doc]
             const ENTRIES: &'static [(#enum_ident, f32)] = &[
                 #(#entries),*
             ];
+
+            #rolls_const
+
+            fn weight(&self) -> f32 {
+                match self {
+                    #(#weight_arms,)*
+                }
+            }
+
+            fn odds(&self) -> droptables::Odds {
+                let p: f64 = match self {
+                    #(#odds_arms,)*
+                };
+                droptables::Odds::from_prob(p).expect("derive-validated probabilities are in [0, 1]")
+            }
         }
 
         impl #enum_ident {
@@ -206,6 +569,8 @@ pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
             /// Returns `StaticDropTable<WeightedSampler, Self>`, which can:
             /// - `sample(&mut rng) -> &'static Self` (borrowed)
             /// - `sample_owned(&mut rng) -> Self`    (requires `Copy`)
+            ///
+            #[doc = #rates_doc]
             pub fn droptable() -> ::core::result::Result<
                 droptables::StaticDropTable<droptables::WeightedSampler, #enum_ident>,
                 droptables::ProbError
@@ -223,6 +588,21 @@ pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
                 Ok(droptables::StaticDropTable::new(sampler, VARS))
             }
 
+            /// Infallible [`droptable`](Self::droptable): the derive already
+            /// rejected any weights that could fail to build at compile time.
+            /// Built on the first call; later calls return the same table.
+            pub fn droptable_unchecked() -> &'static droptables::StaticDropTable<droptables::WeightedSampler, #enum_ident>
+            where
+                #enum_ident: Copy + Sync + 'static
+            {
+                static TABLE: ::std::sync::OnceLock<
+                    droptables::StaticDropTable<droptables::WeightedSampler, #enum_ident>
+                > = ::std::sync::OnceLock::new();
+                TABLE.get_or_init(|| {
+                    #enum_ident::droptable().expect("derive-validated weights always build")
+                })
+            }
+
             /// If you explicitly want the **owning** table with internal alias state
             /// (stores a `Vec<Self>` so you can take `&Self` without `'static`),
             /// call this.
@@ -241,6 +621,50 @@ pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
                 droptables::WeightedSampler::new(WEIGHTS)
             }
 
+            /// Every variant with its **resolved** probability (including the
+            /// computed `#[rest]` remainder), in declaration order.
+            pub fn probability_table() -> &'static [(#enum_ident, f64)] {
+                const TABLE: &[(#enum_ident, f64)] = &[
+                    #(#resolved),*
+                ];
+                TABLE
+            }
+
+            /// [`probability_table`](Self::probability_table) wrapped for display.
+            pub fn probability_report() -> droptables::ProbabilityReport<#enum_ident> {
+                droptables::ProbabilityReport::new(#enum_ident::probability_table())
+            }
+
+            /// Draw one variant. The alias sampler is built once on first use
+            /// and shared for the rest of the process.
+            pub fn sample<R: droptables::rand::Rng + ?Sized>(rng: &mut R) -> #enum_ident
+            where
+                #enum_ident: Copy
+            {
+                const VARS: &'static [#enum_ident] = &[
+                    #(#var_idents_ref),*
+                ];
+                static SAMPLER: ::std::sync::OnceLock<droptables::WeightedSampler> =
+                    ::std::sync::OnceLock::new();
+                let sampler = SAMPLER.get_or_init(|| {
+                    #enum_ident::sampler().expect("derive-validated weights always build")
+                });
+                VARS[sampler.sample_index(rng)]
+            }
+
+            /// The variant's name: its identifier, or the `#[name = "..."]`
+            /// override. [`FromStr`](::core::str::FromStr) parses it back.
+            pub const fn as_str(&self) -> &'static str {
+                match self {
+                    #(#name_arms,)*
+                }
+            }
+
+            #roll_fn
+
+            #discriminants
+
+            #cached
         }
     };
 
@@ -249,23 +673,474 @@ pub fn derive_weighted_enum(input: TokenStream) -> TokenStream {
 
 // --- helpers ---
 
-// Parse "A/B" (ints or floats), allow spaces around '/', A>0, B>0
-fn parse_odds_str(s: &str) -> Result<f64, &'static str> {
-    let s = s.trim();
-    let parts: Vec<&str> = s.split('/').collect();
-    if parts.len() != 2 {
-        return Err(r#"expected "A/B" (e.g., "1/100")"#);
+// Enum-level options from `#[weighted_enum(...)]` and `#[extends(...)]`.
+#[derive(Default)]
+struct EnumOpts {
+    selftest: bool,
+    relative: bool,
+    rolls: Option<usize>,
+    // every #[odds] denominator must divide this
+    denominator: Option<u64>,
+    // read `@odds A/B` from variant doc comments
+    doc_odds: bool,
+    extends: Vec<Extends>,
+}
+
+// `#[extends(Other, scale = 0.1)]`: splice `Other`'s entries in at `scale`.
+struct Extends {
+    path: syn::Path,
+    scale: f64,
+    // the wrapper variant's name, from `as = "..."`
+    name: Option<syn::Ident>,
+}
+
+impl Extends {
+    fn parse(attr: &Attribute) -> syn::Result<Self> {
+        let mut path = None;
+        let mut scale = None;
+        let mut name = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("as") {
+                let s: syn::LitStr = meta.value()?.parse()?;
+                name = Some(s.parse::<syn::Ident>()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("scale") {
+                let lit: Lit = meta.value()?.parse()?;
+                let value = match &lit {
+                    Lit::Float(f) => f.base10_parse::<f64>()?,
+                    Lit::Int(i) => i.base10_parse::<f64>()?,
+                    Lit::Str(s) => {
+                        parse_odds_str(&s.value()).map_err(|e| syn::Error::new(s.span(), e))?
+                    }
+                    _ => {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            r#"scale must be a number or "A/B""#,
+                        ));
+                    }
+                };
+                if !(value > 0.0 && value <= 1.0) {
+                    return Err(syn::Error::new(lit.span(), "scale must be in (0, 1]"));
+                }
+                scale = Some(value);
+                Ok(())
+            } else if path.is_none() {
+                path = Some(meta.path.clone());
+                Ok(())
+            } else {
+                Err(meta.error("expected #[extends(OtherEnum, scale = 0.1)]"))
+            }
+        })?;
+        match (path, scale) {
+            (Some(path), Some(scale)) => Ok(Self { path, scale, name }),
+            _ => Err(syn::Error::new(
+                attr.span(),
+                "expected #[extends(OtherEnum, scale = 0.1)]",
+            )),
+        }
     }
-    let a = parse_num(parts[0].trim())?;
-    let b = parse_num(parts[1].trim())?;
-    if a <= 0.0 || b <= 0.0 {
-        return Err("A and B must be positive");
+}
+
+impl EnumOpts {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut opts = Self::default();
+        for attr in attrs {
+            if let Some(name) = VARIANT_ATTRS.iter().find(|n| attr.path().is_ident(n)) {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    format!("#[{name}] goes on a variant, not on the enum"),
+                ));
+            }
+            if attr.path().is_ident("extends") {
+                opts.extends.push(Extends::parse(attr)?);
+                continue;
+            }
+            if !attr.path().is_ident("weighted_enum") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("selftest") {
+                    opts.selftest = true;
+                    Ok(())
+                } else if meta.path.is_ident("relative") {
+                    opts.relative = true;
+                    Ok(())
+                } else if meta.path.is_ident("doc_odds") {
+                    opts.doc_odds = true;
+                    Ok(())
+                } else if meta.path.is_ident("rolls") {
+                    let n: syn::LitInt = meta.value()?.parse()?;
+                    match n.base10_parse::<usize>() {
+                        Ok(r) if r > 0 => {
+                            opts.rolls = Some(r);
+                            Ok(())
+                        }
+                        _ => Err(syn::Error::new(
+                            n.span(),
+                            "rolls must be a positive integer",
+                        )),
+                    }
+                } else if meta.path.is_ident("denominator") {
+                    let n: syn::LitInt = meta.value()?.parse()?;
+                    match n.base10_parse::<u64>() {
+                        Ok(d) if d > 0 => {
+                            opts.denominator = Some(d);
+                            Ok(())
+                        }
+                        _ => Err(syn::Error::new(
+                            n.span(),
+                            "denominator must be a positive integer",
+                        )),
+                    }
+                } else {
+                    Err(meta.error("unknown weighted_enum option"))
+                }
+            })?;
+        }
+        Ok(opts)
     }
-    Ok(a / b)
 }
 
-fn parse_num(s: &str) -> Result<f64, &'static str> {
-    s.parse::<f64>().map_err(|_| "failed to parse number")
+// The integer type from `#[repr(u8)]` and friends, if any.
+fn int_repr(attrs: &[Attribute]) -> Option<syn::Ident> {
+    const INTS: [&str; 12] = [
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ];
+    let mut repr = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        // `#[repr(C, u8)]` and the like; anything unparseable is rustc's to report
+        let _ = attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident()
+                && INTS.contains(&ident.to_string().as_str())
+            {
+                repr = Some(ident.clone());
+            }
+            Ok(())
+        });
+    }
+    repr
+}
+
+// Markdown "Drop rates" table for the generated docs: one row per variant
+// with its resolved probability and "1 in N" odds, then one per extended
+// table.
+fn drop_rates_doc(finalized: &[(syn::Ident, f64)], extends: &[Extends]) -> String {
+    fn percent(p: f64) -> String {
+        if p <= 0.0 {
+            return "0%".to_string();
+        }
+        let pct = p * 100.0;
+        // at least three significant digits for rare drops
+        let prec = (2 - pct.log10().floor() as i32).clamp(2, 12) as usize;
+        format!("{pct:.prec$}%")
+    }
+    fn one_in(p: f64) -> String {
+        if p <= 0.0 {
+            return "never".to_string();
+        }
+        let n = 1.0 / p;
+        if n < 100.0 {
+            return format!("1 in {n:.2}");
+        }
+        let digits = (n.round() as u64).to_string();
+        let mut grouped = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push(',');
+            }
+            grouped.push(c);
+        }
+        format!("1 in {grouped}")
+    }
+
+    let mut doc =
+        String::from(" # Drop rates\n\n | Variant | Probability | Odds |\n |---|---:|---:|\n");
+    for (ident, p) in finalized {
+        doc.push_str(&format!(
+            " | `{ident}` | {} | {} |\n",
+            percent(*p),
+            one_in(*p)
+        ));
+    }
+    for e in extends {
+        let path = &e.path;
+        let path = quote!(#path).to_string().replace(' ', "");
+        doc.push_str(&format!(
+            " | *from* `{path}` | {} | {} |\n",
+            percent(e.scale),
+            one_in(e.scale)
+        ));
+    }
+    doc
+}
+
+const DUPLICATE_PROB: &str = "variant has more than one of #[odds], #[probability], #[bp], #[ppm]";
+
+// Helper attributes that only make sense on variants.
+const VARIANT_ATTRS: [&str; 7] = [
+    "odds",
+    "probability",
+    "rest",
+    "retired",
+    "bp",
+    "ppm",
+    "name",
+];
+
+// `#[probability(...)]` payload: a number, `A / B` of numbers (parenthesized
+// freely), or an "A/B" string.
+fn eval_probability(expr: &syn::Expr) -> syn::Result<f64> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit, .. }) => match lit {
+            Lit::Float(f) => f.base10_parse(),
+            Lit::Int(i) => i.base10_parse(),
+            Lit::Str(s) => parse_odds_str(&s.value()).map_err(|e| syn::Error::new(s.span(), e)),
+            _ => Err(syn::Error::new(lit.span(), "expected a number or \"A/B\"")),
+        },
+        syn::Expr::Binary(syn::ExprBinary {
+            left,
+            op: syn::BinOp::Div(_),
+            right,
+            ..
+        }) => {
+            let (a, b) = (eval_probability(left)?, eval_probability(right)?);
+            if b == 0.0 {
+                return Err(syn::Error::new(right.span(), "division by zero"));
+            }
+            Ok(a / b)
+        }
+        syn::Expr::Paren(p) => eval_probability(&p.expr),
+        syn::Expr::Group(g) => eval_probability(&g.expr),
+        _ => Err(syn::Error::new(
+            expr.span(),
+            "expected #[probability(0.25)], #[probability(1/4)] or #[probability(\"1/4\")]",
+        )),
+    }
+}
+
+// The `#[probability]` forms as exact odds text for `check_denominator`:
+// `"A/B"`, a number literal (`0.25` is exactly 1/4), or `A / B` over number
+// literals. `None` for anything else.
+fn probability_fraction(expr: &syn::Expr) -> Option<String> {
+    let number = |e: &syn::Expr| match e {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: Lit::Int(i), ..
+        }) => Some(i.base10_digits().to_string()),
+        syn::Expr::Lit(syn::ExprLit {
+            lit: Lit::Float(f), ..
+        }) => Some(f.base10_digits().to_string()),
+        _ => None,
+    };
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(s), ..
+        }) => Some(s.value()),
+        syn::Expr::Lit(_) => number(expr),
+        syn::Expr::Binary(syn::ExprBinary {
+            left,
+            op: syn::BinOp::Div(_),
+            right,
+            ..
+        }) => Some(format!("{}/{}", number(left)?, number(right)?)),
+        syn::Expr::Paren(p) => probability_fraction(&p.expr),
+        syn::Expr::Group(g) => probability_fraction(&g.expr),
+        _ => None,
+    }
+}
+
+// With `#[extends(...)]`: a `{Enum}Drop` wrapper with one variant per source
+// table, plus `{Enum}::extended_droptable()` over all of them.
+fn expand_extends(
+    vis: &syn::Visibility,
+    enum_ident: &syn::Ident,
+    extends: &[Extends],
+) -> proc_macro2::TokenStream {
+    if extends.is_empty() {
+        return quote! {};
+    }
+    let wrapper = syn::Ident::new(&format!("{enum_ident}Drop"), enum_ident.span());
+    // `Rare` for `Rare`, `TablesRare` for `tables::Rare` (skipping `crate`,
+    // `self` and `super`), so same-named tables from different modules
+    // don't collide
+    let names: Vec<syn::Ident> = extends
+        .iter()
+        .map(|e| {
+            e.name.clone().unwrap_or_else(|| {
+                let joined: String = e
+                    .path
+                    .segments
+                    .iter()
+                    .filter(|s| !["crate", "self", "super"].iter().any(|k| s.ident == k))
+                    .map(|s| {
+                        let s = s.ident.to_string();
+                        let s = s.trim_start_matches("r#");
+                        s.split('_')
+                            .flat_map(|part| {
+                                let mut chars = part.chars();
+                                chars
+                                    .next()
+                                    .map(|c| c.to_ascii_uppercase())
+                                    .into_iter()
+                                    .chain(chars)
+                            })
+                            .collect::<String>()
+                    })
+                    .collect();
+                syn::Ident::new(&joined, e.path.span())
+            })
+        })
+        .collect();
+    for (i, name) in names.iter().enumerate() {
+        if name == enum_ident || names[..i].contains(name) {
+            return syn::Error::new(
+                name.span(),
+                format!("`{wrapper}::{name}` is taken; name this one with #[extends(..., as = \"Name\")]"),
+            )
+            .to_compile_error();
+        }
+    }
+    let paths: Vec<&syn::Path> = extends.iter().map(|e| &e.path).collect();
+    let scales: Vec<f32> = extends.iter().map(|e| e.scale as f32).collect();
+    let doc = format!(" A drop from [`{enum_ident}`] or from one of the tables it extends.");
+    quote! {
+        #[doc = #doc]
+        #[derive(Copy, Clone, Debug)]
+        #vis enum #wrapper {
+            #enum_ident(#enum_ident),
+            #(#names(#paths)),*
+        }
+
+        impl #enum_ident {
+            /// Table over this enum's own variants plus every `#[extends]`
+            /// table, each scaled by its factor.
+            pub fn extended_droptable() -> ::core::result::Result<
+                droptables::DropTable<#wrapper>,
+                droptables::ProbError
+            >
+            where
+                #enum_ident: Copy
+            {
+                let mut pairs = ::std::vec::Vec::new();
+                for &(v, w) in <#enum_ident as droptables::WeightedEnum>::ENTRIES {
+                    pairs.push((#wrapper::#enum_ident(v), w));
+                }
+                #(
+                    for &(v, w) in <#paths as droptables::WeightedEnum>::ENTRIES {
+                        pairs.push((#wrapper::#names(v), w * #scales));
+                    }
+                )*
+                droptables::DropTable::from_pairs(pairs)
+            }
+        }
+    }
+}
+
+// Integer-scaled probability attributes: (name, denominator).
+fn scaled_attr(meta: &Meta) -> Option<(&'static str, f64)> {
+    if meta.path().is_ident("bp") {
+        Some(("bp", 10_000.0))
+    } else if meta.path().is_ident("ppm") {
+        Some(("ppm", 1_000_000.0))
+    } else {
+        None
+    }
+}
+
+// `droptable_cached()`, emitted only with the `cache` feature: builds the
+// static table on first use and hands out the same `&'static` afterwards.
+fn cached_droptable(
+    enum_ident: &syn::Ident,
+    sampler: proc_macro2::TokenStream,
+    what: &str,
+) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "cache") {
+        return quote! {};
+    }
+    let doc = format!(
+        " Lazily built, process-wide [`droptables::StaticDropTable`] backed by {what}.\n\n \
+         The same table as `droptable_unchecked()`."
+    );
+    quote! {
+        #[doc = #doc]
+        pub fn droptable_cached() -> &'static droptables::StaticDropTable<#sampler, #enum_ident>
+        where
+            #enum_ident: Copy + Sync + 'static
+        {
+            #enum_ident::droptable_unchecked()
+        }
+    }
+}
+
+// The grammar of `Odds::parse`: "1/128", "1 in 128", "0.78%" or "0.0078".
+// Callers check the result is positive.
+fn parse_odds_str(s: &str) -> Result<f64, String> {
+    let (num, den) = parse_odds(s).map_err(|e| e.to_string())?;
+    Ok(num as f64 / den as f64)
+}
+
+// `#[weighted_enum(doc_odds)]`: the probability from an `@odds A/B` line
+// in the variant's doc comment, checked like `#[odds]`.
+fn doc_odds(
+    attrs: &[Attribute],
+    denominator: Option<u64>,
+) -> syn::Result<Option<(f64, proc_macro2::Span)>> {
+    let mut found = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("doc")) {
+        let Meta::NameValue(MetaNameValue {
+            value: syn::Expr::Lit(syn::ExprLit {
+                lit: Lit::Str(s), ..
+            }),
+            ..
+        }) = &attr.meta
+        else {
+            continue;
+        };
+        let text = s.value();
+        for line in text.lines() {
+            let Some((_, odds)) = line.split_once("@odds") else {
+                continue;
+            };
+            let odds = odds.trim();
+            let p = parse_odds_str(odds).map_err(|e| syn::Error::new(s.span(), e))?;
+            if p <= 0.0 || !p.is_finite() {
+                return Err(syn::Error::new(
+                    s.span(),
+                    "odds must produce a positive, finite probability",
+                ));
+            }
+            if let Some(base) = denominator {
+                check_denominator(odds, base).map_err(|e| syn::Error::new(s.span(), e))?;
+            }
+            if found.replace((p, s.span())).is_some() {
+                return Err(syn::Error::new(s.span(), "more than one @odds tag"));
+            }
+        }
+    }
+    Ok(found)
+}
+
+// `#[weighted_enum(denominator = N)]`: already-parsed odds, in lowest
+// terms, must have a denominator dividing `N`.
+fn check_denominator(s: &str, base: u64) -> Result<(), String> {
+    let (_, den) = parse_odds(s).map_err(|e| e.to_string())?;
+    if u128::from(base) % den == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "odds {s:?} have denominator {den} in lowest terms, which doesn't divide the enum's denominator {base}"
+        ))
+    }
+}
+
+/// Compile-time check that a drop-rate config file agrees with a
+/// `WeightedEnum`; see `droptables::assert_table_matches!`.
+#[proc_macro]
+pub fn assert_table_matches(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as config::Args);
+    config::expand(args)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
 #[proc_macro_derive(UniformEnum)]
@@ -296,6 +1171,34 @@ pub fn derive_uniform_enum(input: TokenStream) -> TokenStream {
     }
 
     let vars = idents.iter().map(|ident| quote! { #enum_ident::#ident });
+    // an enum without variants has no table to build, so only the
+    // fallible constructors
+    let (unchecked, cached) = if idents.is_empty() {
+        (quote! {}, quote! {})
+    } else {
+        let unchecked = quote! {
+            /// Infallible [`droptable`](Self::droptable), for enums with at
+            /// least one variant. Built on the first call; later calls
+            /// return the same table.
+            pub fn droptable_unchecked() -> &'static droptables::StaticDropTable<droptables::UniformSampler, #enum_ident>
+            where
+                #enum_ident: Copy + Sync + 'static
+            {
+                static TABLE: ::std::sync::OnceLock<
+                    droptables::StaticDropTable<droptables::UniformSampler, #enum_ident>
+                > = ::std::sync::OnceLock::new();
+                TABLE.get_or_init(|| {
+                    #enum_ident::droptable().expect("derived enums with variants always build")
+                })
+            }
+        };
+        let cached = cached_droptable(
+            enum_ident,
+            quote! { droptables::UniformSampler },
+            "a uniform sampler",
+        );
+        (unchecked, cached)
+    };
 
     let expanded = quote! {
         impl droptables::UniformEnum for #enum_ident {
@@ -317,6 +1220,8 @@ pub fn derive_uniform_enum(input: TokenStream) -> TokenStream {
                 Ok(droptables::StaticDropTable::new(sampler, <#enum_ident as droptables::UniformEnum>::VARS))
             }
 
+            #unchecked
+
             /// Owning Vec-backed uniform table (allocates).
             pub fn droptable_stateful() -> ::core::result::Result<
                 droptables::UniformTable<#enum_ident>,
@@ -327,6 +1232,17 @@ pub fn derive_uniform_enum(input: TokenStream) -> TokenStream {
             {
                 droptables::UniformTable::from_items(<#enum_ident as droptables::UniformEnum>::VARS.iter().cloned())
             }
+
+            /// Draw one variant uniformly. No table is built.
+            pub fn sample<R: droptables::rand::Rng + ?Sized>(rng: &mut R) -> #enum_ident
+            where
+                #enum_ident: Copy
+            {
+                const VARS: &'static [#enum_ident] = <#enum_ident as droptables::UniformEnum>::VARS;
+                VARS[droptables::rand::Rng::random_range(rng, 0..VARS.len())]
+            }
+
+            #cached
         }
     };
 
@@ -0,0 +1,213 @@
+//! The odds grammar shared by `droptables` (`Odds::parse` and the text
+//! loaders) and the `WeightedEnum` derive in `droptables_macros`, so every
+//! input surface accepts the same forms.
+//!
+//! ```text
+//! odds    = ratio | one-in | percent | decimal
+//! ratio   = decimal "/" decimal           "1/128", "3 / 1000"
+//! one-in  = decimal "in" decimal          "1 in 128"
+//! percent = decimal "%"                   "0.78%"
+//! decimal = digits ["." digits] | "." digits
+//! ```
+//!
+//! Numbers are parsed exactly, as decimal fractions, so "0.78%" is exactly
+//! 39/5000. Whitespace around tokens is ignored; signs, exponents, `inf`
+//! and `NaN` are rejected.
+//!
+//! [`toml_line`] splits one line of the flat TOML subset drop-rate configs
+//! use, for the same reason: the library's loaders and
+//! `assert_table_matches!` read the files alike.
+
+use std::fmt;
+
+/// Most digits in one number; keeps every product within `u128`.
+pub const MAX_DIGITS: usize = 18;
+
+/// Why a string is not valid odds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OddsParseError {
+    /// Nothing but whitespace.
+    Empty,
+    /// A number in the string is not a plain non-negative decimal; holds
+    /// the offending text.
+    BadNumber(String),
+    /// A number has more than [`MAX_DIGITS`] digits.
+    TooManyDigits(String),
+    /// The `B` of `A/B` or `A in B` is zero.
+    ZeroDenominator,
+    /// The odds are above 1, e.g. "3/2" or "150%".
+    AboveOne,
+}
+
+impl fmt::Display for OddsParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OddsParseError::Empty => write!(
+                f,
+                r#"expected odds like "1/128", "1 in 128", "0.78%" or "0.0078", found nothing"#
+            ),
+            OddsParseError::BadNumber(s) => write!(
+                f,
+                r#"{s:?} is not a non-negative decimal number (odds are written "1/128", "1 in 128", "0.78%" or "0.0078")"#
+            ),
+            OddsParseError::TooManyDigits(s) => {
+                write!(f, "{s:?} has more than {MAX_DIGITS} digits")
+            }
+            OddsParseError::ZeroDenominator => {
+                write!(f, "the denominator of the odds is zero")
+            }
+            OddsParseError::AboveOne => write!(f, "odds are above 1 (100%)"),
+        }
+    }
+}
+
+impl std::error::Error for OddsParseError {}
+
+/// Parse `s` into a fraction `(num, den)` in lowest terms, `num <= den`.
+pub fn parse_odds(s: &str) -> Result<(u128, u128), OddsParseError> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(OddsParseError::Empty);
+    }
+    let (num, den) = if let Some(pct) = s.strip_suffix('%') {
+        let (n, d) = decimal(pct)?;
+        (n, d * 100)
+    } else if let Some((a, b)) = s.split_once('/') {
+        ratio(a, b)?
+    } else if let Some((a, b)) = split_in(s) {
+        ratio(a, b)?
+    } else {
+        decimal(s)?
+    };
+    if num > den {
+        return Err(OddsParseError::AboveOne);
+    }
+    let g = gcd(num, den).max(1);
+    Ok((num / g, den / g))
+}
+
+/// `"A in B"`, with `in` in any case and any whitespace around it.
+fn split_in(s: &str) -> Option<(&str, &str)> {
+    let mut words = s.split_whitespace();
+    let (a, word, b) = (words.next()?, words.next()?, words.next()?);
+    if words.next().is_some() || !word.eq_ignore_ascii_case("in") {
+        return None;
+    }
+    Some((a, b))
+}
+
+fn ratio(a: &str, b: &str) -> Result<(u128, u128), OddsParseError> {
+    let (an, ad) = decimal(a)?;
+    let (bn, bd) = decimal(b)?;
+    if bn == 0 {
+        return Err(OddsParseError::ZeroDenominator);
+    }
+    // (an / ad) / (bn / bd)
+    Ok((an * bd, ad * bn))
+}
+
+/// An exact non-negative decimal as `(digits, 10^fraction_len)`.
+fn decimal(s: &str) -> Result<(u128, u128), OddsParseError> {
+    let s = s.trim();
+    let bad = || OddsParseError::BadNumber(s.to_owned());
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+    if int.is_empty() && frac.is_empty() {
+        return Err(bad());
+    }
+    if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+        return Err(bad());
+    }
+    if int.len() + frac.len() > MAX_DIGITS {
+        return Err(OddsParseError::TooManyDigits(s.to_owned()));
+    }
+    let digits = int.bytes().chain(frac.bytes());
+    let num = digits.fold(0u128, |n, b| n * 10 + u128::from(b - b'0'));
+    Ok((num, 10u128.pow(frac.len() as u32)))
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// One line of the flat TOML subset drop-rate configs use: `name = value`
+/// lines, where the name is bare or quoted, under optional `[table]`
+/// headers. `#` starts a comment unless it's inside a quoted string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TomlLine<'a> {
+    /// Nothing but whitespace and comments.
+    Blank,
+    /// `[name]`, with the name unquoted.
+    Header(&'a str),
+    /// `name = value`, with the name unquoted and the value trimmed but
+    /// still quoted if it was.
+    Entry { name: &'a str, value: &'a str },
+}
+
+/// Why a line is not valid flat TOML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TomlLineError {
+    /// A `[` with no closing `]`.
+    UnclosedHeader,
+    /// Neither a header nor `name = value`.
+    MissingEquals,
+    /// The name before `=` is empty.
+    EmptyName,
+}
+
+impl fmt::Display for TomlLineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TomlLineError::UnclosedHeader => write!(f, "unclosed table header"),
+            TomlLineError::MissingEquals => write!(f, "expected `name = value`"),
+            TomlLineError::EmptyName => write!(f, "empty name"),
+        }
+    }
+}
+
+impl std::error::Error for TomlLineError {}
+
+/// Split one line of flat TOML; see [`TomlLine`].
+pub fn toml_line(line: &str) -> Result<TomlLine<'_>, TomlLineError> {
+    let src = strip_comment(line).trim();
+    if src.is_empty() {
+        return Ok(TomlLine::Blank);
+    }
+    if let Some(header) = src.strip_prefix('[') {
+        let header = header
+            .strip_suffix(']')
+            .ok_or(TomlLineError::UnclosedHeader)?;
+        return Ok(TomlLine::Header(
+            unquote(header.trim()).unwrap_or(header.trim()),
+        ));
+    }
+    let (key, value) = src.split_once('=').ok_or(TomlLineError::MissingEquals)?;
+    let name = unquote(key.trim()).unwrap_or(key.trim());
+    if name.is_empty() {
+        return Err(TomlLineError::EmptyName);
+    }
+    Ok(TomlLine::Entry {
+        name,
+        value: value.trim(),
+    })
+}
+
+/// The inside of a `"..."` string; `None` if `s` isn't quoted.
+pub fn unquote(s: &str) -> Option<&str> {
+    s.strip_prefix('"').and_then(|s| s.strip_suffix('"'))
+}
+
+/// Drop a `#` comment, unless the `#` is inside a quoted string.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
@@ -310,7 +310,7 @@ fn sample_unique_stat_types<R: Rng>(
         if (seen & stat_bit) != 0 {
             continue;
         }
-        seen = seen | stat_bit;
+        seen |= stat_bit;
         item.stat_storage[count as usize] = roll_stat(rng, stat_type, item_rarity, tables);
         count += 1;
     }
@@ -373,7 +373,7 @@ fn roll_item<R: Rng>(rng: &mut R, t: &Tables, item: &mut Item) {
     // Stat slots and unique stat kinds
     let bonus = rarity_slot_bonus(item_rarity);
     let gem_slots = (base_gem_slots.saturating_add(bonus)).min(6);
-    let stat_slots = (base_stat_slots.saturating_add(bonus)).min(6).max(1);
+    let stat_slots = (base_stat_slots.saturating_add(bonus)).clamp(1, 6);
 
     item.rarity = item_rarity;
     item.gem_slots = gem_slots;
@@ -388,7 +388,7 @@ fn roll_item<R: Rng>(rng: &mut R, t: &Tables, item: &mut Item) {
     }
 
     // Stat slots and unique stat kinds
-    sample_unique_stat_types(rng, stat_slots, &t, item, item_rarity);
+    sample_unique_stat_types(rng, stat_slots, t, item, item_rarity);
 }
 
 // -------------------- demo --------------------
@@ -41,14 +41,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("Rarity:");
     let mut v: Vec<_> = rarity_hist.into_iter().collect();
-    v.sort_by(|a, b| b.1.cmp(&a.1));
+    v.sort_by_key(|b| std::cmp::Reverse(b.1));
     for (k, c) in v {
         println!("{c:>6} {k:?}");
     }
 
     println!("\nLegendary Loot (only when legendary):");
     let mut v: Vec<_> = leg_hist.into_iter().collect();
-    v.sort_by(|a, b| b.1.cmp(&a.1));
+    v.sort_by_key(|b| std::cmp::Reverse(b.1));
     for (k, c) in v {
         println!("{c:>6} {k:?}");
     }
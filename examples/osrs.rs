@@ -242,7 +242,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Pretty print totals (highest first)
     let mut items: Vec<(String, u64)> = hist.into_iter().collect();
-    items.sort_by(|a, b| b.1.cmp(&a.1));
+    items.sort_by_key(|b| std::cmp::Reverse(b.1));
 
     println!("General Graardor (Bandos) — simulated drops:");
     for (item, count) in items {
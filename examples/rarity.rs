@@ -1,5 +1,4 @@
-use droptables::{DropTable, WeightedEnum};
-use std::collections::HashMap;
+use droptables::{DropTable, WeightedEnum, assert_table_matches, simulate};
 
 #[derive(Copy, Eq, PartialEq, Clone, Debug, Hash, WeightedEnum)]
 enum Rarity {
@@ -13,10 +12,12 @@ enum Rarity {
     Common,
 }
 
+// fails the build if rarity.toml and the attributes above drift apart
+assert_table_matches!("examples/rarity.toml", Rarity);
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Build straight from the enum:
     let table = Rarity::droptable()?; // uses the macro-provided ENTRIES
-    let mut hist: HashMap<Rarity, u64> = HashMap::default();
 
     // Or, if you want to mix arbitrary items with weights:
     let _custom: DropTable<&'static str> =
@@ -24,20 +25,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Sample:
     let mut rng = rand::rng();
-    for _ in 0..2000000 {
-        hist.entry(*table.sample(&mut rng))
-            .and_modify(|acc| {
-                *acc += 1;
-            })
-            .or_insert(1);
-    }
-
-    let mut values: Vec<(Rarity, u64)> = hist.into_iter().collect();
-    values.sort_by(|(_, ca), (_, cb)| cb.cmp(ca));
+    println!("one drop: {:?}", table.sample(&mut rng));
 
-    for (rarity, count) in values {
-        println!("{count: >5} {rarity:?}");
-    }
+    // Or check a couple million draws against the declared odds:
+    print!("{}", simulate::<Rarity>(2_000_000, 42)?);
 
     Ok(())
 }
@@ -0,0 +1,33 @@
+/* C interface to droptables (build with `--features capi`). */
+#ifndef DROPTABLES_H
+#define DROPTABLES_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Opaque alias table over indices 0..n. */
+typedef struct DropTableHandle DropTableHandle;
+
+/* Build from n non-negative weights. Returns NULL on invalid input
+ * (NULL pointer, n == 0, a negative weight, or a zero/non-finite sum). */
+DropTableHandle *droptable_new(const float *weights, size_t n);
+
+/* Sample an index in 0..n. Deterministic for a given (table, seed);
+ * pass a fresh seed per draw. Returns SIZE_MAX for a NULL handle. */
+size_t droptable_sample(const DropTableHandle *handle, uint64_t seed);
+
+/* Number of entries, or 0 for a NULL handle. */
+size_t droptable_len(const DropTableHandle *handle);
+
+/* Release a table; NULL is ignored. */
+void droptable_free(DropTableHandle *handle);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* DROPTABLES_H */
@@ -0,0 +1,225 @@
+use rand::Rng;
+
+use crate::IndexSampler;
+use crate::error::ProbError;
+
+/// One alias bucket, as in [`Bucket`](crate::Bucket): keep the index when
+/// the top 53 bits of a draw are below `threshold`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ArrayBucket {
+    threshold: u64,
+    alias: usize,
+}
+
+const ONE: u64 = 1 << 53;
+
+/// A drop table of exactly `N` entries held in arrays: no heap, no
+/// allocation, nothing to free, so it can live in a `static` and be sampled
+/// from an interrupt handler or a real-time thread.
+///
+/// [`new`](Self::new) is a `const fn` and runs Vose's method with
+/// stack-allocated worklists, so a table can be checked and built at compile
+/// time:
+///
+/// ```
+/// use droptables::DropTableN;
+///
+/// static LOOT: DropTableN<&str, 3> =
+///     match DropTableN::new(["coins", "gem", "relic"], [90.0, 9.0, 1.0]) {
+///         Ok(t) => t,
+///         Err(_) => panic!("bad loot weights"),
+///     };
+///
+/// let drop = LOOT.sample(&mut rand::rng());
+/// assert!(LOOT.items().contains(drop));
+/// ```
+///
+/// Sampling matches [`WeightedSampler`](crate::WeightedSampler): one
+/// uniform index, one 53-bit comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DropTableN<T, const N: usize> {
+    items: [T; N],
+    buckets: [ArrayBucket; N],
+}
+
+/// Vose's pairing over `weights`, entirely in `const`-evaluable code.
+const fn build_buckets<const N: usize>(weights: &[f32; N]) -> Result<[ArrayBucket; N], ProbError> {
+    if N == 0 {
+        return Err(ProbError::Empty);
+    }
+    let mut sum = 0.0f64;
+    let mut i = 0;
+    while i < N {
+        let w = weights[i];
+        if w < 0.0 {
+            return Err(ProbError::Negative { index: i, value: w });
+        }
+        sum += w as f64;
+        i += 1;
+    }
+    if !sum.is_finite() || sum == 0.0 {
+        return Err(ProbError::ZeroSum);
+    }
+
+    let mut scaled = [0.0f64; N];
+    let (mut small, mut large) = ([0usize; N], [0usize; N]);
+    let (mut ns, mut nl) = (0, 0);
+    let mut i = 0;
+    while i < N {
+        scaled[i] = weights[i] as f64 * N as f64 / sum;
+        if scaled[i] < 1.0 {
+            small[ns] = i;
+            ns += 1;
+        } else {
+            large[nl] = i;
+            nl += 1;
+        }
+        i += 1;
+    }
+
+    let mut buckets = [ArrayBucket {
+        threshold: ONE,
+        alias: 0,
+    }; N];
+    let mut i = 0;
+    while i < N {
+        buckets[i].alias = i;
+        i += 1;
+    }
+    while ns > 0 && nl > 0 {
+        ns -= 1;
+        nl -= 1;
+        let (s, l) = (small[ns], large[nl]);
+        // scaled[s] is in [0, 1); the cast truncates after rounding
+        buckets[s] = ArrayBucket {
+            threshold: (scaled[s] * ONE as f64 + 0.5) as u64,
+            alias: l,
+        };
+        scaled[l] = (scaled[l] + scaled[s]) - 1.0;
+        if scaled[l] < 1.0 - 1e-15 {
+            small[ns] = l;
+            ns += 1;
+        } else {
+            large[nl] = l;
+            nl += 1;
+        }
+    }
+    // leftovers keep their default `threshold: ONE`, aliasing themselves
+    Ok(buckets)
+}
+
+impl<T, const N: usize> DropTableN<T, N> {
+    /// Build from `N` items and their weights, in `const` contexts too.
+    ///
+    /// # Errors
+    /// * [`ProbError::Empty`] if `N == 0`
+    /// * [`ProbError::Negative`] if any weight is negative
+    /// * [`ProbError::ZeroSum`] if the sum is zero or not finite (`NaN`/∞)
+    pub const fn new(items: [T; N], weights: [f32; N]) -> Result<Self, ProbError>
+    where
+        T: Copy,
+    {
+        match build_buckets(&weights) {
+            Ok(buckets) => Ok(Self { items, buckets }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// [`new`](Self::new) for items that aren't `Copy` (not `const`).
+    ///
+    /// # Errors
+    /// As [`new`](Self::new).
+    pub fn from_arrays(items: [T; N], weights: [f32; N]) -> Result<Self, ProbError> {
+        build_buckets(&weights).map(|buckets| Self { items, buckets })
+    }
+
+    /// Draw an index in O(1), without allocating.
+    #[inline]
+    pub fn sample_index<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let i = rng.random_range(0..N);
+        let u = rng.next_u64() >> 11;
+        let b = self.buckets[i];
+        if u < b.threshold { i } else { b.alias }
+    }
+
+    #[inline]
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        &self.items[self.sample_index(rng)]
+    }
+
+    #[inline]
+    pub fn sample_owned<R: Rng + ?Sized>(&self, rng: &mut R) -> T
+    where
+        T: Copy,
+    {
+        self.items[self.sample_index(rng)]
+    }
+
+    pub const fn items(&self) -> &[T; N] {
+        &self.items
+    }
+
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+}
+
+impl<T, const N: usize> IndexSampler for DropTableN<T, N> {
+    fn len(&self) -> usize {
+        N
+    }
+
+    fn sample_index_dyn(&self, rng: &mut dyn rand::RngCore) -> usize {
+        DropTableN::sample_index(self, rng)
+    }
+
+    #[inline]
+    fn sample_index<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        DropTableN::sample_index(self, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WeightedSampler;
+
+    const TIERS: DropTableN<u8, 4> = match DropTableN::new([1, 2, 3, 4], [6.0, 3.0, 0.0, 1.0]) {
+        Ok(t) => t,
+        Err(_) => panic!(),
+    };
+
+    #[test]
+    fn const_table_matches_the_heap_sampler() {
+        let heap = WeightedSampler::new(&[6.0, 3.0, 0.0, 1.0]).unwrap();
+        for (i, (b, h)) in TIERS.buckets.iter().zip(heap.as_buckets()).enumerate() {
+            // a self-aliased bucket always keeps `i`, whatever its threshold
+            let keep = |t: u64, alias: usize| if alias == i { ONE } else { t };
+            assert_eq!(keep(b.threshold, b.alias), keep(h.threshold(), h.alias()));
+            assert_eq!(b.alias, h.alias());
+        }
+        let mut rng = rand::rng();
+        let mut counts = [0usize; 5];
+        for _ in 0..20_000 {
+            counts[usize::from(TIERS.sample_owned(&mut rng))] += 1;
+        }
+        assert_eq!(counts[3], 0);
+        assert!(
+            (counts[1] as f64 / 20_000.0 - 0.6).abs() < 0.02,
+            "{counts:?}"
+        );
+
+        assert_eq!(
+            DropTableN::<u8, 0>::new([], []).unwrap_err(),
+            ProbError::Empty
+        );
+        assert!(matches!(
+            DropTableN::from_arrays([String::new()], [-1.0]),
+            Err(ProbError::Negative { index: 0, .. })
+        ));
+    }
+}
@@ -0,0 +1,228 @@
+//! Helpers for game AI: behaviour-tree nodes that pick an action by weight.
+
+use rand::Rng;
+
+use crate::WeightedSampler;
+use crate::error::ProbError;
+
+/// A weighted selector node: each tick, score every action with a closure
+/// and run one drawn in proportion to its score.
+///
+/// Building an alias table is O(n) and allocates, which adds up when every
+/// agent rebuilds one every tick. The selector keeps the table from the
+/// last rebuild and reuses it while no score has moved by more than
+/// `tolerance` × the cached total, so agents whose situation is steady
+/// sample in O(1) without allocating. The cost is that a cached draw uses
+/// the scores from the last rebuild, off by at most that tolerance. A score
+/// that becomes zero or negative, or stops being zero, always rebuilds, so
+/// an action scored out is never drawn and one scored in is never skipped.
+///
+/// ```
+/// use droptables::behavior::WeightedSelector;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Action { Attack, Flee, Heal }
+///
+/// let mut node = WeightedSelector::new(vec![Action::Attack, Action::Flee, Action::Heal]).unwrap();
+/// let (health, enemies) = (0.2f32, 3.0f32);
+/// let action = node
+///     .tick(&mut rand::rng(), |a| match a {
+///         Action::Attack => health * 2.0,
+///         Action::Flee => enemies * (1.0 - health),
+///         Action::Heal => 0.0,
+///     })
+///     .unwrap();
+/// assert_ne!(*action, Action::Heal);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WeightedSelector<A> {
+    actions: Vec<A>,
+    /// Scores the cached sampler was built from.
+    weights: Vec<f32>,
+    total: f64,
+    sampler: Option<WeightedSampler>,
+    scratch: Vec<f32>,
+    tolerance: f32,
+    rebuilds: u64,
+}
+
+impl<A> WeightedSelector<A> {
+    /// Default for [`with_tolerance`](Self::with_tolerance): rebuild once a
+    /// score moves by more than 1% of the total.
+    pub const DEFAULT_TOLERANCE: f32 = 0.01;
+
+    /// A selector over `actions`; the alias table is built on the first
+    /// [`tick`](Self::tick).
+    ///
+    /// # Errors
+    /// [`ProbError::Empty`] if there are no actions.
+    pub fn new(actions: Vec<A>) -> Result<Self, ProbError> {
+        if actions.is_empty() {
+            return Err(ProbError::Empty);
+        }
+        Ok(Self {
+            weights: Vec::with_capacity(actions.len()),
+            scratch: Vec::with_capacity(actions.len()),
+            actions,
+            total: 0.0,
+            sampler: None,
+            tolerance: Self::DEFAULT_TOLERANCE,
+            rebuilds: 0,
+        })
+    }
+
+    /// How far, as a fraction of the cached total, any score may drift
+    /// before the table is rebuilt. `0.0` rebuilds on every change.
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Score every action with `weight` and draw one, rebuilding the alias
+    /// table only if the scores drifted past the tolerance.
+    ///
+    /// # Errors
+    /// Whatever [`WeightedSampler::new`] reports for the scores (e.g.
+    /// [`ProbError::ZeroSum`] if every action scores zero). The previous
+    /// table is kept, so the next tick compares against it.
+    pub fn tick<R, F>(&mut self, rng: &mut R, weight: F) -> Result<&A, ProbError>
+    where
+        R: Rng + ?Sized,
+        F: FnMut(&A) -> f32,
+    {
+        self.tick_index(rng, weight).map(|i| &self.actions[i])
+    }
+
+    /// [`tick`](Self::tick), returning the action's index.
+    ///
+    /// # Errors
+    /// As [`tick`](Self::tick).
+    pub fn tick_index<R, F>(&mut self, rng: &mut R, weight: F) -> Result<usize, ProbError>
+    where
+        R: Rng + ?Sized,
+        F: FnMut(&A) -> f32,
+    {
+        self.scratch.clear();
+        self.scratch.extend(self.actions.iter().map(weight));
+        if self.is_stale() {
+            let sampler = WeightedSampler::new(&self.scratch)?;
+            self.sampler = Some(sampler);
+            std::mem::swap(&mut self.weights, &mut self.scratch);
+            self.total = self.weights.iter().map(|&w| f64::from(w)).sum();
+            self.rebuilds += 1;
+        }
+        let sampler = self.sampler.as_ref().expect("built above");
+        Ok(sampler.sample_index(rng))
+    }
+
+    fn is_stale(&self) -> bool {
+        if self.sampler.is_none() {
+            return true;
+        }
+        let limit = f64::from(self.tolerance) * self.total;
+        self.weights.iter().zip(&self.scratch).any(|(&old, &new)| {
+            new.is_nan()
+                || new < 0.0
+                || (old > 0.0) != (new > 0.0)
+                || f64::from((new - old).abs()) > limit
+        })
+    }
+
+    /// Drop the cached table so the next tick rebuilds it.
+    pub fn invalidate(&mut self) {
+        self.sampler = None;
+    }
+
+    /// Scores the cached table was built from (empty before the first tick).
+    pub fn cached_weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /// Number of alias-table builds so far, for profiling the tolerance.
+    pub fn rebuilds(&self) -> u64 {
+        self.rebuilds
+    }
+
+    /// The actions, in the order passed to [`new`](Self::new); indices from
+    /// [`tick_index`](Self::tick_index) point into this.
+    pub fn actions(&self) -> &[A] {
+        &self.actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn rebuilds_only_past_the_tolerance() {
+        let mut node = WeightedSelector::new(vec!["attack", "flee", "heal"])
+            .unwrap()
+            .with_tolerance(0.05);
+        let mut rng = StdRng::seed_from_u64(1396);
+        let mut scores = [1.0f32, 1.0, 0.0];
+
+        for step in 0..10 {
+            // total 2.0, so drifts under 0.1 reuse the table
+            scores[0] = 1.0 + step as f32 * 0.005;
+            assert_ne!(*node.tick(&mut rng, |a| scores[pos(a)]).unwrap(), "heal");
+        }
+        assert_eq!(node.rebuilds(), 1);
+
+        scores = [0.0, 0.0, 1.0];
+        assert_eq!(*node.tick(&mut rng, |a| scores[pos(a)]).unwrap(), "heal");
+        assert_eq!(node.rebuilds(), 2);
+        assert_eq!(node.cached_weights(), scores);
+
+        assert!(matches!(
+            node.tick(&mut rng, |_| 0.0),
+            Err(ProbError::ZeroSum)
+        ));
+        assert_eq!(node.cached_weights(), scores);
+        node.invalidate();
+        node.tick(&mut rng, |a| scores[pos(a)]).unwrap();
+        assert_eq!(node.rebuilds(), 3);
+
+        assert!(matches!(
+            WeightedSelector::<u8>::new(Vec::new()),
+            Err(ProbError::Empty)
+        ));
+    }
+
+    #[test]
+    fn crossing_zero_always_rebuilds() {
+        let mut node = WeightedSelector::new(vec!["attack", "flee", "heal"])
+            .unwrap()
+            .with_tolerance(0.5);
+        let mut rng = StdRng::seed_from_u64(1396);
+        let mut scores = [100.0f32, 100.0, 0.0];
+        node.tick(&mut rng, |a| scores[pos(a)]).unwrap();
+
+        // well inside the tolerance, but heal can now drop
+        scores[2] = 0.001;
+        node.tick(&mut rng, |a| scores[pos(a)]).unwrap();
+        assert_eq!(node.rebuilds(), 2);
+
+        // and flee no longer can
+        scores[1] = 0.0;
+        scores[0] = 100.001;
+        for _ in 0..1000 {
+            assert_ne!(*node.tick(&mut rng, |a| scores[pos(a)]).unwrap(), "flee");
+        }
+        assert_eq!(node.rebuilds(), 3);
+
+        scores[2] = -0.001;
+        assert!(matches!(
+            node.tick(&mut rng, |a| scores[pos(a)]),
+            Err(ProbError::Negative { .. })
+        ));
+    }
+
+    fn pos(action: &&str) -> usize {
+        ["attack", "flee", "heal"]
+            .iter()
+            .position(|a| a == action)
+            .unwrap()
+    }
+}
@@ -0,0 +1,48 @@
+//! Reference samplers used by the comparative benchmarks (feature `bench-support`).
+//!
+//! These are the baselines the alias method is measured against. They
+//! implement [`IndexSampler`] so benchmarks and regression checks can drive
+//! every implementation through the same interface.
+
+use rand::distr::Distribution;
+use rand::distr::weighted::WeightedIndex;
+use rand::{Rng, RngCore};
+
+use crate::IndexSampler;
+use crate::error::ProbError;
+use crate::walker::validate_weights;
+
+pub use crate::cdf::CdfSampler;
+
+/// Thin wrapper over `rand::distr::weighted::WeightedIndex`.
+#[derive(Debug, Clone)]
+pub struct RandWeightedIndex {
+    inner: WeightedIndex<f32>,
+    n: usize,
+}
+
+impl RandWeightedIndex {
+    pub fn new(weights: &[f32]) -> Result<Self, ProbError> {
+        validate_weights(weights)?;
+        let inner = WeightedIndex::new(weights).map_err(|_| ProbError::ZeroSum)?;
+        Ok(Self {
+            inner,
+            n: weights.len(),
+        })
+    }
+}
+
+impl IndexSampler for RandWeightedIndex {
+    #[inline]
+    fn len(&self) -> usize {
+        self.n
+    }
+    #[inline]
+    fn sample_index<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        self.inner.sample(rng)
+    }
+    #[inline]
+    fn sample_index_dyn(&self, rng: &mut dyn RngCore) -> usize {
+        self.sample_index(rng)
+    }
+}
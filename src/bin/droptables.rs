@@ -0,0 +1,273 @@
+//! `droptables sim <FILE> [--draws N] [--seed S]`
+//!
+//! Reads a weights file, runs `N` simulated draws and prints a histogram,
+//! a chi-squared goodness-of-fit statistic and how many draws each item
+//! takes to drop (median and 90th percentile, from `stats::kills_until`).
+//!
+//! Supported inputs:
+//! * `.json`: either `{"name": weight, ...}` or `[["name", weight], ...]`,
+//!   optionally wrapped as `{"version": N, "weights": ...}`
+//! * `.toml`: flat `name = weight` lines, where a weight is a number or a
+//!   quoted odds string (`"1/128"`, `"5%"`); `[table]` headers are rejected
+//!   (see `droptables::parse::parse_toml_pairs`)
+//! * anything else: the `name:weight` text format (see `droptables::parse`)
+//!
+//! TOML and text files carry the table version as a `#! version = N` line.
+//! The version and content hash are printed above the histogram.
+
+use std::error::Error;
+use std::process::ExitCode;
+
+use droptables::stats::kills_until;
+use droptables::{DropTable, parse};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+const USAGE: &str = "usage: droptables sim <FILE> [--draws N] [--seed S]";
+
+struct SimArgs {
+    path: String,
+    draws: u64,
+    seed: Option<u64>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<SimArgs, String> {
+    match args.next().as_deref() {
+        Some("sim") => {}
+        Some(other) => return Err(format!("unknown command `{other}`")),
+        None => return Err("missing command".into()),
+    }
+    let mut path = None;
+    let mut draws = 1_000_000;
+    let mut seed = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-n" | "--draws" => {
+                let v = args.next().ok_or("--draws needs a value")?;
+                draws = v.parse().map_err(|_| format!("bad --draws `{v}`"))?;
+            }
+            "--seed" => {
+                let v = args.next().ok_or("--seed needs a value")?;
+                seed = Some(v.parse().map_err(|_| format!("bad --seed `{v}`"))?);
+            }
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+    Ok(SimArgs {
+        path: path.ok_or("missing FILE")?,
+        draws,
+        seed,
+    })
+}
+
+/// A weights file: `(name, weight)` pairs and the table version.
+struct Loaded {
+    pairs: Vec<(String, f32)>,
+    version: u32,
+}
+
+fn json_table(src: &str) -> Result<Loaded, Box<dyn Error>> {
+    use serde_json::Value;
+
+    let value: Value = serde_json::from_str(src)?;
+    match value.get("weights") {
+        Some(weights) => {
+            let version = match value.get("version") {
+                None => 0,
+                Some(v) => v
+                    .as_u64()
+                    .and_then(|v| u32::try_from(v).ok())
+                    .ok_or_else(|| format!("version must be a u32, got {v}"))?,
+            };
+            Ok(Loaded {
+                pairs: json_pairs(weights)?,
+                version,
+            })
+        }
+        None => Ok(Loaded {
+            pairs: json_pairs(&value)?,
+            version: 0,
+        }),
+    }
+}
+
+fn json_pairs(value: &serde_json::Value) -> Result<Vec<(String, f32)>, Box<dyn Error>> {
+    use serde_json::Value;
+
+    let weight = |v: &Value| {
+        v.as_f64()
+            .map(|w| w as f32)
+            .ok_or_else(|| format!("weight must be a number, got {v}"))
+    };
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, v)| Ok((k.clone(), weight(v)?)))
+            .collect(),
+        Value::Array(rows) => rows
+            .iter()
+            .map(|row| match row.as_array().map(Vec::as_slice) {
+                Some([Value::String(name), w]) => Ok((name.clone(), weight(w)?)),
+                _ => Err(format!("expected [\"name\", weight], got {row}").into()),
+            })
+            .collect(),
+        _ => Err("expected a JSON object or array".into()),
+    }
+}
+
+/// Flat TOML or the text format, with the version from a `#! version`
+/// line in either.
+fn text_table(src: &str, toml: bool) -> Result<Loaded, Box<dyn Error>> {
+    let pairs = if toml {
+        parse::parse_toml_pairs(src)?
+    } else {
+        parse::parse_pairs(src)?
+    };
+    Ok(Loaded {
+        pairs,
+        version: parse::parse_version(src)?,
+    })
+}
+
+fn load_table(path: &str) -> Result<Loaded, Box<dyn Error>> {
+    let src = std::fs::read_to_string(path)?;
+    if path.ends_with(".json") {
+        json_table(&src)
+    } else {
+        text_table(&src, path.ends_with(".toml"))
+    }
+}
+
+fn run(args: SimArgs) -> Result<(), Box<dyn Error>> {
+    let Loaded { pairs, version } = load_table(&args.path)?;
+    let hash = DropTable::from_pairs(pairs.iter().cloned())?.content_hash();
+    println!("table version {version}, content hash {hash:016x}\n");
+    let total: f64 = pairs.iter().map(|(_, w)| *w as f64).sum();
+    let table = DropTable::from_pairs(pairs.iter().enumerate().map(|(i, (_, w))| (i, *w)))?;
+
+    let mut rng = match args.seed {
+        Some(s) => StdRng::seed_from_u64(s),
+        None => StdRng::from_os_rng(),
+    };
+    let mut counts = vec![0u64; pairs.len()];
+    for _ in 0..args.draws {
+        counts[*table.sample(&mut rng)] += 1;
+    }
+
+    let name_w = pairs.iter().map(|(n, _)| n.len()).max().unwrap_or(4).max(4);
+    println!(
+        "{:<name_w$} {:>12} {:>10} {:>10} {:>12} {:>12} {:>12}",
+        "item", "count", "observed", "expected", "1 in N", "median draws", "90% by"
+    );
+    let mut chi2 = 0.0f64;
+    let mut dof = 0usize;
+    for (i, (name, w)) in pairs.iter().enumerate() {
+        let p = *w as f64 / total;
+        let expected = p * args.draws as f64;
+        let observed = counts[i] as f64 / args.draws.max(1) as f64;
+        if expected > 0.0 {
+            chi2 += (counts[i] as f64 - expected).powi(2) / expected;
+            dof += 1;
+        }
+        let one_in = if p > 0.0 {
+            format!("{:.1}", 1.0 / p)
+        } else {
+            "never".into()
+        };
+        let until = kills_until(&table, i);
+        let (median, p90) = if p > 0.0 {
+            (until.median.to_string(), until.p90.to_string())
+        } else {
+            ("-".into(), "-".into())
+        };
+        println!(
+            "{name:<name_w$} {:>12} {:>9.4}% {:>9.4}% {one_in:>12} {median:>12} {p90:>12}",
+            counts[i],
+            observed * 100.0,
+            p * 100.0
+        );
+    }
+    println!(
+        "\nchi-squared = {chi2:.3} with {} degrees of freedom over {} draws",
+        dof.saturating_sub(1),
+        args.draws
+    );
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("error: {e}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Result<SimArgs, String> {
+        parse_args(s.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parses_sim_args() {
+        let a = args("sim boss.toml --draws 500 --seed 7").unwrap();
+        assert_eq!(
+            (a.path.as_str(), a.draws, a.seed),
+            ("boss.toml", 500, Some(7))
+        );
+        let a = args("sim -n 3 boss.txt").unwrap();
+        assert_eq!((a.path.as_str(), a.draws, a.seed), ("boss.txt", 3, None));
+
+        assert_eq!(args("").err().unwrap(), "missing command");
+        assert_eq!(args("run x").err().unwrap(), "unknown command `run`");
+        assert_eq!(args("sim").err().unwrap(), "missing FILE");
+        assert_eq!(args("sim a b").err().unwrap(), "unexpected argument `b`");
+        assert_eq!(args("sim a --draws x").err().unwrap(), "bad --draws `x`");
+        assert_eq!(args("sim a --seed").err().unwrap(), "--seed needs a value");
+    }
+
+    #[test]
+    fn json_forms_and_version() {
+        let object = json_table(r#"{"a": 1, "b": 3}"#).unwrap();
+        assert_eq!(object.version, 0);
+        assert_eq!(object.pairs, [("a".into(), 1.0), ("b".into(), 3.0)]);
+
+        let rows = json_table(r#"{"version": 4, "weights": [["a", 1], ["b", 3]]}"#).unwrap();
+        assert_eq!(rows.version, 4);
+        assert_eq!(rows.pairs, object.pairs);
+
+        assert!(json_table(r#"{"version": -1, "weights": {}}"#).is_err());
+        assert!(json_table(r#"{"a": "x"}"#).is_err());
+        assert!(json_table(r#"[["a"]]"#).is_err());
+        assert!(json_table("3").is_err());
+    }
+
+    #[test]
+    fn toml_and_text_carry_the_version() {
+        let toml = text_table("#! version = 2\na = 1\nb = \"1/4\"\n", true).unwrap();
+        assert_eq!(toml.version, 2);
+        assert_eq!(toml.pairs, [("a".into(), 1.0), ("b".into(), 0.25)]);
+        let err = text_table("[boss]\na = 1", true).err().unwrap();
+        assert!(
+            err.to_string().contains("tables are not supported"),
+            "{err}"
+        );
+
+        let text = text_table("#! version = 5\na:1, b:3", false).unwrap();
+        assert_eq!((text.version, text.pairs.len()), (5, 2));
+        assert!(text_table("#! version x\na:1", false).is_err());
+    }
+}
@@ -0,0 +1,229 @@
+//! Direct binomial and multinomial count sampling.
+//!
+//! Small means use inversion; larger ones use Hörmann's BTRD
+//! (transformed rejection with decomposition, 1993), which costs O(1)
+//! expected time regardless of `n`.
+
+use rand::Rng;
+
+use crate::DropTable;
+
+/// Stirling series remainder `ln k! - [(k + 0.5) ln(k + 1) - (k + 1) + ln √(2π)]`.
+fn stirling_tail(k: f64) -> f64 {
+    const TABLE: [f64; 10] = [
+        0.081_061_466_795_327_26,
+        0.041_340_695_955_409_29,
+        0.027_677_925_684_998_34,
+        0.020_790_672_103_765_09,
+        0.016_644_691_189_821_19,
+        0.013_876_128_823_070_75,
+        0.011_896_709_945_891_77,
+        0.010_411_265_261_972_09,
+        0.009_255_462_182_712_733,
+        0.008_330_563_433_362_87,
+    ];
+    if k < 10.0 {
+        return TABLE[k as usize];
+    }
+    let r = 1.0 / (k + 1.0);
+    let r2 = r * r;
+    (1.0 / 12.0 - (1.0 / 360.0 - r2 / 1260.0) * r2) * r
+}
+
+/// Number of successes in `n` independent trials with success chance `p`.
+pub(crate) fn binomial<R: Rng + ?Sized>(rng: &mut R, n: u64, p: f64) -> u64 {
+    if n == 0 || p <= 0.0 {
+        return 0;
+    }
+    if p >= 1.0 {
+        return n;
+    }
+    if p > 0.5 {
+        return n - binomial(rng, n, 1.0 - p);
+    }
+    if (n as f64) * p < 10.0 {
+        inversion(rng, n, p)
+    } else {
+        btrd(rng, n, p)
+    }
+}
+
+fn inversion<R: Rng + ?Sized>(rng: &mut R, n: u64, p: f64) -> u64 {
+    let q = 1.0 - p;
+    let s = p / q;
+    let a = (n as f64 + 1.0) * s;
+    let r0 = q.powf(n as f64);
+    'retry: loop {
+        let mut r = r0;
+        let mut u: f64 = rng.random();
+        let mut x = 0u64;
+        while u > r {
+            u -= r;
+            x += 1;
+            if x > n {
+                // only reachable through rounding; start over
+                continue 'retry;
+            }
+            r *= a / x as f64 - s;
+        }
+        return x;
+    }
+}
+
+fn btrd<R: Rng + ?Sized>(rng: &mut R, n: u64, p: f64) -> u64 {
+    let nf = n as f64;
+    let q = 1.0 - p;
+    let m = ((nf + 1.0) * p).floor();
+    let r = p / q;
+    let nr = (nf + 1.0) * r;
+    let npq = nf * p * q;
+    let spq = npq.sqrt();
+    let b = 1.15 + 2.53 * spq;
+    let a = -0.0873 + 0.0248 * b + 0.01 * p;
+    let c = nf * p + 0.5;
+    let alpha = (2.83 + 5.1 / b) * spq;
+    let vr = 0.92 - 4.2 / b;
+    let urvr = 0.86 * vr;
+
+    loop {
+        let mut v: f64 = rng.random();
+        let u;
+        if v <= urvr {
+            let u = v / vr - 0.43;
+            return ((2.0 * a / (0.5 - u.abs()) + b) * u + c).floor() as u64;
+        }
+        if v >= vr {
+            u = rng.random::<f64>() - 0.5;
+        } else {
+            let t = v / vr - 0.93;
+            u = 0.5f64.copysign(t) - t;
+            v = rng.random::<f64>() * vr;
+        }
+
+        let us = 0.5 - u.abs();
+        let k = ((2.0 * a / us + b) * u + c).floor();
+        if k < 0.0 || k > nf {
+            continue;
+        }
+        v = v * alpha / (a / (us * us) + b);
+        let km = (k - m).abs();
+
+        if km <= 15.0 {
+            // walk the pmf ratio f(i)/f(i-1) from the mode
+            let mut f = 1.0;
+            if m < k {
+                let mut i = m + 1.0;
+                while i <= k {
+                    f *= nr / i - r;
+                    i += 1.0;
+                }
+            } else {
+                let mut i = k + 1.0;
+                while i <= m {
+                    v *= nr / i - r;
+                    i += 1.0;
+                }
+            }
+            if v <= f {
+                return k as u64;
+            }
+            continue;
+        }
+
+        let v = v.ln();
+        let rho = (km / npq) * (((km / 3.0 + 0.625) * km + 1.0 / 6.0) / npq + 0.5);
+        let t = -km * km / (2.0 * npq);
+        if v < t - rho {
+            return k as u64;
+        }
+        if v > t + rho {
+            continue;
+        }
+        let nm = nf - m + 1.0;
+        let h = (m + 0.5) * ((m + 1.0) / (r * nm)).ln() + stirling_tail(m) + stirling_tail(nf - m);
+        let nk = nf - k + 1.0;
+        if v <= h + (nf + 1.0) * (nm / nk).ln() + (k + 0.5) * (nk * r / (k + 1.0)).ln()
+            - stirling_tail(k)
+            - stirling_tail(nf - k)
+        {
+            return k as u64;
+        }
+    }
+}
+
+impl<T> DropTable<T> {
+    /// Counts per item after `n` draws, sampled directly as a multinomial
+    /// via one conditional binomial per item instead of `n` separate draws.
+    ///
+    /// The result is in item order and sums to `n`. Cost is O(len) and
+    /// independent of `n`, so "a million kills" costs the same as ten.
+    pub fn multinomial<R: Rng + ?Sized>(&self, rng: &mut R, n: u64) -> Vec<u64> {
+        let mut counts = vec![0u64; self.len()];
+        // the last item that can drop takes whatever the others left, so
+        // rounding in `mass` can't lose or misplace draws
+        let Some(last) = self.weights().iter().rposition(|&w| w > 0.0) else {
+            return counts;
+        };
+        let mut left = n;
+        let mut mass: f64 = self.weights().iter().map(|&w| f64::from(w)).sum();
+        for (count, &w) in counts[..last].iter_mut().zip(self.weights()) {
+            if left == 0 {
+                break;
+            }
+            let w = f64::from(w);
+            let k = binomial(rng, left, w / mass);
+            *count = k;
+            left -= k;
+            mass -= w;
+        }
+        counts[last] = left;
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    fn mean_var(xs: &[u64]) -> (f64, f64) {
+        let n = xs.len() as f64;
+        let mean = xs.iter().map(|&x| x as f64).sum::<f64>() / n;
+        let var = xs.iter().map(|&x| (x as f64 - mean).powi(2)).sum::<f64>() / n;
+        (mean, var)
+    }
+
+    #[test]
+    fn binomial_moments() {
+        let mut rng = StdRng::seed_from_u64(1345);
+        for &(n, p) in &[(20u64, 0.1), (1_000, 0.3), (1_000_000, 0.7), (50, 0.5)] {
+            let xs: Vec<u64> = (0..20_000).map(|_| binomial(&mut rng, n, p)).collect();
+            let (mean, var) = mean_var(&xs);
+            let (em, ev) = (n as f64 * p, n as f64 * p * (1.0 - p));
+            assert!(
+                (mean - em).abs() < 4.0 * (ev / 20_000.0).sqrt() + 1e-9,
+                "{n} {p}: {mean}"
+            );
+            assert!((var / ev - 1.0).abs() < 0.05, "{n} {p}: {var} vs {ev}");
+            assert!(xs.iter().all(|&x| x <= n));
+        }
+    }
+
+    #[test]
+    fn multinomial_sums_and_skips_zero_weights() {
+        let dt = DropTable::from_pairs([("a", 1.0), ("never", 0.0), ("b", 3.0)]).unwrap();
+        let mut rng = StdRng::seed_from_u64(7);
+        let counts = dt.multinomial(&mut rng, 1_000_000);
+        assert_eq!(counts.iter().sum::<u64>(), 1_000_000);
+        assert_eq!(counts[1], 0);
+        assert!((counts[0] as f64 - 250_000.0).abs() < 2_500.0, "{counts:?}");
+
+        // a zero-weight last item gets nothing, even with a heavy item before
+        let dt = DropTable::from_pairs([("a", 9.0), ("b", 1.0), ("never", 0.0)]).unwrap();
+        for seed in 0..50 {
+            let counts = dt.multinomial(&mut StdRng::seed_from_u64(seed), 1_000);
+            assert_eq!(counts.iter().sum::<u64>(), 1_000);
+            assert_eq!(counts[2], 0);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use rand::Rng;
+
+use crate::DropTable;
+use crate::error::ProbError;
+
+impl<D: ?Sized> DropTable<Box<D>> {
+    /// Build a table over trait objects, so entries of different types
+    /// (currency, items, buffs) share one table and are used through the
+    /// trait when drawn.
+    ///
+    /// Casting the first box is enough for the rest of an array to coerce:
+    ///
+    /// ```
+    /// use droptables::DropTable;
+    ///
+    /// trait Reward {
+    ///     fn grant(&self, gold: &mut u32, buffs: &mut Vec<&'static str>);
+    /// }
+    ///
+    /// struct Coins(u32);
+    /// struct Buff(&'static str);
+    ///
+    /// impl Reward for Coins {
+    ///     fn grant(&self, gold: &mut u32, _: &mut Vec<&'static str>) {
+    ///         *gold += self.0;
+    ///     }
+    /// }
+    ///
+    /// impl Reward for Buff {
+    ///     fn grant(&self, _: &mut u32, buffs: &mut Vec<&'static str>) {
+    ///         buffs.push(self.0);
+    ///     }
+    /// }
+    ///
+    /// let table = DropTable::from_boxed_pairs([
+    ///     (Box::new(Coins(50)) as Box<dyn Reward>, 90.0),
+    ///     (Box::new(Buff("haste")), 10.0),
+    /// ])
+    /// .unwrap();
+    ///
+    /// let (mut gold, mut buffs) = (0, Vec::new());
+    /// for _ in 0..10 {
+    ///     table.sample_dyn(&mut rand::rng()).grant(&mut gold, &mut buffs);
+    /// }
+    /// assert_eq!(gold as usize + 50 * buffs.len(), 500);
+    /// ```
+    ///
+    /// # Errors
+    /// As [`from_pairs`](Self::from_pairs).
+    pub fn from_boxed_pairs<I>(pairs: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = (Box<D>, f32)>,
+    {
+        Self::from_pairs(pairs)
+    }
+
+    /// [`sample`](Self::sample), unboxed: `&dyn Trait` rather than
+    /// `&Box<dyn Trait>`.
+    #[inline]
+    pub fn sample_dyn<R: Rng + ?Sized>(&self, rng: &mut R) -> &D {
+        self.sample(rng)
+    }
+
+    /// Items as `&D`, in insertion order.
+    pub fn iter_dyn(&self) -> impl Iterator<Item = (&D, f32)> {
+        self.items
+            .iter()
+            .map(|b| &**b)
+            .zip(self.weights.iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Debug;
+
+    #[test]
+    fn samples_through_the_trait() {
+        let table = DropTable::from_boxed_pairs([
+            (Box::new(7u8) as Box<dyn Debug>, 0.0),
+            (Box::new("text"), 1.0),
+        ])
+        .unwrap();
+        let drawn = table.sample_dyn(&mut rand::rng());
+        assert_eq!(format!("{drawn:?}"), "\"text\"");
+        let formatted: Vec<_> = table
+            .iter_dyn()
+            .map(|(item, w)| format!("{item:?}:{w}"))
+            .collect();
+        assert_eq!(formatted, ["7:0", "\"text\":1"]);
+    }
+}
@@ -0,0 +1,85 @@
+//! "Roll loot worth ~N": keep sampling until the accumulated cost reaches a budget.
+
+use rand::Rng;
+
+use crate::{DropTable, IndexSampler, StaticDropTable};
+
+/// Shared loop: draw via `draw` until `cost` sums to at least `budget` or
+/// `max_iters` draws have been made.
+fn until_budget<'a, T: 'a, R, F>(
+    rng: &mut R,
+    mut draw: impl FnMut(&mut R) -> &'a T,
+    mut cost: F,
+    budget: f64,
+    max_iters: usize,
+) -> Vec<&'a T>
+where
+    R: Rng + ?Sized,
+    F: FnMut(&T) -> f64,
+{
+    let mut drops = Vec::new();
+    let mut spent = 0.0;
+    while spent < budget && drops.len() < max_iters {
+        let item = draw(rng);
+        spent += cost(item);
+        drops.push(item);
+    }
+    drops
+}
+
+impl<T> DropTable<T> {
+    /// Sample until the summed `cost` of the drops reaches `budget`.
+    ///
+    /// The drop that crosses the budget is included, so the total can
+    /// overshoot by at most one item's cost. At most `max_iters` items are
+    /// drawn, which bounds the loop when costs can be zero or negative.
+    pub fn sample_until_budget<'a, R, F>(
+        &'a self,
+        rng: &mut R,
+        cost: F,
+        budget: f64,
+        max_iters: usize,
+    ) -> Vec<&'a T>
+    where
+        R: Rng + ?Sized,
+        F: FnMut(&T) -> f64,
+    {
+        until_budget(rng, |rng| self.sample(rng), cost, budget, max_iters)
+    }
+}
+
+impl<S: IndexSampler, T> StaticDropTable<S, T> {
+    /// See [`DropTable::sample_until_budget`].
+    pub fn sample_until_budget<R, F>(
+        &self,
+        rng: &mut R,
+        cost: F,
+        budget: f64,
+        max_iters: usize,
+    ) -> Vec<&'static T>
+    where
+        R: Rng + ?Sized,
+        F: FnMut(&T) -> f64,
+    {
+        until_budget(rng, |rng| self.sample(rng), cost, budget, max_iters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stops_at_budget_or_cap() {
+        let table = DropTable::from_pairs([(10u32, 1.0), (25u32, 1.0)]).unwrap();
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let drops = table.sample_until_budget(&mut rng, |&g| g as f64, 100.0, 1000);
+            let total: u32 = drops.iter().copied().sum();
+            let last = **drops.last().unwrap();
+            assert!(total >= 100 && total - last < 100);
+        }
+        let drops = table.sample_until_budget(&mut rng, |_| 0.0, 1.0, 7);
+        assert_eq!(drops.len(), 7);
+    }
+}
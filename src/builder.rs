@@ -0,0 +1,197 @@
+use rand::Rng;
+
+use crate::DropTable;
+use crate::error::ProbError;
+
+/// Builds a [`LootTable`]: items that always drop plus a weighted table
+/// that is rolled, like a wiki drop table's "Always" and "Main" sections.
+///
+/// ```
+/// use droptables::DropTableBuilder;
+///
+/// let loot = DropTableBuilder::new()
+///     .always("big bones")
+///     .entry("rune scimitar", 1.0)
+///     .entry("coins", 9.0)
+///     .build()
+///     .unwrap();
+/// let drop = loot.roll(&mut rand::rng(), 1);
+/// assert_eq!(drop.guaranteed, ["big bones"]);
+/// assert_eq!(drop.rolled.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DropTableBuilder<T> {
+    always: Vec<T>,
+    pairs: Vec<(T, f32)>,
+    rolls: usize,
+}
+
+impl<T> Default for DropTableBuilder<T> {
+    fn default() -> Self {
+        Self {
+            always: Vec::new(),
+            pairs: Vec::new(),
+            rolls: 1,
+        }
+    }
+}
+
+impl<T> DropTableBuilder<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an item that drops on every roll, before anything is rolled.
+    pub fn always(mut self, item: T) -> Self {
+        self.always.push(item);
+        self
+    }
+
+    /// Add a rolled entry.
+    pub fn entry(mut self, item: T, weight: f32) -> Self {
+        self.pairs.push((item, weight));
+        self
+    }
+
+    /// How many times [`LootTable::roll_default`] rolls the weighted part
+    /// (default 1), e.g. 2 for a boss whose main table is rolled twice.
+    /// Zero is rejected by [`build`](Self::build).
+    pub fn rolls(mut self, rolls: usize) -> Self {
+        self.rolls = rolls;
+        self
+    }
+
+    /// # Errors
+    /// [`ProbError::Empty`] if nothing was added, [`ProbError::ZeroRolls`]
+    /// for `rolls(0)`; otherwise whatever [`DropTable::from_pairs`] reports
+    /// for the rolled entries. A table with only `always` items is fine.
+    pub fn build(self) -> Result<LootTable<T>, ProbError> {
+        if self.rolls == 0 {
+            return Err(ProbError::ZeroRolls);
+        }
+        let rolled = if self.pairs.is_empty() {
+            if self.always.is_empty() {
+                return Err(ProbError::Empty);
+            }
+            None
+        } else {
+            Some(DropTable::from_pairs(self.pairs)?)
+        };
+        Ok(LootTable {
+            always: self.always,
+            rolled,
+            rolls: self.rolls,
+        })
+    }
+}
+
+/// Guaranteed items plus a rolled [`DropTable`]; see [`DropTableBuilder`].
+#[derive(Debug, Clone)]
+pub struct LootTable<T> {
+    always: Vec<T>,
+    rolled: Option<DropTable<T>>,
+    rolls: usize,
+}
+
+/// What one kill dropped: every guaranteed item, then the rolled ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollResult<'a, T> {
+    pub guaranteed: &'a [T],
+    pub rolled: Vec<&'a T>,
+}
+
+impl<'a, T> RollResult<'a, T> {
+    /// Guaranteed items first, then rolled ones in roll order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + '_ {
+        self.guaranteed.iter().chain(self.rolled.iter().copied())
+    }
+
+    pub fn len(&self) -> usize {
+        self.guaranteed.len() + self.rolled.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> LootTable<T> {
+    /// The guaranteed items, once each, plus `n_rolls` independent draws
+    /// from the rolled table (none if it has no entries).
+    pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R, n_rolls: usize) -> RollResult<'_, T> {
+        let rolled = match &self.rolled {
+            Some(table) => (0..n_rolls).map(|_| table.sample(rng)).collect(),
+            None => Vec::new(),
+        };
+        RollResult {
+            guaranteed: &self.always,
+            rolled,
+        }
+    }
+
+    /// [`roll`](Self::roll) with the configured number of
+    /// [`rolls`](DropTableBuilder::rolls).
+    pub fn roll_default<R: Rng + ?Sized>(&self, rng: &mut R) -> RollResult<'_, T> {
+        self.roll(rng, self.rolls)
+    }
+
+    pub fn rolls(&self) -> usize {
+        self.rolls
+    }
+
+    pub fn always(&self) -> &[T] {
+        &self.always
+    }
+
+    /// The rolled table, if any entries were added.
+    pub fn rolled(&self) -> Option<&DropTable<T>> {
+        self.rolled.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn always_items_come_first_on_every_roll() {
+        let loot = DropTableBuilder::new()
+            .always("bones")
+            .always("ashes")
+            .entry("rune", 0.0)
+            .entry("coins", 1.0)
+            .build()
+            .unwrap();
+        let drop = loot.roll(&mut rand::rng(), 3);
+        assert_eq!(drop.len(), 5);
+        assert_eq!(
+            drop.iter().copied().collect::<Vec<_>>(),
+            ["bones", "ashes", "coins", "coins", "coins"]
+        );
+
+        let twice = DropTableBuilder::new()
+            .entry("coins", 1.0)
+            .rolls(2)
+            .build()
+            .unwrap();
+        assert_eq!(twice.roll_default(&mut rand::rng()).rolled, [&"coins"; 2]);
+
+        let only_always = DropTableBuilder::new().always(1).build().unwrap();
+        assert_eq!(
+            only_always.roll(&mut rand::rng(), 2).rolled,
+            Vec::<&i32>::new()
+        );
+        assert!(matches!(
+            DropTableBuilder::<u8>::new().build(),
+            Err(ProbError::Empty)
+        ));
+        assert_eq!(
+            DropTableBuilder::new()
+                .entry("coins", 1.0)
+                .rolls(0)
+                .build()
+                .err(),
+            Some(ProbError::ZeroRolls)
+        );
+    }
+}
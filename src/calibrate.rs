@@ -0,0 +1,298 @@
+//! Solving nested-table weights from target drop rates.
+//!
+//! Drop rates are usually published as overall odds ("the hilt is 1/508"),
+//! while a [`NestedTable`] is built from per-level weights: a gate into
+//! the armour table, a uniform pick inside it. [`calibrate`] works
+//! backwards from the overall odds to those weights.
+
+use std::fmt;
+
+use crate::error::ProbError;
+use crate::{NestedEntry, NestedTable};
+
+/// Slack below which leftover probability counts as zero.
+const EPSILON: f64 = 1e-9;
+
+/// Why [`calibrate`] found no weights.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalibrateError {
+    /// Target `index` is not a probability in `(0, 1]`.
+    OutOfRange { index: usize },
+    /// Target `index` names an item the table doesn't contain.
+    UnknownItem { index: usize },
+    /// Target `index` names an item found at several places in the table,
+    /// so its overall rate doesn't pin any one of them.
+    DuplicateItem { index: usize },
+    /// The targets under `table` add up to more than the chance of
+    /// reaching it.
+    Infeasible {
+        table: String,
+        targets: f64,
+        available: f64,
+    },
+    /// Every item under `table` has a target, and together they leave
+    /// `slack` of its chance unclaimed.
+    NoSlack { table: String, slack: f64 },
+    /// A solved level was rejected when rebuilding it.
+    Table(ProbError),
+}
+
+impl fmt::Display for CalibrateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrateError::OutOfRange { index } => {
+                write!(f, "target {index} is not a probability in (0, 1]")
+            }
+            CalibrateError::UnknownItem { index } => {
+                write!(f, "target {index} is not an item of the table")
+            }
+            CalibrateError::DuplicateItem { index } => {
+                write!(f, "target {index} appears more than once in the table")
+            }
+            CalibrateError::Infeasible {
+                table,
+                targets,
+                available,
+            } => write!(
+                f,
+                "targets under {table:?} sum to {targets}, but it is only reached with chance {available}"
+            ),
+            CalibrateError::NoSlack { table, slack } => write!(
+                f,
+                "every item under {table:?} has a target, leaving {slack} unclaimed"
+            ),
+            CalibrateError::Table(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for CalibrateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CalibrateError::Table(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Overall chance that one roll of `table` ends in `item`, summed over
+/// every place it appears.
+pub fn marginal<T: PartialEq>(table: &NestedTable<T>, item: &T) -> f64 {
+    table
+        .entries()
+        .iter()
+        .zip(table.probabilities())
+        .map(|(entry, p)| match entry {
+            NestedEntry::Item(it) if it == item => p,
+            NestedEntry::Item(_) => 0.0,
+            NestedEntry::Table(sub) => p * marginal(sub, item),
+        })
+        .sum()
+}
+
+/// Rebuild `table` with weights that give each `(item, probability)` in
+/// `targets` that overall chance per roll.
+///
+/// Items without a target share whatever chance is left at each level in
+/// proportion to their current weights, so a table whose only job is to
+/// hold "everything else" keeps its internal balance. Names and structure
+/// are unchanged.
+///
+/// ```
+/// use droptables::{NestedEntry, NestedTable};
+/// use droptables::calibrate::{calibrate, marginal};
+///
+/// let armour = NestedTable::new("armour", ["chestplate", "tassets", "boots"].map(|i| (NestedEntry::Item(i), 1.0))).unwrap();
+/// let graardor = NestedTable::new("graardor", [
+///     (armour.into(), 1.0),
+///     (NestedEntry::Item("hilt"), 1.0),
+///     (NestedEntry::Item("bones"), 100.0),
+/// ])
+/// .unwrap();
+///
+/// let solved = calibrate(&graardor, &[
+///     ("chestplate", 1.0 / 384.0),
+///     ("tassets", 1.0 / 384.0),
+///     ("boots", 1.0 / 384.0),
+///     ("hilt", 1.0 / 508.0),
+/// ])
+/// .unwrap();
+/// // the gate into the armour table comes out at 1/128
+/// assert!((solved.probabilities()[0] - 1.0 / 128.0).abs() < 1e-7);
+/// assert!((marginal(&solved, &"hilt") - 1.0 / 508.0).abs() < 1e-7);
+/// ```
+///
+/// # Errors
+/// See [`CalibrateError`]; targets are checked before anything is solved.
+pub fn calibrate<T: PartialEq + Clone>(
+    table: &NestedTable<T>,
+    targets: &[(T, f64)],
+) -> Result<NestedTable<T>, CalibrateError> {
+    for (index, (item, p)) in targets.iter().enumerate() {
+        if !(*p > 0.0 && *p <= 1.0) {
+            return Err(CalibrateError::OutOfRange { index });
+        }
+        match occurrences(table, item) {
+            0 => return Err(CalibrateError::UnknownItem { index }),
+            1 => {}
+            _ => return Err(CalibrateError::DuplicateItem { index }),
+        }
+    }
+    solve(table, 1.0, targets)
+}
+
+fn occurrences<T: PartialEq>(table: &NestedTable<T>, item: &T) -> usize {
+    table
+        .entries()
+        .iter()
+        .map(|entry| match entry {
+            NestedEntry::Item(it) => usize::from(it == item),
+            NestedEntry::Table(sub) => occurrences(sub, item),
+        })
+        .sum()
+}
+
+fn target<T: PartialEq>(targets: &[(T, f64)], item: &T) -> Option<f64> {
+    targets.iter().find(|(t, _)| t == item).map(|&(_, p)| p)
+}
+
+/// Total target under `entry`, and whether anything under it is free.
+fn demand<T: PartialEq>(entry: &NestedEntry<T>, targets: &[(T, f64)]) -> (f64, bool) {
+    match entry {
+        NestedEntry::Item(item) => match target(targets, item) {
+            Some(p) => (p, false),
+            None => (0.0, true),
+        },
+        NestedEntry::Table(sub) => sub.entries().iter().fold((0.0, false), |acc, e| {
+            let (p, free) = demand(e, targets);
+            (acc.0 + p, acc.1 || free)
+        }),
+    }
+}
+
+/// Re-weight `table`, reached with overall chance `mass`.
+fn solve<T: PartialEq + Clone>(
+    table: &NestedTable<T>,
+    mass: f64,
+    targets: &[(T, f64)],
+) -> Result<NestedTable<T>, CalibrateError> {
+    let current = table.probabilities();
+    let demands: Vec<(f64, bool)> = table.entries().iter().map(|e| demand(e, targets)).collect();
+    let pinned: f64 = demands.iter().map(|d| d.0).sum();
+    let slack = mass - pinned;
+    if slack < -EPSILON {
+        return Err(CalibrateError::Infeasible {
+            table: table.name().to_owned(),
+            targets: pinned,
+            available: mass,
+        });
+    }
+    let slack = slack.max(0.0);
+
+    // free entries split the slack by current weight, or evenly if those
+    // are all zero
+    let free_weight: f64 = demands
+        .iter()
+        .zip(&current)
+        .filter(|(d, _)| d.1)
+        .map(|(_, &p)| p)
+        .sum();
+    let free_count = demands.iter().filter(|d| d.1).count();
+    if free_count == 0 && slack > EPSILON {
+        return Err(CalibrateError::NoSlack {
+            table: table.name().to_owned(),
+            slack,
+        });
+    }
+    let share = |i: usize| match (demands[i].1, free_weight > 0.0) {
+        (false, _) => 0.0,
+        (true, true) => slack * current[i] / free_weight,
+        (true, false) => slack / free_count as f64,
+    };
+
+    let mut entries = Vec::with_capacity(current.len());
+    for (i, entry) in table.entries().iter().enumerate() {
+        let child = demands[i].0 + share(i);
+        let weight = if mass > 0.0 { child / mass } else { current[i] };
+        let entry = match entry {
+            NestedEntry::Item(item) => NestedEntry::Item(item.clone()),
+            NestedEntry::Table(sub) => NestedEntry::Table(solve(sub, child, targets)?),
+        };
+        entries.push((entry, weight as f32));
+    }
+    NestedTable::new(table.name(), entries).map_err(CalibrateError::Table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items<const N: usize>(
+        names: [&'static str; N],
+        w: f32,
+    ) -> [(NestedEntry<&'static str>, f32); N] {
+        names.map(|n| (NestedEntry::Item(n), w))
+    }
+
+    #[test]
+    fn solves_gates_and_keeps_free_balance() {
+        let rdt = NestedTable::new("rdt", items(["spear", "law", "nature"], 1.0)).unwrap();
+        let filler = NestedTable::new(
+            "filler",
+            [
+                (NestedEntry::Item("coins"), 3.0),
+                (NestedEntry::Item("logs"), 1.0),
+            ],
+        )
+        .unwrap();
+        let main = NestedTable::new(
+            "main",
+            [
+                (rdt.into(), 1.0),
+                (filler.into(), 10.0),
+                (NestedEntry::Item("pet"), 1.0),
+            ],
+        )
+        .unwrap();
+
+        let solved = calibrate(&main, &[("spear", 0.001), ("pet", 0.0002)]).unwrap();
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-7;
+        assert!(close(marginal(&solved, &"spear"), 0.001));
+        assert!(close(marginal(&solved, &"pet"), 0.0002));
+        // law and nature keep their 1:1, coins and logs their 3:1
+        assert!(close(
+            marginal(&solved, &"law"),
+            marginal(&solved, &"nature")
+        ));
+        assert!(close(
+            marginal(&solved, &"coins"),
+            3.0 * marginal(&solved, &"logs")
+        ));
+        let total: f64 = ["spear", "law", "nature", "coins", "logs", "pet"]
+            .iter()
+            .map(|i| marginal(&solved, i))
+            .sum();
+        assert!(close(total, 1.0));
+
+        assert!(matches!(
+            calibrate(&main, &[("spear", 0.7), ("pet", 0.4)]),
+            Err(CalibrateError::Infeasible { .. })
+        ));
+        assert!(matches!(
+            calibrate(&main, &[("dragon", 0.1)]),
+            Err(CalibrateError::UnknownItem { index: 0 })
+        ));
+        assert!(matches!(
+            calibrate(&main, &[("pet", 0.0)]),
+            Err(CalibrateError::OutOfRange { index: 0 })
+        ));
+        let gate = NestedTable::new("gate", items(["yes", "no"], 1.0)).unwrap();
+        assert_eq!(
+            calibrate(&gate, &[("yes", 0.2), ("no", 0.3)]).unwrap_err(),
+            CalibrateError::NoSlack {
+                table: "gate".into(),
+                slack: 0.5
+            }
+        );
+    }
+}
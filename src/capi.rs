@@ -0,0 +1,93 @@
+//! C API (feature `capi`). The matching header is `include/droptables.h`.
+//!
+//! Build a shared or static library with
+//! `cargo rustc --release --features capi --crate-type cdylib` (or
+//! `staticlib`). Tables are index-only: the C side keeps its own item array
+//! and maps the returned index into it.
+
+use std::ptr;
+
+use crate::{DropRng, WeightedSampler};
+
+/// Opaque handle owned by the C caller; release with [`droptable_free`].
+pub struct DropTableHandle {
+    alias: WeightedSampler,
+}
+
+/// Build a table from `n` weights. Returns `NULL` if `weights` is `NULL` or
+/// the weights are rejected (empty, negative, zero or non-finite sum).
+///
+/// # Safety
+/// `weights` must point to `n` readable `f32`s (or be `NULL`).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn droptable_new(weights: *const f32, n: usize) -> *mut DropTableHandle {
+    if weights.is_null() {
+        return ptr::null_mut();
+    }
+    // SAFETY: caller guarantees `n` readable f32s.
+    let weights = unsafe { std::slice::from_raw_parts(weights, n) };
+    match WeightedSampler::new(weights) {
+        Ok(alias) => Box::into_raw(Box::new(DropTableHandle { alias })),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Sample an index in `0..n` using a [`DropRng`] seeded with `seed`; the
+/// same handle and seed always give the same index. Returns `SIZE_MAX` for
+/// a `NULL` handle.
+///
+/// # Safety
+/// `handle` must come from [`droptable_new`] and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn droptable_sample(handle: *const DropTableHandle, seed: u64) -> usize {
+    // SAFETY: caller guarantees a live handle or NULL.
+    match unsafe { handle.as_ref() } {
+        Some(h) => h.alias.sample_index(&mut DropRng::seeded(seed)),
+        None => usize::MAX,
+    }
+}
+
+/// Number of entries in the table, or 0 for a `NULL` handle.
+///
+/// # Safety
+/// As [`droptable_sample`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn droptable_len(handle: *const DropTableHandle) -> usize {
+    // SAFETY: caller guarantees a live handle or NULL.
+    unsafe { handle.as_ref() }.map_or(0, |h| h.alias.len())
+}
+
+/// Release a table. `NULL` is ignored.
+///
+/// # Safety
+/// `handle` must come from [`droptable_new`] and not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn droptable_free(handle: *mut DropTableHandle) {
+    if !handle.is_null() {
+        // SAFETY: the handle was produced by Box::into_raw in droptable_new.
+        drop(unsafe { Box::from_raw(handle) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_through_c_abi() {
+        let weights = [0.0f32, 2.0, 0.0];
+        unsafe {
+            let h = droptable_new(weights.as_ptr(), weights.len());
+            assert!(!h.is_null());
+            assert_eq!(droptable_len(h), 3);
+            for seed in 0..20 {
+                assert_eq!(droptable_sample(h, seed), 1);
+            }
+            droptable_free(h);
+
+            assert!(droptable_new([-1.0f32].as_ptr(), 1).is_null());
+            assert!(droptable_new(ptr::null(), 0).is_null());
+            assert_eq!(droptable_sample(ptr::null(), 0), usize::MAX);
+        }
+    }
+}
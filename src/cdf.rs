@@ -0,0 +1,104 @@
+use rand::{Rng, RngCore};
+
+use crate::error::ProbError;
+use crate::walker::validate_weights;
+use crate::{Bucket, IndexSampler};
+
+/// Cumulative-distribution sampler: one uniform draw plus a binary search.
+/// **O(log n)** per sample, but only one RNG call and a compact table, which
+/// beats the alias method for small `n`.
+///
+/// The cumulative distribution is stored as 53-bit cut points, the same
+/// resolution as the alias table's thresholds, so a `1e-9` weight is drawn
+/// about as often here as from a [`WeightedSampler`](crate::WeightedSampler).
+#[derive(Debug, Clone)]
+pub struct CdfSampler {
+    cdf: Vec<u64>,
+}
+
+impl CdfSampler {
+    /// Build from non-negative weights.
+    ///
+    /// # Errors
+    /// Same as [`WeightedSampler::new`](crate::WeightedSampler::new).
+    pub fn new(weights: &[f32]) -> Result<Self, ProbError> {
+        let sum = validate_weights(weights)?;
+        let mut acc = 0.0f64;
+        let mut cdf: Vec<u64> = weights
+            .iter()
+            .map(|&w| {
+                acc += f64::from(w);
+                ((acc / sum).min(1.0) * Bucket::ONE as f64).round() as u64
+            })
+            .collect();
+        // from the last non-zero entry on, the total is exactly one, so
+        // every draw lands somewhere
+        let last = weights.iter().rposition(|&w| w > 0.0).unwrap_or(0);
+        cdf[last..].fill(Bucket::ONE);
+        Ok(Self { cdf })
+    }
+
+    /// The distribution this sampler actually draws from, in index order.
+    /// Differs from the input weights only by rounding of the cut points to
+    /// multiples of 2⁻⁵³.
+    pub fn probabilities(&self) -> Vec<f64> {
+        let mut prev = 0;
+        self.cdf
+            .iter()
+            .map(|&c| {
+                let p = (c - prev) as f64 / Bucket::ONE as f64;
+                prev = c;
+                p
+            })
+            .collect()
+    }
+}
+
+impl IndexSampler for CdfSampler {
+    #[inline]
+    fn len(&self) -> usize {
+        self.cdf.len()
+    }
+    #[inline]
+    fn sample_index<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let u = rng.next_u64() >> 11;
+        self.cdf.partition_point(|&c| c <= u)
+    }
+    #[inline]
+    fn sample_index_dyn(&self, rng: &mut dyn RngCore) -> usize {
+        self.sample_index(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn cdf_skips_zero_weights() {
+        let cdf = CdfSampler::new(&[0.0, 1.0, 0.0]).unwrap();
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..1000 {
+            assert_eq!(cdf.sample_index(&mut rng), 1);
+        }
+    }
+
+    #[test]
+    fn auto_keeps_rare_odds_like_alias() {
+        use crate::WeightedSampler;
+        use crate::kind::{SamplerKind, TableSampler};
+
+        for weights in [[1.0, 1e-9], [1.0, 1e-8], [3.0, 1e-7]] {
+            let TableSampler::Cdf(cdf) = TableSampler::build(&weights, SamplerKind::Auto).unwrap()
+            else {
+                panic!("Auto should pick a CDF for {weights:?}");
+            };
+            let alias = WeightedSampler::new(&weights).unwrap().probabilities();
+            for (c, a) in cdf.probabilities().iter().zip(&alias) {
+                assert!((c - a).abs() < 1e-15, "{weights:?}: {c} vs {a}");
+            }
+            assert!(cdf.probabilities()[1] > 0.0);
+        }
+    }
+}
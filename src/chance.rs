@@ -0,0 +1,108 @@
+use rand::Rng;
+
+use crate::binomial::binomial;
+use crate::error::ProbError;
+
+/// Odds of a single independent event, e.g. a pet dropping at 1/5000.
+///
+/// Besides [`roll`](Self::roll), it samples the two questions people
+/// usually answer with a Bernoulli loop, in O(1) instead:
+///
+/// ```
+/// use droptables::Chance;
+///
+/// let pet = Chance::one_in(5_000).unwrap();
+/// let mut rng = rand::rng();
+/// let kc = pet.geometric_rolls_until_hit(&mut rng).unwrap(); // kills until the pet
+/// let pets = pet.binomial_hits(&mut rng, 10_000); // pets in 10k kills
+/// assert!(kc >= 1);
+/// assert!(pets <= 10_000);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Chance {
+    p: f64,
+}
+
+impl Chance {
+    /// # Errors
+    /// [`ProbError::ChanceOutOfRange`] unless `0 <= p <= 1`.
+    pub fn new(p: f64) -> Result<Self, ProbError> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(ProbError::ChanceOutOfRange { value: p });
+        }
+        Ok(Self { p })
+    }
+
+    /// `1 / n`.
+    ///
+    /// # Errors
+    /// [`ProbError::ChanceOutOfRange`] if `n == 0`.
+    pub fn one_in(n: u64) -> Result<Self, ProbError> {
+        if n == 0 {
+            return Err(ProbError::ChanceOutOfRange {
+                value: f64::INFINITY,
+            });
+        }
+        Self::new(1.0 / n as f64)
+    }
+
+    pub fn probability(&self) -> f64 {
+        self.p
+    }
+
+    /// A single Bernoulli trial.
+    #[inline]
+    pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> bool {
+        rng.random::<f64>() < self.p
+    }
+
+    /// Number of rolls up to and including the first hit (so at least 1),
+    /// or `None` if the chance is zero. Saturates at `u64::MAX`.
+    pub fn geometric_rolls_until_hit<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<u64> {
+        if self.p <= 0.0 {
+            return None;
+        }
+        if self.p >= 1.0 {
+            return Some(1);
+        }
+        // inversion: U in (0, 1] keeps ln finite
+        let u = 1.0 - rng.random::<f64>();
+        let misses = (u.ln() / (-self.p).ln_1p()).floor();
+        Some((misses as u64).saturating_add(1))
+    }
+
+    /// Number of hits in `n` rolls.
+    pub fn binomial_hits<R: Rng + ?Sized>(&self, rng: &mut R, n: u64) -> u64 {
+        binomial(rng, n, self.p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn geometric_mean_is_one_over_p() {
+        let c = Chance::one_in(200).unwrap();
+        let mut rng = StdRng::seed_from_u64(1346);
+        let n = 50_000;
+        let total: u64 = (0..n)
+            .map(|_| c.geometric_rolls_until_hit(&mut rng).unwrap())
+            .sum();
+        let mean = total as f64 / n as f64;
+        assert!((mean - 200.0).abs() < 5.0, "{mean}");
+
+        assert_eq!(
+            Chance::new(0.0)
+                .unwrap()
+                .geometric_rolls_until_hit(&mut rng),
+            None
+        );
+        assert_eq!(Chance::new(1.0).unwrap().binomial_hits(&mut rng, 7), 7);
+        assert_eq!(
+            Chance::new(1.5),
+            Err(ProbError::ChanceOutOfRange { value: 1.5 })
+        );
+    }
+}
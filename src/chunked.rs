@@ -0,0 +1,280 @@
+//! Weighted sampling over item lists too large to hold in memory.
+//!
+//! [`ChunkedDropTable`] reads a file with one `name:weight` entry per line
+//! (blank lines and lines starting with `#` are skipped; the name is
+//! everything before the *last* `:`) and splits it into fixed-size chunks.
+//! Each chunk gets its own alias table, and a top-level table picks a chunk
+//! by its total weight, so a draw is two O(1) steps. Names stay on disk:
+//! RAM holds one bucket and one file offset per item (24 bytes), and only
+//! the drawn name is read back.
+//!
+//! ```no_run
+//! use droptables::chunked::ChunkedDropTable;
+//!
+//! let catalog = ChunkedDropTable::open("catalog.txt").unwrap();
+//! let pick = catalog.sample(&mut rand::rng()).unwrap();
+//! ```
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use rand::Rng;
+
+use crate::error::ProbError;
+use crate::odds::parse_weight;
+use crate::walker::WeightedSampler;
+
+/// Entries per chunk used by [`ChunkedDropTable::open`].
+pub const DEFAULT_CHUNK_SIZE: usize = 1 << 16;
+
+/// Why building a [`ChunkedDropTable`] failed.
+#[derive(Debug)]
+pub enum ChunkedError {
+    Io(io::Error),
+    /// The 1-based line is not `name:weight` with a numeric weight.
+    BadLine {
+        line: usize,
+    },
+    /// A weight was rejected, or every weight is zero.
+    Table(ProbError),
+}
+
+impl fmt::Display for ChunkedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChunkedError::Io(e) => write!(f, "read failed: {e}"),
+            ChunkedError::BadLine { line } => write!(f, "{line}: expected `name:weight`"),
+            ChunkedError::Table(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ChunkedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ChunkedError::Io(e) => Some(e),
+            ChunkedError::BadLine { .. } => None,
+            ChunkedError::Table(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for ChunkedError {
+    fn from(e: io::Error) -> Self {
+        ChunkedError::Io(e)
+    }
+}
+
+/// `(name, weight)` of an entry line, or `None` for lines to skip.
+fn split_entry(line: &str) -> Option<Result<(&str, f32), ()>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let entry = line
+        .rsplit_once(':')
+        .map(|(name, w)| (name.trim(), parse_weight(w)))
+        .and_then(|(name, w)| Some((name, w.ok()?)))
+        .filter(|(name, _)| !name.is_empty());
+    Some(entry.ok_or(()))
+}
+
+/// A drop table over an on-disk item list; see the [module docs](self).
+#[derive(Debug)]
+pub struct ChunkedDropTable {
+    path: PathBuf,
+    reader: Mutex<BufReader<File>>,
+    top: WeightedSampler,
+    /// `None` for chunks whose weights are all zero; `top` never picks them.
+    chunks: Vec<Option<WeightedSampler>>,
+    chunk_size: usize,
+    /// Byte offset of each entry's line.
+    offsets: Vec<u64>,
+}
+
+impl ChunkedDropTable {
+    /// [`open_with_chunk_size`](Self::open_with_chunk_size) with
+    /// [`DEFAULT_CHUNK_SIZE`].
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ChunkedError> {
+        Self::open_with_chunk_size(path, DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Stream `path` once, building one alias table per `chunk_size`
+    /// entries; only one chunk's weights are buffered at a time.
+    ///
+    /// # Errors
+    /// * [`ChunkedError::Io`] if the file can't be read
+    /// * [`ChunkedError::BadLine`] for a malformed entry
+    /// * [`ChunkedError::Table`] for a negative or non-finite weight, a
+    ///   chunk too large for an alias table, or no positive weight at all
+    ///
+    /// # Panics
+    /// If `chunk_size` is zero.
+    pub fn open_with_chunk_size(
+        path: impl AsRef<Path>,
+        chunk_size: usize,
+    ) -> Result<Self, ChunkedError> {
+        assert!(chunk_size > 0, "chunk_size must be positive");
+        let path = path.as_ref().to_path_buf();
+        let mut reader = BufReader::new(File::open(&path)?);
+
+        let mut offsets = Vec::new();
+        let mut chunks = Vec::new();
+        let mut totals: Vec<f32> = Vec::new();
+        let mut pending: Vec<f32> = Vec::with_capacity(chunk_size);
+        let mut flush = |pending: &mut Vec<f32>| -> Result<(), ChunkedError> {
+            match WeightedSampler::new(pending) {
+                Ok(s) => {
+                    let total: f64 = pending.iter().map(|&w| f64::from(w)).sum();
+                    chunks.push(Some(s));
+                    totals.push(total as f32);
+                }
+                Err(ProbError::ZeroSum) if pending.iter().all(|&w| w == 0.0) => {
+                    chunks.push(None);
+                    totals.push(0.0);
+                }
+                Err(e) => return Err(ChunkedError::Table(e)),
+            }
+            pending.clear();
+            Ok(())
+        };
+
+        let (mut line, mut offset, mut line_no) = (String::new(), 0u64, 0usize);
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 {
+                break;
+            }
+            line_no += 1;
+            if let Some(entry) = split_entry(&line) {
+                let (_, w) = entry.map_err(|()| ChunkedError::BadLine { line: line_no })?;
+                offsets.push(offset);
+                pending.push(w);
+                if pending.len() == chunk_size {
+                    flush(&mut pending)?;
+                }
+            }
+            offset += read as u64;
+        }
+        if !pending.is_empty() {
+            flush(&mut pending)?;
+        }
+        if offsets.is_empty() {
+            return Err(ChunkedError::Table(ProbError::Empty));
+        }
+        let top = WeightedSampler::new(&totals).map_err(ChunkedError::Table)?;
+        Ok(Self {
+            path,
+            reader: Mutex::new(reader),
+            top,
+            chunks,
+            chunk_size,
+            offsets,
+        })
+    }
+
+    /// Draw an entry's index (its position among the file's entries) in
+    /// O(1), without touching the file.
+    pub fn sample_index<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let c = self.top.sample_index(rng);
+        let chunk = self.chunks[c]
+            .as_ref()
+            .expect("zero-weight chunks are never drawn");
+        c * self.chunk_size + chunk.sample_index(rng)
+    }
+
+    /// Draw an entry and read its name from disk.
+    ///
+    /// # Errors
+    /// If reading the file fails, or it changed since it was opened.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> io::Result<String> {
+        self.name(self.sample_index(rng))
+    }
+
+    /// Read the name of entry `index` from disk.
+    ///
+    /// # Errors
+    /// If reading the file fails, or it changed since it was opened.
+    ///
+    /// # Panics
+    /// If `index >= len()`.
+    pub fn name(&self, index: usize) -> io::Result<String> {
+        let mut reader = self.reader.lock().unwrap_or_else(|e| e.into_inner());
+        reader.seek(SeekFrom::Start(self.offsets[index]))?;
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        match split_entry(&line) {
+            Some(Ok((name, _))) => Ok(name.to_owned()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "entry line changed since the table was built",
+            )),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn samples_across_chunks_by_weight() {
+        let path =
+            std::env::temp_dir().join(format!("droptables-chunked-{}.txt", std::process::id()));
+        let mut src = String::from("# catalog\n");
+        for i in 0..10 {
+            // chunk 1 (entries 3..6) is all zero; entry 7 weighs 10
+            let w = match i {
+                7 => 10,
+                3..=5 => 0,
+                _ => 1,
+            };
+            src.push_str(&format!("urn:item:{i}: {w}\n\n"));
+        }
+        fs::write(&path, &src).unwrap();
+
+        let table = ChunkedDropTable::open_with_chunk_size(&path, 3).unwrap();
+        assert_eq!((table.len(), table.chunk_count()), (10, 4));
+        assert_eq!(table.name(7).unwrap(), "urn:item:7");
+
+        let mut rng = rand::rng();
+        let mut counts = [0usize; 10];
+        for _ in 0..20_000 {
+            counts[table.sample_index(&mut rng)] += 1;
+        }
+        assert_eq!(counts[3..6], [0, 0, 0]);
+        assert!(
+            (counts[7] as f64 / 20_000.0 - 10.0 / 16.0).abs() < 0.02,
+            "{counts:?}"
+        );
+        assert!(table.sample(&mut rng).unwrap().starts_with("urn:item:"));
+
+        fs::write(&path, "a: 1\nb 2\n").unwrap();
+        assert!(matches!(
+            ChunkedDropTable::open(&path),
+            Err(ChunkedError::BadLine { line: 2 })
+        ));
+        fs::remove_file(&path).unwrap();
+    }
+}
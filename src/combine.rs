@@ -0,0 +1,120 @@
+//! Building tables out of other tables: base + seasonal + event layers.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::DropTable;
+use crate::error::ProbError;
+
+/// Raw weight per item, summed over duplicates, in first-seen order.
+fn summed<'a, T: Eq + Hash + 'a>(
+    tables: impl IntoIterator<Item = &'a DropTable<T>>,
+) -> (Vec<&'a T>, HashMap<&'a T, f64>) {
+    let mut order = Vec::new();
+    let mut weights = HashMap::new();
+    for table in tables {
+        for (item, w) in table.iter() {
+            *weights.entry(item).or_insert_with(|| {
+                order.push(item);
+                0.0
+            }) += f64::from(w);
+        }
+    }
+    (order, weights)
+}
+
+impl<T: Eq + Hash + Clone> DropTable<T> {
+    /// Every item of both tables, with **raw weights** summed for items they
+    /// share (and for duplicates within either table). Items keep `self`'s
+    /// order, then `other`'s new ones follow.
+    ///
+    /// Weights are added as they are, not as probabilities, so a table's
+    /// share of the union is its total weight over both totals; use
+    /// [`scaled`](Self::scaled) to set it.
+    ///
+    /// ```
+    /// use droptables::DropTable;
+    ///
+    /// let base = DropTable::from_pairs([("bones", 90.0), ("coins", 10.0)]).unwrap();
+    /// let event = DropTable::from_pairs([("pumpkin", 1.0), ("coins", 1.0)]).unwrap();
+    /// let halloween = base.union_with(&event.scaled(10.0).unwrap()).unwrap();
+    /// assert_eq!(halloween.weights(), [90.0, 20.0, 10.0]);
+    /// ```
+    ///
+    /// # Errors
+    /// Whatever [`from_pairs`](Self::from_pairs) reports for the sums (e.g.
+    /// [`ProbError::ZeroSum`] if they overflow).
+    pub fn union_with(&self, other: &DropTable<T>) -> Result<DropTable<T>, ProbError> {
+        let (order, weights) = summed([self, other]);
+        DropTable::from_pairs(
+            order
+                .into_iter()
+                .map(|item| (item.clone(), weights[item] as f32)),
+        )
+    }
+
+    /// Only the items both tables can produce, weighted by the product of
+    /// their probabilities in each: the distribution of the result given
+    /// that independent rolls on both tables agree. Items keep `self`'s
+    /// order.
+    ///
+    /// # Errors
+    /// [`ProbError::Empty`] if the tables share no item, or
+    /// [`ProbError::ZeroSum`] if every shared item has zero weight in one of
+    /// them.
+    pub fn intersection_with(&self, other: &DropTable<T>) -> Result<DropTable<T>, ProbError> {
+        let (order, ours) = summed([self]);
+        let (_, theirs) = summed([other]);
+        let (our_total, their_total) = (self.total_weight(), other.total_weight());
+        DropTable::from_pairs(order.into_iter().filter_map(|item| {
+            let p = ours[item] / our_total * theirs.get(item)? / their_total;
+            Some((item.clone(), p as f32))
+        }))
+    }
+
+    /// The same table with every weight multiplied by `factor`. Sampling is
+    /// unchanged; the point is the table's share in a
+    /// [`union_with`](Self::union_with).
+    ///
+    /// # Errors
+    /// [`ProbError::Negative`] for a negative `factor`, or
+    /// [`ProbError::ZeroSum`] if it is zero or not finite.
+    pub fn scaled(&self, factor: f32) -> Result<DropTable<T>, ProbError> {
+        DropTable::from_pairs(self.iter().map(|(item, w)| (item.clone(), w * factor)))
+    }
+
+    fn total_weight(&self) -> f64 {
+        self.weights().iter().map(|&w| f64::from(w)).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_sums_and_intersection_multiplies() {
+        let base = DropTable::from_pairs([("a", 2.0), ("b", 1.0), ("a", 1.0)]).unwrap();
+        let event = DropTable::from_pairs([("c", 4.0), ("b", 2.0)]).unwrap();
+
+        let union = base.union_with(&event).unwrap();
+        assert_eq!(union.items(), ["a", "b", "c"]);
+        assert_eq!(union.weights(), [3.0, 3.0, 4.0]);
+
+        // P(a) = 3/4 and 0, P(b) = 1/4 and 1/3
+        let both = base.intersection_with(&event).unwrap();
+        assert_eq!(both.items(), ["b"]);
+        let only_a = DropTable::from_pairs([("a", 1.0)]).unwrap();
+        assert!(matches!(
+            only_a.intersection_with(&event),
+            Err(ProbError::Empty)
+        ));
+
+        assert_eq!(event.scaled(0.5).unwrap().weights(), [2.0, 1.0]);
+        assert!(matches!(
+            event.scaled(-1.0),
+            Err(ProbError::Negative { .. })
+        ));
+        assert!(matches!(event.scaled(0.0), Err(ProbError::ZeroSum)));
+    }
+}
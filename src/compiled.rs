@@ -0,0 +1,342 @@
+//! A flat, zero-copy alias table format for very large item sets.
+//!
+//! [`CompiledTable::compile`] builds the alias table once (offline) and
+//! writes it to a byte buffer; [`CompiledTable::from_bytes`] opens any
+//! `&[u8]` holding that buffer (a `Vec`, an `include_bytes!`, or a memory
+//! map) in O(1) and samples straight out of it. Nothing is copied or
+//! deserialized, and reads are unaligned so the buffer can sit anywhere.
+//!
+//! ## Layout (little endian)
+//!
+//! | bytes          | contents                                  |
+//! |----------------|-------------------------------------------|
+//! | 4              | magic `b"DTBL"`                           |
+//! | 4              | format version (`u32`, currently 3)       |
+//! | 8              | `n`, the number of items (`u64`)          |
+//! | 4              | the table's [`version`](crate::DropTable::version) (`u32`) |
+//! | 4              | zero                                      |
+//! | `16 * n`       | buckets: `threshold: u64`, `alias: u32`, 4 zero bytes |
+//! | `8 * (n + 1)`  | item offsets into the blob (`u64`)        |
+//! | rest           | item blob; item `i` is `offsets[i]..offsets[i + 1]` |
+//!
+//! Format version 2 buffers, which have no table version field (so the
+//! header is 16 bytes), are still read, with table version 0.
+
+use std::fmt;
+
+use rand::{Rng, RngCore};
+
+use crate::error::ProbError;
+use crate::walker::{WeightedSampler, check_len};
+use crate::{Bucket, IndexSampler};
+
+const MAGIC: &[u8; 4] = b"DTBL";
+const HEADER_LEN: usize = 24;
+/// Header length of format version 2.
+const HEADER_LEN_V2: usize = 16;
+
+/// Why [`CompiledTable::from_bytes`] rejected a buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompiledError {
+    /// The buffer does not start with `DTBL`.
+    BadMagic,
+    /// Written by a newer (or unknown) format version.
+    UnsupportedVersion(u32),
+    /// The header declares zero items.
+    Empty,
+    /// Shorter than its own header says.
+    Truncated { expected: usize, found: usize },
+    /// Item offsets are not non-decreasing or point past the blob
+    /// (only reported by [`CompiledTable::validate`]).
+    BadOffset { index: usize },
+    /// A bucket has a threshold above [`Bucket::ONE`](crate::Bucket::ONE)
+    /// or an alias `>= n`
+    /// (only reported by [`CompiledTable::validate`]).
+    BadBucket { index: usize },
+}
+
+impl fmt::Display for CompiledError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompiledError::BadMagic => write!(f, "not a compiled drop table"),
+            CompiledError::Empty => write!(f, "compiled table has no items"),
+            CompiledError::UnsupportedVersion(v) => write!(f, "unsupported format version {v}"),
+            CompiledError::Truncated { expected, found } => {
+                write!(f, "expected at least {expected} bytes, found {found}")
+            }
+            CompiledError::BadOffset { index } => write!(f, "bad item offset at index {index}"),
+            CompiledError::BadBucket { index } => write!(f, "bad bucket at index {index}"),
+        }
+    }
+}
+
+impl std::error::Error for CompiledError {}
+
+/// An alias table borrowed from a compiled byte buffer; the format is
+/// described at the top of `src/compiled.rs`.
+#[derive(Debug, Clone, Copy)]
+pub struct CompiledTable<'a> {
+    n: usize,
+    version: u32,
+    buckets: &'a [u8],
+    offsets: &'a [u8],
+    blob: &'a [u8],
+}
+
+#[inline]
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+#[inline]
+fn u64_at(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+impl<'a> CompiledTable<'a> {
+    /// Format version written by [`compile`](Self::compile). Version 2
+    /// switched buckets from `f32` probabilities to 53-bit thresholds
+    /// (version 1 buffers are rejected); version 3 added the table version.
+    pub const VERSION: u32 = 3;
+
+    /// Build an alias table over `(item bytes, weight)` pairs and serialize
+    /// it. Items are opaque bytes: ids, UTF-8 names, or your own encoding.
+    ///
+    /// # Errors
+    /// As [`WeightedSampler::new`]; the format stores `u32` aliases, so more
+    /// than `2^32` items is [`ProbError::TooLarge`] even with `u64-alias`.
+    pub fn compile<I, B>(pairs: I) -> Result<Vec<u8>, ProbError>
+    where
+        I: IntoIterator<Item = (B, f32)>,
+        B: AsRef<[u8]>,
+    {
+        Self::compile_with_version(pairs, 0)
+    }
+
+    /// [`compile`](Self::compile), recording `version` as the table's
+    /// version (see [`DropTable::version`](crate::DropTable::version)).
+    ///
+    /// # Errors
+    /// As [`compile`](Self::compile).
+    pub fn compile_with_version<I, B>(pairs: I, version: u32) -> Result<Vec<u8>, ProbError>
+    where
+        I: IntoIterator<Item = (B, f32)>,
+        B: AsRef<[u8]>,
+    {
+        let (items, weights): (Vec<B>, Vec<f32>) = pairs.into_iter().unzip();
+        check_len(items.len(), (u32::MAX as usize).saturating_add(1))?;
+        let alias = WeightedSampler::new(&weights)?;
+        let n = items.len();
+        let blob_len: usize = items.iter().map(|b| b.as_ref().len()).sum();
+
+        let mut out = Vec::with_capacity(HEADER_LEN + 24 * n + 8 + blob_len);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&Self::VERSION.to_le_bytes());
+        out.extend_from_slice(&(n as u64).to_le_bytes());
+        out.extend_from_slice(&version.to_le_bytes());
+        out.extend_from_slice(&[0; 4]);
+        for b in alias.as_buckets() {
+            out.extend_from_slice(&b.threshold().to_le_bytes());
+            out.extend_from_slice(&(b.alias() as u32).to_le_bytes());
+            out.extend_from_slice(&[0; 4]);
+        }
+        let mut offset = 0u64;
+        out.extend_from_slice(&offset.to_le_bytes());
+        for item in &items {
+            offset += item.as_ref().len() as u64;
+            out.extend_from_slice(&offset.to_le_bytes());
+        }
+        for item in &items {
+            out.extend_from_slice(item.as_ref());
+        }
+        Ok(out)
+    }
+
+    /// Open a compiled buffer. Checks the header and section sizes only,
+    /// so this is O(1); call [`validate`](Self::validate) once on untrusted
+    /// input, since a corrupt offset table makes sampling panic.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, CompiledError> {
+        if bytes.len() < HEADER_LEN_V2 {
+            return Err(CompiledError::Truncated {
+                expected: HEADER_LEN_V2,
+                found: bytes.len(),
+            });
+        }
+        if &bytes[..4] != MAGIC {
+            return Err(CompiledError::BadMagic);
+        }
+        let header_len = match u32_at(bytes, 4) {
+            2 => HEADER_LEN_V2,
+            Self::VERSION => HEADER_LEN,
+            other => return Err(CompiledError::UnsupportedVersion(other)),
+        };
+        if bytes.len() < header_len {
+            return Err(CompiledError::Truncated {
+                expected: header_len,
+                found: bytes.len(),
+            });
+        }
+        let version = if header_len == HEADER_LEN {
+            u32_at(bytes, 16)
+        } else {
+            0
+        };
+        let n = u64_at(bytes, 8);
+        if n == 0 {
+            return Err(CompiledError::Empty);
+        }
+        let tables = n
+            .checked_mul(24)
+            .and_then(|t| t.checked_add(8 + header_len as u64))
+            .and_then(|t| usize::try_from(t).ok());
+        let Some(tables_end) = tables else {
+            return Err(CompiledError::Truncated {
+                expected: usize::MAX,
+                found: bytes.len(),
+            });
+        };
+        if bytes.len() < tables_end {
+            return Err(CompiledError::Truncated {
+                expected: tables_end,
+                found: bytes.len(),
+            });
+        }
+        let n = n as usize;
+        let buckets_end = header_len + 16 * n;
+        Ok(Self {
+            n,
+            version,
+            buckets: &bytes[header_len..buckets_end],
+            offsets: &bytes[buckets_end..tables_end],
+            blob: &bytes[tables_end..],
+        })
+    }
+
+    /// Full O(n) consistency check of buckets and offsets.
+    pub fn validate(&self) -> Result<(), CompiledError> {
+        for index in 0..self.n {
+            let threshold = u64_at(self.buckets, 16 * index);
+            let alias = u32_at(self.buckets, 16 * index + 8) as usize;
+            if threshold > Bucket::ONE || alias >= self.n {
+                return Err(CompiledError::BadBucket { index });
+            }
+        }
+        let mut prev = 0;
+        for index in 0..=self.n {
+            let off = u64_at(self.offsets, 8 * index);
+            if off < prev || off > self.blob.len() as u64 {
+                return Err(CompiledError::BadOffset { index });
+            }
+            prev = off;
+        }
+        Ok(())
+    }
+
+    /// Bytes of item `i`.
+    ///
+    /// # Panics
+    /// If `i >= len()` or the offsets are corrupt.
+    #[inline]
+    pub fn item(&self, i: usize) -> &'a [u8] {
+        let lo = u64_at(self.offsets, 8 * i) as usize;
+        let hi = u64_at(self.offsets, 8 * i + 8) as usize;
+        &self.blob[lo..hi]
+    }
+
+    /// Sample an item's bytes.
+    #[inline]
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &'a [u8] {
+        self.item(self.sample_index(rng))
+    }
+
+    /// The table's version, as passed to
+    /// [`compile_with_version`](Self::compile_with_version); `0` for format
+    /// version 2 buffers.
+    pub fn table_version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+}
+
+impl IndexSampler for CompiledTable<'_> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.n
+    }
+    #[inline]
+    fn sample_index<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let i = rng.random_range(0..self.n);
+        let u = rng.next_u64() >> 11;
+        if u < u64_at(self.buckets, 16 * i) {
+            i
+        } else {
+            u32_at(self.buckets, 16 * i + 8) as usize
+        }
+    }
+    #[inline]
+    fn sample_index_dyn(&self, rng: &mut dyn RngCore) -> usize {
+        self.sample_index(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn round_trip_matches_alias_table() {
+        let pairs = [("sword", 1.0), ("", 0.0), ("shield", 3.0)];
+        let bytes = CompiledTable::compile(pairs.map(|(s, w)| (s.as_bytes(), w))).unwrap();
+        let table = CompiledTable::from_bytes(&bytes).unwrap();
+        table.validate().unwrap();
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.item(2), b"shield");
+
+        let alias = WeightedSampler::new(&[1.0, 0.0, 3.0]).unwrap();
+        let (mut a, mut b) = (StdRng::seed_from_u64(5), StdRng::seed_from_u64(5));
+        for _ in 0..1000 {
+            assert_eq!(table.sample_index(&mut a), alias.sample_index(&mut b));
+        }
+        assert_eq!(table.table_version(), 0);
+    }
+
+    #[test]
+    fn keeps_the_table_version_and_reads_format_2() {
+        let pairs = [(b"a", 1.0), (b"b", 2.0)];
+        let bytes = CompiledTable::compile_with_version(pairs, 7).unwrap();
+        let table = CompiledTable::from_bytes(&bytes).unwrap();
+        assert_eq!(table.table_version(), 7);
+
+        // the same table in format 2: no version field
+        let mut old = bytes[..16].to_vec();
+        old[4..8].copy_from_slice(&2u32.to_le_bytes());
+        old.extend_from_slice(&bytes[HEADER_LEN..]);
+        let old = CompiledTable::from_bytes(&old).unwrap();
+        old.validate().unwrap();
+        assert_eq!(old.table_version(), 0);
+        assert_eq!(old.item(1), b"b");
+    }
+
+    #[test]
+    fn rejects_bad_headers() {
+        let bytes = CompiledTable::compile([(b"x", 1.0)]).unwrap();
+        let mut bad = bytes.clone();
+        bad[0] = b'X';
+        assert_eq!(
+            CompiledTable::from_bytes(&bad).unwrap_err(),
+            CompiledError::BadMagic
+        );
+        assert!(matches!(
+            CompiledTable::from_bytes(&bytes[..bytes.len() - 2]),
+            Err(CompiledError::Truncated { .. })
+        ));
+    }
+}
@@ -0,0 +1,316 @@
+use std::fmt;
+
+use rand::Rng;
+
+use crate::DropTable;
+
+type Predicate<T> = Box<dyn Fn(&T) -> bool + Send + Sync>;
+
+struct Bound<T> {
+    min: usize,
+    max: usize,
+    matches: Predicate<T>,
+}
+
+/// Per-category count limits over a batch of rolls, such as "at least one
+/// 4-star in every 10 pulls" or "no more than two duplicates of the banner
+/// item". A category is any predicate over items; categories may overlap.
+///
+/// Use with [`roll_with_constraints`] or [`roll_conditioned`].
+pub struct ConstraintSet<T> {
+    bounds: Vec<Bound<T>>,
+}
+
+impl<T> fmt::Debug for ConstraintSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranges: Vec<_> = self.bounds.iter().map(|b| b.min..=b.max).collect();
+        f.debug_struct("ConstraintSet")
+            .field("bounds", &ranges)
+            .finish()
+    }
+}
+
+impl<T> Default for ConstraintSet<T> {
+    fn default() -> Self {
+        Self { bounds: Vec::new() }
+    }
+}
+
+impl<T> ConstraintSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// At least `n` results matching `category`.
+    pub fn at_least<F>(self, n: usize, category: F) -> Self
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.between(n, usize::MAX, category)
+    }
+
+    /// At most `n` results matching `category`.
+    pub fn at_most<F>(self, n: usize, category: F) -> Self
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.between(0, n, category)
+    }
+
+    /// Between `min` and `max` (inclusive) results matching `category`.
+    pub fn between<F>(mut self, min: usize, max: usize, category: F) -> Self
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.bounds.push(Bound {
+            min,
+            max,
+            matches: Box::new(category),
+        });
+        self
+    }
+
+    fn counts(&self, results: &[&T]) -> Vec<usize> {
+        self.bounds
+            .iter()
+            .map(|b| results.iter().filter(|r| (b.matches)(r)).count())
+            .collect()
+    }
+
+    /// Whether `results` satisfies every bound.
+    pub fn is_satisfied(&self, results: &[&T]) -> bool {
+        self.bounds
+            .iter()
+            .zip(self.counts(results))
+            .all(|(b, c)| (b.min..=b.max).contains(&c))
+    }
+}
+
+/// The constraints could not be met.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintError {
+    /// No reroll could fix the bound at this index (in the order added);
+    /// the bounds conflict or the table can't produce the items they need.
+    Unsatisfiable { bound: usize },
+    /// [`roll_conditioned`] used up its attempts.
+    Exhausted { attempts: usize },
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintError::Unsatisfiable { bound } => {
+                write!(f, "constraint {bound} can't be satisfied")
+            }
+            ConstraintError::Exhausted { attempts } => {
+                write!(f, "no roll met the constraints in {attempts} attempts")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConstraintError {}
+
+/// Draw from `table` among the items `allowed` accepts, by weight, in one
+/// O(n) pass.
+pub(crate) fn draw_masked<T, R: Rng + ?Sized>(
+    table: &DropTable<T>,
+    rng: &mut R,
+    allowed: impl Fn(&T) -> bool,
+) -> Option<usize> {
+    draw_masked_index(table.weights(), rng, |i| allowed(&table.items()[i]))
+}
+
+/// [`draw_masked`] by index, over raw `weights`.
+pub(crate) fn draw_masked_index<R: Rng + ?Sized>(
+    weights: &[f32],
+    rng: &mut R,
+    allowed: impl Fn(usize) -> bool,
+) -> Option<usize> {
+    let mass = |i: usize| {
+        if allowed(i) {
+            f64::from(weights[i])
+        } else {
+            0.0
+        }
+    };
+    let total: f64 = (0..weights.len()).map(mass).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let mut u = rng.random::<f64>() * total;
+    let mut last = None;
+    for i in 0..weights.len() {
+        let m = mass(i);
+        if m > 0.0 {
+            if u < m {
+                return Some(i);
+            }
+            u -= m;
+            last = Some(i);
+        }
+    }
+    last
+}
+
+/// Roll `table` `n` times, then reroll as few results as possible to meet
+/// `constraints`.
+///
+/// Bounds are fixed in the order added. A bound that is short replaces
+/// results from the *last* roll backwards, skipping results other bounds
+/// still need, with draws from the table restricted to its category (and
+/// away from any category already at its maximum). A bound that is over
+/// replaces its matching results the same way, drawing from outside the
+/// category. Each replacement keeps the table's relative odds within the
+/// allowed items.
+///
+/// The induced distribution is not the table's: results that already meet
+/// the constraints are kept as rolled, and only the replaced positions are
+/// drawn conditionally. It matches a typical "pity" or "guaranteed slot"
+/// system, where the guarantee lands on the last pull. Use
+/// [`roll_conditioned`] for the exact conditional distribution instead.
+///
+/// # Errors
+/// [`ConstraintError::Unsatisfiable`] if a bound can't be met without
+/// breaking another.
+///
+/// ```
+/// use droptables::{ConstraintSet, DropTable, roll_with_constraints};
+///
+/// let gacha = DropTable::from_pairs([("3-star", 94.0), ("4-star", 5.1), ("5-star", 0.9)]).unwrap();
+/// let ten_pull = ConstraintSet::new().at_least(1, |s: &&str| *s != "3-star");
+/// let pulls = roll_with_constraints(&gacha, 10, &ten_pull, &mut rand::rng()).unwrap();
+/// assert!(pulls.iter().any(|s| **s != "3-star"));
+/// ```
+pub fn roll_with_constraints<'a, T, R: Rng + ?Sized>(
+    table: &'a DropTable<T>,
+    n: usize,
+    constraints: &ConstraintSet<T>,
+    rng: &mut R,
+) -> Result<Vec<&'a T>, ConstraintError> {
+    let mut results: Vec<&T> = (0..n).map(|_| table.sample(rng)).collect();
+    let bounds = &constraints.bounds;
+
+    for (bi, bound) in bounds.iter().enumerate() {
+        for pos in (0..n).rev() {
+            let mut counts = constraints.counts(&results);
+            let count = counts[bi];
+            if (bound.min..=bound.max).contains(&count) {
+                break;
+            }
+            let short = count < bound.min;
+            let current = results[pos];
+            if (bound.matches)(current) == short {
+                // already helps (short) or isn't part of the excess (over)
+                continue;
+            }
+            // removing `current` must not take another bound below its min
+            let needed = bounds
+                .iter()
+                .zip(&counts)
+                .enumerate()
+                .any(|(oi, (o, &c))| oi != bi && (o.matches)(current) && c <= o.min);
+            if needed {
+                continue;
+            }
+            for (o, c) in bounds.iter().zip(counts.iter_mut()) {
+                if (o.matches)(current) {
+                    *c -= 1;
+                }
+            }
+            let replacement = draw_masked(table, rng, |item| {
+                (bound.matches)(item) == short
+                    && bounds
+                        .iter()
+                        .zip(&counts)
+                        .all(|(o, &c)| !(o.matches)(item) || c < o.max)
+            });
+            if let Some(i) = replacement {
+                results[pos] = &table.items()[i];
+            }
+        }
+        let count = constraints.counts(&results)[bi];
+        if !(bound.min..=bound.max).contains(&count) {
+            return Err(ConstraintError::Unsatisfiable { bound: bi });
+        }
+    }
+    if !constraints.is_satisfied(&results) {
+        let bound = constraints
+            .counts(&results)
+            .iter()
+            .zip(bounds)
+            .position(|(c, b)| !(b.min..=b.max).contains(c))
+            .unwrap_or(0);
+        return Err(ConstraintError::Unsatisfiable { bound });
+    }
+    Ok(results)
+}
+
+/// Roll `table` `n` times, repeating the whole batch until it meets
+/// `constraints`: results follow the table's distribution conditioned on
+/// the constraints exactly, at the cost of up to `max_attempts` batches.
+///
+/// # Errors
+/// [`ConstraintError::Exhausted`] if no batch met the constraints.
+pub fn roll_conditioned<'a, T, R: Rng + ?Sized>(
+    table: &'a DropTable<T>,
+    n: usize,
+    constraints: &ConstraintSet<T>,
+    max_attempts: usize,
+    rng: &mut R,
+) -> Result<Vec<&'a T>, ConstraintError> {
+    for _ in 0..max_attempts {
+        let results: Vec<&T> = (0..n).map(|_| table.sample(rng)).collect();
+        if constraints.is_satisfied(&results) {
+            return Ok(results);
+        }
+    }
+    Err(ConstraintError::Exhausted {
+        attempts: max_attempts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimal_rerolls_meet_min_and_max() {
+        let table =
+            DropTable::from_pairs([("common", 90.0), ("rare", 9.0), ("epic", 1.0)]).unwrap();
+        let set = ConstraintSet::new()
+            .at_least(1, |s: &&str| *s == "epic")
+            .at_most(6, |s: &&str| *s == "common");
+        let mut rng = rand::rng();
+        for _ in 0..500 {
+            let pulls = roll_with_constraints(&table, 10, &set, &mut rng).unwrap();
+            assert_eq!(pulls.len(), 10);
+            assert!(set.is_satisfied(&pulls), "{pulls:?}");
+        }
+
+        let impossible = ConstraintSet::new()
+            .at_least(3, |s: &&str| *s == "epic")
+            .at_most(2, |s: &&str| *s != "common");
+        assert_eq!(
+            roll_with_constraints(&table, 5, &impossible, &mut rng),
+            Err(ConstraintError::Unsatisfiable { bound: 0 })
+        );
+        assert!(matches!(
+            roll_conditioned(&table, 5, &impossible, 50, &mut rng),
+            Err(ConstraintError::Exhausted { attempts: 50 })
+        ));
+    }
+
+    #[test]
+    fn conditioned_keeps_relative_odds() {
+        let table = DropTable::from_pairs([("a", 1.0), ("b", 1.0), ("c", 2.0)]).unwrap();
+        let set = ConstraintSet::new().at_most(0, |s: &&str| *s == "a");
+        let mut rng = rand::rng();
+        let mut c = 0;
+        for _ in 0..5_000 {
+            let r = roll_conditioned(&table, 1, &set, 1_000, &mut rng).unwrap();
+            c += usize::from(*r[0] == "c");
+        }
+        assert!((c as f64 / 5_000.0 - 2.0 / 3.0).abs() < 0.03, "{c}");
+    }
+}
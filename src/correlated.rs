@@ -0,0 +1,146 @@
+use rand::Rng;
+
+use crate::DropTable;
+
+/// Consecutive rolls on one table that are correlated with each other but
+/// individually still follow the table's odds.
+///
+/// Every roll maps a uniform `u` through the table's cumulative weights.
+/// With correlation `c > 0`, each roll reuses the previous `u` with
+/// probability `c` (streaks); with `c < 0` it uses `1 - u` with probability
+/// `|c|`, landing at the opposite end of the table (streak-breaking).
+/// Otherwise `u` is fresh. All three are uniform, so the per-roll
+/// distribution is exactly the table's; only the sequence changes.
+///
+/// Items are ordered as inserted, so "opposite end" means the other end of
+/// that order: list commons first and rares last (or the reverse) for
+/// `c < 0` to mean "a common after a rare".
+///
+/// ```
+/// use droptables::DropTable;
+///
+/// let table = DropTable::from_pairs([("common", 90.0), ("rare", 10.0)]).unwrap();
+/// let mut rolls = table.correlated(-0.8);
+/// let mut rng = rand::rng();
+/// let loot: Vec<_> = (0..10).map(|_| *rolls.sample(&mut rng)).collect();
+/// assert_eq!(loot.len(), 10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CorrelatedRolls<'a, T> {
+    table: &'a DropTable<T>,
+    cdf: Vec<f64>,
+    correlation: f64,
+    last: Option<f64>,
+}
+
+impl<'a, T> CorrelatedRolls<'a, T> {
+    /// # Panics
+    /// If `correlation` is not in `[-1, 1]`.
+    pub fn new(table: &'a DropTable<T>, correlation: f64) -> Self {
+        assert!(
+            (-1.0..=1.0).contains(&correlation),
+            "correlation {correlation} is outside [-1, 1]"
+        );
+        let mut acc = 0.0f64;
+        let cdf = table
+            .weights()
+            .iter()
+            .map(|&w| {
+                acc += f64::from(w);
+                acc
+            })
+            .collect();
+        Self {
+            table,
+            cdf,
+            correlation,
+            last: None,
+        }
+    }
+
+    pub fn correlation(&self) -> f64 {
+        self.correlation
+    }
+
+    /// Forget the previous roll; the next one is independent.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+
+    /// Roll an index; see the [type docs](Self) for how it relates to the
+    /// previous roll.
+    pub fn sample_index<R: Rng + ?Sized>(&mut self, rng: &mut R) -> usize {
+        let u = match self.last {
+            Some(prev) if rng.random::<f64>() < self.correlation.abs() => {
+                if self.correlation > 0.0 {
+                    prev
+                } else {
+                    1.0 - prev
+                }
+            }
+            _ => rng.random(),
+        };
+        self.last = Some(u);
+        let total = self.cdf[self.cdf.len() - 1];
+        let i = self.cdf.partition_point(|&c| c <= u * total);
+        if i < self.cdf.len() {
+            i
+        } else {
+            // `1 - 0.0` hit the very top: take the last non-zero entry
+            self.cdf.partition_point(|&c| c < total)
+        }
+    }
+
+    pub fn sample<R: Rng + ?Sized>(&mut self, rng: &mut R) -> &'a T {
+        &self.table.items()[self.sample_index(rng)]
+    }
+}
+
+impl<T> DropTable<T> {
+    /// Rolls on this table with the given lag-one `correlation` in
+    /// `[-1, 1]`; see [`CorrelatedRolls`].
+    ///
+    /// # Panics
+    /// If `correlation` is not in `[-1, 1]`.
+    pub fn correlated(&self, correlation: f64) -> CorrelatedRolls<'_, T> {
+        CorrelatedRolls::new(self, correlation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    fn streak_rate(correlation: f64, rng: &mut StdRng) -> (f64, f64) {
+        let table = DropTable::from_pairs([("a", 1.0), ("nothing", 0.0), ("b", 1.0)]).unwrap();
+        let mut rolls = table.correlated(correlation);
+        let draws = 50_000;
+        let mut prev = rolls.sample_index(rng);
+        let (mut same, mut a) = (0, 0);
+        for _ in 0..draws {
+            let i = rolls.sample_index(rng);
+            assert_ne!(i, 1);
+            same += usize::from(i == prev);
+            a += usize::from(i == 0);
+            prev = i;
+        }
+        (same as f64 / draws as f64, a as f64 / draws as f64)
+    }
+
+    #[test]
+    fn correlation_changes_streaks_not_odds() {
+        let mut rng = StdRng::seed_from_u64(1366);
+        let (independent, a0) = streak_rate(0.0, &mut rng);
+        let (streaky, a1) = streak_rate(0.6, &mut rng);
+        let (breaking, a2) = streak_rate(-0.6, &mut rng);
+        assert!((independent - 0.5).abs() < 0.02, "{independent}");
+        assert!((streaky - 0.8).abs() < 0.02, "{streaky}");
+        assert!((breaking - 0.2).abs() < 0.02, "{breaking}");
+        for a in [a0, a1, a2] {
+            assert!((a - 0.5).abs() < 0.02, "{a}");
+        }
+        assert_eq!(streak_rate(1.0, &mut rng).0, 1.0);
+        assert_eq!(streak_rate(-1.0, &mut rng).0, 0.0);
+    }
+}
@@ -0,0 +1,429 @@
+//! CSV import/export (feature `csv`).
+//!
+//! Columns are `item,weight[,quantity_min,quantity_max]`. A first row of
+//! exactly those column names (any case) is a header. Fields may be quoted
+//! with `"`, with `""` for a literal quote; blank lines are skipped.
+//! Quantities are kept by `DropTable<CsvRow>`; a `DropTable<String>` only
+//! holds names and rejects them.
+//! An optional first line `#! version = N` sets the table's
+//! [`version`](DropTable::version).
+//!
+//! ```text
+//! item,weight,quantity_min,quantity_max
+//! coins,60,10,250
+//! "Rune scimitar",2,1,1
+//! ```
+
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use droptables_odds::OddsParseError;
+
+use crate::DropTable;
+use crate::error::ProbError;
+use crate::odds::parse_weight;
+use crate::version::version_pragma;
+
+/// One data row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvRow {
+    pub item: String,
+    pub weight: f32,
+    /// `(min, max)` when the quantity columns are present.
+    pub quantity: Option<(u32, u32)>,
+}
+
+/// What went wrong in a row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CsvErrorKind {
+    /// Reading the input failed.
+    Io(io::ErrorKind),
+    /// The input is not valid UTF-8.
+    Utf8,
+    /// A quoted field was never closed.
+    UnterminatedQuote,
+    /// The first line is a `#!` pragma other than `#! version = N`.
+    BadVersion,
+    /// Fewer than two fields, exactly three, or more than four.
+    ColumnCount(usize),
+    EmptyItem,
+    /// The weight field is not a number.
+    BadWeight(String),
+    /// The weight field is written as odds but they don't parse.
+    BadOdds(OddsParseError),
+    /// A quantity is not an integer, or `min > max`.
+    BadQuantity(String),
+    /// Quantity columns read into a table that can't keep them; read into a
+    /// `DropTable<CsvRow>` instead.
+    QuantityNotKept,
+    /// The rows parsed, but the weights were rejected.
+    Table(ProbError),
+}
+
+/// A CSV failure and the 1-based line of the row it refers to (`0` when it
+/// concerns the whole input).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvError {
+    pub kind: CsvErrorKind,
+    pub line: usize,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}: ", self.line)?;
+        }
+        match &self.kind {
+            CsvErrorKind::Io(k) => write!(f, "read failed: {k}"),
+            CsvErrorKind::Utf8 => write!(f, "input is not valid UTF-8"),
+            CsvErrorKind::UnterminatedQuote => write!(f, "unterminated quoted field"),
+            CsvErrorKind::BadVersion => write!(f, "expected `#! version = N`"),
+            CsvErrorKind::ColumnCount(n) => {
+                write!(f, "expected 2 or 4 columns, found {n}")
+            }
+            CsvErrorKind::EmptyItem => write!(f, "item is empty"),
+            CsvErrorKind::BadWeight(s) => write!(f, "weight `{s}` is not a number"),
+            CsvErrorKind::BadOdds(e) => write!(f, "{e}"),
+            CsvErrorKind::BadQuantity(s) => write!(f, "bad quantity `{s}`"),
+            CsvErrorKind::QuantityNotKept => {
+                write!(f, "quantity columns need a DropTable<CsvRow>")
+            }
+            CsvErrorKind::Table(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            CsvErrorKind::Table(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+fn error(kind: CsvErrorKind, line: usize) -> CsvError {
+    CsvError { kind, line }
+}
+
+/// Split `src` into records of fields, each tagged with its starting line.
+fn records(src: &str) -> Result<Vec<(usize, Vec<String>)>, CsvError> {
+    let mut out = Vec::new();
+    let mut chars = src.chars().peekable();
+    let mut line = 1;
+    while chars.peek().is_some() {
+        let start = line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        loop {
+            match chars.next() {
+                None if quoted => return Err(error(CsvErrorKind::UnterminatedQuote, start)),
+                None => break,
+                Some('"') if quoted => {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        field.push('"');
+                    } else {
+                        quoted = false;
+                    }
+                }
+                Some('"') if field.trim().is_empty() => {
+                    field.clear();
+                    quoted = true;
+                }
+                Some(c) if quoted => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+                Some(',') => fields.push(std::mem::take(&mut field)),
+                Some('\n') => {
+                    line += 1;
+                    break;
+                }
+                Some('\r') if chars.peek() == Some(&'\n') => {}
+                Some(c) => field.push(c),
+            }
+        }
+        fields.push(field);
+        if fields.len() == 1 && fields[0].trim().is_empty() {
+            continue;
+        }
+        out.push((start, fields));
+    }
+    Ok(out)
+}
+
+fn row(line: usize, fields: &[String]) -> Result<CsvRow, CsvError> {
+    let n = fields.len();
+    if n != 2 && n != 4 {
+        return Err(error(CsvErrorKind::ColumnCount(n), line));
+    }
+    let item = fields[0].trim();
+    if item.is_empty() {
+        return Err(error(CsvErrorKind::EmptyItem, line));
+    }
+    let w = fields[1].trim();
+    let weight = parse_weight(w).map_err(|e| {
+        let kind = e.map_or_else(
+            || CsvErrorKind::BadWeight(w.to_string()),
+            CsvErrorKind::BadOdds,
+        );
+        error(kind, line)
+    })?;
+    let quantity = if n == 4 {
+        let q = |s: &str| {
+            s.trim()
+                .parse::<u32>()
+                .map_err(|_| error(CsvErrorKind::BadQuantity(s.trim().to_string()), line))
+        };
+        let (lo, hi) = (q(&fields[2])?, q(&fields[3])?);
+        if lo > hi {
+            return Err(error(
+                CsvErrorKind::BadQuantity(format!("{lo} > {hi}")),
+                line,
+            ));
+        }
+        Some((lo, hi))
+    } else {
+        None
+    };
+    Ok(CsvRow {
+        item: item.to_string(),
+        weight,
+        quantity,
+    })
+}
+
+const HEADER: [&str; 4] = ["item", "weight", "quantity_min", "quantity_max"];
+
+/// Whether `fields` are the column names, with or without the quantities.
+fn is_header(fields: &[String]) -> bool {
+    matches!(fields.len(), 2 | 4)
+        && fields
+            .iter()
+            .zip(HEADER)
+            .all(|(f, name)| f.trim().eq_ignore_ascii_case(name))
+}
+
+/// The version pragma (`0` if absent) and the data rows with their lines.
+type Parsed = (u32, Vec<(usize, CsvRow)>);
+
+fn read_rows_with_lines<R: Read>(mut reader: R) -> Result<Parsed, CsvError> {
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|e| error(CsvErrorKind::Io(e.kind()), 0))?;
+    let src = String::from_utf8(bytes).map_err(|_| error(CsvErrorKind::Utf8, 0))?;
+    let mut body = src.as_str();
+    let mut version = 0;
+    if let Some(first) = src.lines().next()
+        && let Some(v) = version_pragma(first)
+    {
+        version = v.ok_or(error(CsvErrorKind::BadVersion, 1))?;
+        // keep the newline so line numbers stay put
+        body = &src[first.len()..];
+    }
+    let mut recs = records(body)?;
+    if recs.first().is_some_and(|(_, f)| is_header(f)) {
+        recs.remove(0);
+    }
+    let rows = recs
+        .iter()
+        .map(|(line, fields)| row(*line, fields).map(|r| (*line, r)))
+        .collect::<Result<_, _>>()?;
+    Ok((version, rows))
+}
+
+/// Parse every data row, keeping the quantity columns.
+///
+/// # Errors
+/// The first malformed row, with its line number.
+pub fn read_rows<R: Read>(reader: R) -> Result<Vec<CsvRow>, CsvError> {
+    Ok(read_rows_with_lines(reader)?
+        .1
+        .into_iter()
+        .map(|(_, r)| r)
+        .collect())
+}
+
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) || field.trim() != field {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Build a table over `item(row)` for every row, reporting weight errors
+/// against the row's line.
+fn table_of<T>(
+    (version, rows): Parsed,
+    item: impl Fn(CsvRow) -> T,
+) -> Result<DropTable<T>, CsvError> {
+    let lines: Vec<usize> = rows.iter().map(|(line, _)| *line).collect();
+    let pairs: Vec<(T, f32)> = rows
+        .into_iter()
+        .map(|(_, r)| {
+            let w = r.weight;
+            (item(r), w)
+        })
+        .collect();
+    DropTable::from_pairs(pairs)
+        .map(|t| t.with_version(version))
+        .map_err(|e| {
+            let line = match &e {
+                ProbError::Negative { index, .. } => lines.get(*index).copied().unwrap_or(0),
+                _ => 0,
+            };
+            error(CsvErrorKind::Table(e), line)
+        })
+}
+
+impl DropTable<String> {
+    /// Build from CSV without quantity columns; read into a
+    /// [`DropTable<CsvRow>`](DropTable::from_csv_rows) to keep them.
+    ///
+    /// # Errors
+    /// A [`CsvError`] naming the offending line, including
+    /// [`CsvErrorKind::QuantityNotKept`] for a row with quantities; weight
+    /// errors that point at a single entry (such as a negative weight)
+    /// report that entry's line.
+    pub fn from_csv_reader<R: Read>(reader: R) -> Result<Self, CsvError> {
+        let parsed = read_rows_with_lines(reader)?;
+        if let Some((line, _)) = parsed.1.iter().find(|(_, r)| r.quantity.is_some()) {
+            return Err(error(CsvErrorKind::QuantityNotKept, *line));
+        }
+        table_of(parsed, |r| r.item)
+    }
+}
+
+impl DropTable<CsvRow> {
+    /// Build from CSV, keeping each row (quantities included) as the item.
+    ///
+    /// # Errors
+    /// As [`DropTable::<String>::from_csv_reader`], less the quantity check.
+    pub fn from_csv_rows<R: Read>(reader: R) -> Result<Self, CsvError> {
+        table_of(read_rows_with_lines(reader)?, |r| r)
+    }
+
+    /// Write the rows back out, quantity columns included, so
+    /// [`from_csv_rows`](Self::from_csv_rows) reads the same table. A
+    /// non-zero [`version`](DropTable::version) is written as a leading
+    /// `#! version = N` line.
+    pub fn to_csv_rows_writer<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        if self.version() != 0 {
+            writeln!(writer, "#! version = {}", self.version())?;
+        }
+        writeln!(writer, "{}", HEADER.join(","))?;
+        for (row, w) in self.iter() {
+            write!(writer, "{},{w}", quote(&row.item))?;
+            if let Some((lo, hi)) = row.quantity {
+                write!(writer, ",{lo},{hi}")?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: fmt::Display> DropTable<T> {
+    /// Write `item,weight` rows with a header, quoting items as needed. A
+    /// non-zero [`version`](DropTable::version) is written as a leading
+    /// `#! version = N` line.
+    pub fn to_csv_writer<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        if self.version() != 0 {
+            writeln!(writer, "#! version = {}", self.version())?;
+        }
+        writeln!(writer, "item,weight")?;
+        for (item, w) in self.iter() {
+            writeln!(writer, "{},{w}", quote(&item.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_with_quotes_and_quantities() {
+        let src = "Item,Weight,quantity_min,quantity_max\r\n\
+                   coins,60,10,250\r\n\
+                   \r\n\
+                   \"Rune, scimitar\",2,1,1\n\
+                   \"the \"\"big\"\" one\",0.5,1,3\n";
+        let rows = read_rows(src.as_bytes()).unwrap();
+        assert_eq!(rows[1].item, "Rune, scimitar");
+        assert_eq!(rows[2].item, "the \"big\" one");
+        assert_eq!(rows[0].quantity, Some((10, 250)));
+
+        let table = DropTable::from_csv_rows(src.as_bytes())
+            .unwrap()
+            .with_version(3);
+        let mut out = Vec::new();
+        table.to_csv_rows_writer(&mut out).unwrap();
+        let again = DropTable::from_csv_rows(out.as_slice()).unwrap();
+        assert_eq!(again.items(), table.items());
+        assert_eq!(again.items()[2].quantity, Some((1, 3)));
+        assert_eq!(again.weights(), table.weights());
+        assert_eq!(again.version(), 3);
+
+        let names = DropTable::from_csv_reader(
+            "item,weight
+coins,60
+\"a, b\",2
+"
+            .as_bytes(),
+        )
+        .unwrap()
+        .with_version(3);
+        let mut out = Vec::new();
+        names.to_csv_writer(&mut out).unwrap();
+        let again = DropTable::from_csv_reader(out.as_slice()).unwrap();
+        assert_eq!(again.items(), names.items());
+        assert_eq!(again.content_hash(), names.content_hash());
+
+        let err = DropTable::from_csv_reader(src.as_bytes()).unwrap_err();
+        assert_eq!((err.kind, err.line), (CsvErrorKind::QuantityNotKept, 2));
+        // an item that happens to be called "item" is data, not a header
+        let t = DropTable::from_csv_reader(
+            "item,5
+rune,1
+"
+            .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(t.items(), ["item", "rune"]);
+    }
+
+    #[test]
+    fn reports_row_lines() {
+        let err = DropTable::from_csv_reader("#! version = 1\na,1\nb,x\n".as_bytes()).unwrap_err();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.kind, CsvErrorKind::BadWeight("x".into()));
+        let err = DropTable::from_csv_reader(
+            "a,1 in 0
+"
+            .as_bytes(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.kind,
+            CsvErrorKind::BadOdds(OddsParseError::ZeroDenominator)
+        );
+
+        let err = DropTable::from_csv_reader("a,1\n\nb,-2\n".as_bytes()).unwrap_err();
+        assert_eq!(err.line, 3);
+        assert!(matches!(
+            err.kind,
+            CsvErrorKind::Table(ProbError::Negative { .. })
+        ));
+
+        let err = read_rows("a,1,5,2\n".as_bytes()).unwrap_err();
+        assert_eq!(err.kind, CsvErrorKind::BadQuantity("5 > 2".into()));
+    }
+}
@@ -0,0 +1,208 @@
+//! Tables with limited stock: every draw consumes one unit of the chosen
+//! entry, and exhausted entries drop out of the distribution.
+//!
+//! Backed by a Fenwick (binary indexed) tree over the live weights, so a
+//! draw and the renormalization that follows are both **O(log n)** instead
+//! of an O(n) alias rebuild.
+
+use rand::Rng;
+
+use crate::NegativeZero;
+use crate::error::ProbError;
+
+/// A drop table whose entries run out.
+///
+/// Each entry keeps its weight while it has stock left; once its stock hits
+/// zero it can no longer be drawn. Useful for card packs, limited rewards
+/// and raffles.
+#[derive(Debug, Clone)]
+pub struct DepletableDropTable<T> {
+    items: Vec<T>,
+    weights: Vec<f32>,
+    stock: Vec<u32>,
+    tree: Vec<f64>,
+}
+
+impl<T> DepletableDropTable<T> {
+    /// Build from `(item, weight, stock)` triples.
+    ///
+    /// # Errors
+    /// * [`ProbError::Empty`] if there are no entries.
+    /// * [`ProbError::Negative`] if any weight is negative.
+    /// * [`ProbError::ZeroSum`] if no entry with stock has positive finite weight.
+    pub fn from_triples<I>(triples: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = (T, f32, u32)>,
+    {
+        let mut items = Vec::new();
+        let mut weights = Vec::new();
+        let mut stock = Vec::new();
+        for (i, (t, w, s)) in triples.into_iter().enumerate() {
+            NegativeZero::default().check(i, w)?;
+            items.push(t);
+            weights.push(w);
+            stock.push(s);
+        }
+        if items.is_empty() {
+            return Err(ProbError::Empty);
+        }
+        let mut table = Self {
+            items,
+            weights,
+            stock,
+            tree: Vec::new(),
+        };
+        table.rebuild();
+        let total = table.total_weight();
+        if !total.is_finite() || total == 0.0 {
+            return Err(ProbError::ZeroSum);
+        }
+        Ok(table)
+    }
+
+    fn live_weight(&self, i: usize) -> f64 {
+        if self.stock[i] > 0 {
+            self.weights[i] as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// O(n) Fenwick construction from the live weights.
+    fn rebuild(&mut self) {
+        let n = self.items.len();
+        self.tree = vec![0.0; n + 1];
+        for i in 0..n {
+            let k = i + 1;
+            self.tree[k] += self.live_weight(i);
+            let parent = k + (k & k.wrapping_neg());
+            if parent <= n {
+                self.tree[parent] += self.tree[k];
+            }
+        }
+    }
+
+    fn add(&mut self, i: usize, delta: f64) {
+        let mut k = i + 1;
+        while k < self.tree.len() {
+            self.tree[k] += delta;
+            k += k & k.wrapping_neg();
+        }
+    }
+
+    /// Smallest index whose prefix sum exceeds `target`.
+    fn find(&self, mut target: f64) -> usize {
+        let n = self.items.len();
+        let mut pos = 0;
+        let mut step = n.next_power_of_two();
+        while step > 0 {
+            let next = pos + step;
+            if next <= n && self.tree[next] <= target {
+                pos = next;
+                target -= self.tree[next];
+            }
+            step >>= 1;
+        }
+        pos.min(n - 1)
+    }
+
+    /// Sum of the weights that can still be drawn.
+    pub fn total_weight(&self) -> f64 {
+        let mut k = self.items.len();
+        let mut sum = 0.0;
+        while k > 0 {
+            sum += self.tree[k];
+            k &= k - 1;
+        }
+        sum
+    }
+
+    /// Draw one item and consume a unit of its stock. **O(log n)**.
+    ///
+    /// Returns `None` once every entry is exhausted (or only zero-weight
+    /// entries remain).
+    pub fn sample<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<&T> {
+        let i = self.sample_index(rng)?;
+        Some(&self.items[i])
+    }
+
+    /// Like [`sample`](Self::sample), but returns the entry index.
+    pub fn sample_index<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<usize> {
+        for _ in 0..2 {
+            let total = self.total_weight();
+            if total <= 0.0 {
+                return None;
+            }
+            let i = self.find(rng.random::<f64>() * total);
+            if self.live_weight(i) > 0.0 {
+                self.stock[i] -= 1;
+                if self.stock[i] == 0 {
+                    self.add(i, -(self.weights[i] as f64));
+                }
+                return Some(i);
+            }
+            // floating-point drift let a dead entry through; resync and retry
+            self.rebuild();
+        }
+        None
+    }
+
+    /// Add `amount` units of stock to entry `i`.
+    ///
+    /// # Panics
+    /// If `i` is out of range.
+    pub fn restock(&mut self, i: usize, amount: u32) {
+        let was_empty = self.stock[i] == 0;
+        self.stock[i] = self.stock[i].saturating_add(amount);
+        if was_empty && self.stock[i] > 0 {
+            self.add(i, self.weights[i] as f64);
+        }
+    }
+
+    /// Remaining stock of entry `i`.
+    pub fn remaining(&self, i: usize) -> u32 {
+        self.stock[i]
+    }
+
+    /// Whether nothing can be drawn any more. **O(n)**.
+    pub fn is_depleted(&self) -> bool {
+        (0..self.items.len()).all(|i| self.live_weight(i) == 0.0)
+    }
+
+    /// Items in insertion order (including exhausted ones).
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Number of entries (including exhausted ones).
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether the table has no entries at all.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn draws_exactly_the_stock() {
+        let mut table =
+            DepletableDropTable::from_triples([("a", 1.0, 3), ("b", 100.0, 2), ("c", 5.0, 0)])
+                .unwrap();
+        let mut rng = StdRng::seed_from_u64(19);
+        let mut drawn: Vec<&str> = std::iter::from_fn(|| table.sample(&mut rng).copied()).collect();
+        drawn.sort();
+        assert_eq!(drawn, ["a", "a", "a", "b", "b"]);
+        assert!(table.is_depleted());
+
+        table.restock(2, 1);
+        assert_eq!(table.sample(&mut rng), Some(&"c"));
+        assert_eq!(table.sample(&mut rng), None);
+    }
+}
@@ -0,0 +1,114 @@
+//! Comparing two tables: which items were added or removed and how every
+//! shared item's probability moved.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+use crate::DropTable;
+
+/// The difference between two drop tables, produced by [`DropTable::diff`].
+///
+/// Probabilities are normalized per table; duplicate items are summed.
+/// Entries follow the insertion order of the tables they come from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDiff<T> {
+    /// Items only in the new table, with their probability.
+    pub added: Vec<(T, f64)>,
+    /// Items only in the old table, with their former probability.
+    pub removed: Vec<(T, f64)>,
+    /// Items in both tables whose probability changed: `(item, old, new)`.
+    pub changed: Vec<(T, f64, f64)>,
+}
+
+impl<T> TableDiff<T> {
+    /// Whether both tables describe the same distribution.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Per-item probability, summed over duplicates, in first-seen order.
+fn merged<T: Eq + Hash>(table: &DropTable<T>) -> (Vec<&T>, HashMap<&T, f64>) {
+    let mut order = Vec::new();
+    let mut probs = HashMap::new();
+    for (item, p) in table.items().iter().zip(table.probabilities()) {
+        *probs.entry(item).or_insert_with(|| {
+            order.push(item);
+            0.0
+        }) += p;
+    }
+    (order, probs)
+}
+
+impl<T: Eq + Hash> DropTable<T> {
+    /// Compare `self` (old) against `other` (new).
+    pub fn diff<'a>(&'a self, other: &'a DropTable<T>) -> TableDiff<&'a T> {
+        let (old_order, old) = merged(self);
+        let (new_order, new) = merged(other);
+
+        let mut diff = TableDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+        for item in old_order {
+            match new.get(item) {
+                None => diff.removed.push((item, old[item])),
+                Some(&p) if p != old[item] => diff.changed.push((item, old[item], p)),
+                Some(_) => {}
+            }
+        }
+        for item in new_order {
+            if !old.contains_key(item) {
+                diff.added.push((item, new[item]));
+            }
+        }
+        diff
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for TableDiff<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "(no changes)");
+        }
+        for (item, p) in &self.removed {
+            writeln!(f, "- {item:?}: {:.6}%", p * 100.0)?;
+        }
+        for (item, p) in &self.added {
+            writeln!(f, "+ {item:?}: {:.6}%", p * 100.0)?;
+        }
+        for (item, old, new) in &self.changed {
+            writeln!(
+                f,
+                "~ {item:?}: {:.6}% -> {:.6}% ({:+.6}pp)",
+                old * 100.0,
+                new * 100.0,
+                (new - old) * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_added_removed_changed() {
+        let old = DropTable::from_pairs([("coins", 3.0), ("gem", 1.0), ("bones", 0.0)]).unwrap();
+        let new = DropTable::from_pairs([("coins", 2.0), ("gem", 1.0), ("gem", 1.0), ("pet", 0.0)])
+            .unwrap();
+        let d = old.diff(&new);
+        assert_eq!(d.removed, vec![(&"bones", 0.0)]);
+        assert_eq!(d.added, vec![(&"pet", 0.0)]);
+        assert_eq!(d.changed, vec![(&"coins", 0.75, 0.5), (&"gem", 0.25, 0.5)]);
+        assert!(
+            d.to_string()
+                .contains("~ \"coins\": 75.000000% -> 50.000000%")
+        );
+        assert!(old.diff(&old).is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+//! Bootstrapping tables from observed data (logs, telemetry).
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::DropTable;
+use crate::error::ProbError;
+
+impl<T: Eq + Hash + Clone> DropTable<T> {
+    /// Tally observations into a table weighted by frequency. Items appear in
+    /// first-seen order.
+    ///
+    /// # Errors
+    /// [`ProbError::Empty`] if there are no observations.
+    pub fn from_counts<I>(observations: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Self::from_counts_smoothed(observations, [], 0.0)
+    }
+
+    /// Like [`from_counts`](Self::from_counts), with additive (Laplace)
+    /// smoothing: every item gets `alpha` pseudo-observations. Items listed in
+    /// `support` are included even if never observed, so they keep a small
+    /// non-zero chance when `alpha > 0`.
+    ///
+    /// # Errors
+    /// * [`ProbError::Empty`] if there are no items at all.
+    /// * [`ProbError::Negative`] if `alpha` is negative.
+    pub fn from_counts_smoothed<I, S>(
+        observations: I,
+        support: S,
+        alpha: f32,
+    ) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = T>,
+        S: IntoIterator<Item = T>,
+    {
+        if alpha.is_sign_negative() {
+            return Err(ProbError::Negative {
+                index: 0,
+                value: alpha,
+            });
+        }
+        // tally in integers: an f32 stops counting at 2^24
+        let mut index: HashMap<T, usize> = HashMap::new();
+        let mut counts: Vec<(T, u64)> = Vec::new();
+        let mut slot = |t: T, counts: &mut Vec<(T, u64)>| {
+            *index.entry(t.clone()).or_insert_with(|| {
+                counts.push((t, 0));
+                counts.len() - 1
+            })
+        };
+        for t in support {
+            slot(t, &mut counts);
+        }
+        for t in observations {
+            let i = slot(t, &mut counts);
+            counts[i].1 += 1;
+        }
+        DropTable::from_pairs(
+            counts
+                .into_iter()
+                .map(|(t, n)| (t, (n as f64 + f64::from(alpha)) as f32)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tallies_and_smooths() {
+        let log = ["coin", "coin", "gem", "coin"];
+        let t = DropTable::from_counts(log).unwrap();
+        assert_eq!(t.items(), &["coin", "gem"]);
+        assert_eq!(t.weights(), &[3.0, 1.0]);
+
+        let t = DropTable::from_counts_smoothed(log, ["pet"], 1.0).unwrap();
+        assert_eq!(t.items(), &["pet", "coin", "gem"]);
+        assert_eq!(t.weights(), &[1.0, 4.0, 2.0]);
+
+        assert_eq!(
+            DropTable::<u8>::from_counts([]).unwrap_err(),
+            ProbError::Empty
+        );
+    }
+
+    #[test]
+    fn counts_past_f32_precision() {
+        // 2^24 + 1 is the first count an f32 running sum can't reach
+        let n = (1 << 24) + 2;
+        let log = std::iter::repeat_n("coin", n).chain(std::iter::repeat_n("gem", n / 2));
+        let t = DropTable::from_counts(log).unwrap();
+        assert_eq!(t.weights(), &[n as f32, (n / 2) as f32]);
+        assert!((t.probabilities()[0] - 2.0 / 3.0).abs() < 1e-6);
+    }
+}
@@ -1,8 +1,48 @@
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ProbError {
     Empty,
-    Negative { index: usize, value: f32 },
+    Negative {
+        index: usize,
+        value: f32,
+    },
     ZeroSum,
+    DuplicateKey {
+        index: usize,
+    },
+    NotNormalized {
+        sum: f64,
+        epsilon: f64,
+    },
+    Shape {
+        expected: usize,
+        found: usize,
+    },
+    InvalidRange {
+        index: usize,
+    },
+    InvalidShape {
+        index: usize,
+    },
+    NotUniform {
+        index: usize,
+    },
+    ChanceOutOfRange {
+        value: f64,
+    },
+    TooLarge {
+        len: usize,
+        max: usize,
+    },
+    /// A zero weight where [`ZeroPolicy::Reject`](crate::ZeroPolicy::Reject)
+    /// forbids one.
+    ZeroWeight {
+        index: usize,
+    },
+    /// A roll count of zero, which would never drop anything rolled.
+    ZeroRolls,
+    /// The table's sampler is [`SamplerKind::Custom`](crate::SamplerKind::Custom),
+    /// which can't be rebuilt from the weights.
+    CustomSampler,
 }
 
 impl std::fmt::Display for ProbError {
@@ -16,8 +56,96 @@ impl std::fmt::Display for ProbError {
                 )
             }
             ProbError::ZeroSum => write!(f, "sum of weights is zero"),
+            ProbError::DuplicateKey { index } => {
+                write!(f, "duplicate key at index {index}")
+            }
+            ProbError::NotNormalized { sum, epsilon } => {
+                write!(
+                    f,
+                    "probabilities sum to {sum} (off by {:+e}), tolerance is ±{epsilon}",
+                    sum - 1.0
+                )
+            }
+            ProbError::Shape { expected, found } => {
+                write!(f, "expected {expected} weights, found {found}")
+            }
+            ProbError::InvalidRange { index } => {
+                write!(f, "range at index {index} is empty or not finite")
+            }
+            ProbError::InvalidShape { index } => {
+                write!(f, "shape parameter at index {index} is out of range")
+            }
+            ProbError::NotUniform { index } => {
+                write!(f, "weight at index {index} differs from the others")
+            }
+            ProbError::ChanceOutOfRange { value } => {
+                write!(f, "chance {value} is outside [0, 1]")
+            }
+            ProbError::TooLarge { len, max } => {
+                write!(f, "{len} weights exceed the limit of {max}")
+            }
+            ProbError::ZeroWeight { index } => {
+                write!(f, "weight at index {index} is zero")
+            }
+            ProbError::ZeroRolls => write!(f, "roll count is zero"),
+            ProbError::CustomSampler => write!(f, "a custom sampler cannot be rebuilt"),
         }
     }
 }
 
 impl std::error::Error for ProbError {}
+
+/// A structural inconsistency found by
+/// [`WeightedSampler::verify_invariants`](crate::WeightedSampler::verify_invariants)
+/// or [`WeightedSampler::from_raw_parts`](crate::WeightedSampler::from_raw_parts).
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvariantError {
+    Empty,
+    ProbOutOfRange {
+        index: usize,
+        prob: f32,
+    },
+    AliasOutOfRange {
+        index: usize,
+        alias: usize,
+    },
+    /// The probability and alias arrays differ in length.
+    LengthMismatch {
+        probs: usize,
+        aliases: usize,
+    },
+}
+
+impl std::fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvariantError::Empty => write!(f, "alias table has no buckets"),
+            InvariantError::ProbOutOfRange { index, prob } => {
+                write!(f, "bucket {index} has probability {prob} outside [0, 1]")
+            }
+            InvariantError::AliasOutOfRange { index, alias } => {
+                write!(f, "bucket {index} aliases out-of-range index {alias}")
+            }
+            InvariantError::LengthMismatch { probs, aliases } => {
+                write!(f, "{probs} probabilities but {aliases} aliases")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvariantError {}
+
+/// A string that names no variant, from the `FromStr`/`TryFrom<&str>` impls
+/// generated by the `WeightedEnum` derive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownVariant {
+    pub name: String,
+}
+
+impl std::fmt::Display for UnknownVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown variant `{}`", self.name)
+    }
+}
+
+impl std::error::Error for UnknownVariant {}
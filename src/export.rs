@@ -0,0 +1,153 @@
+//! Mermaid and Graphviz exports for design docs.
+//!
+//! Each table renders as a tree rooted at a caller-chosen name, with one
+//! edge per entry labelled by its probability. [`MultiTable`] adds a level:
+//! root → sub-table (meta probability) → item (probability within the
+//! sub-table).
+
+use std::fmt::{self, Debug, Write};
+
+use crate::{DropTable, MultiTable};
+
+/// A labelled tree; edges carry the probability of taking them.
+struct Node {
+    label: String,
+    children: Vec<(f64, Node)>,
+}
+
+impl Node {
+    fn leaf(label: String) -> Self {
+        Self {
+            label,
+            children: Vec::new(),
+        }
+    }
+}
+
+fn edge_label(p: f64) -> String {
+    if p <= 0.0 {
+        "never".to_string()
+    } else if p >= 1e-3 {
+        format!("{:.2}%", p * 100.0)
+    } else {
+        format!("1 in {:.0}", 1.0 / p)
+    }
+}
+
+fn table_node<T: Debug>(label: String, table: &DropTable<T>) -> Node {
+    Node {
+        label,
+        children: table
+            .items()
+            .iter()
+            .zip(table.probabilities())
+            .map(|(t, p)| (p, Node::leaf(format!("{t:?}"))))
+            .collect(),
+    }
+}
+
+fn multi_node<T: Debug>(name: &str, multi: &MultiTable<T>) -> Node {
+    Node {
+        label: name.to_string(),
+        children: multi
+            .tables()
+            .iter()
+            .zip(multi.meta_probabilities())
+            .enumerate()
+            .map(|(i, (t, p))| (p, table_node(format!("table {i}"), t)))
+            .collect(),
+    }
+}
+
+fn mermaid(root: &Node) -> String {
+    fn walk(out: &mut String, node: &Node, id: &str, next: &mut usize) -> fmt::Result {
+        for (p, child) in &node.children {
+            let child_id = format!("n{next}");
+            *next += 1;
+            let label = child.label.replace('"', "#quot;");
+            writeln!(
+                out,
+                "    {id} -->|\"{}\"| {child_id}[\"{label}\"]",
+                edge_label(*p)
+            )?;
+            walk(out, child, &child_id, next)?;
+        }
+        Ok(())
+    }
+    let mut out = String::from("flowchart LR\n");
+    let label = root.label.replace('"', "#quot;");
+    let _ = writeln!(out, "    root[\"{label}\"]");
+    let _ = walk(&mut out, root, "root", &mut 0);
+    out
+}
+
+fn dot(root: &Node) -> String {
+    fn quote(s: &str) -> String {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+    fn walk(out: &mut String, node: &Node, id: &str, next: &mut usize) -> fmt::Result {
+        for (p, child) in &node.children {
+            let child_id = format!("n{next}");
+            *next += 1;
+            writeln!(out, "    {child_id} [label={}];", quote(&child.label))?;
+            writeln!(
+                out,
+                "    {id} -> {child_id} [label={}];",
+                quote(&edge_label(*p))
+            )?;
+            walk(out, child, &child_id, next)?;
+        }
+        Ok(())
+    }
+    let mut out = String::from("digraph droptable {\n    rankdir=LR;\n");
+    let _ = writeln!(out, "    root [label={}];", quote(&root.label));
+    let _ = walk(&mut out, root, "root", &mut 0);
+    out.push_str("}\n");
+    out
+}
+
+impl<T: Debug> DropTable<T> {
+    /// Mermaid flowchart of this table, rooted at a node labelled `name`.
+    pub fn to_mermaid(&self, name: &str) -> String {
+        mermaid(&table_node(name.to_string(), self))
+    }
+
+    /// Graphviz `digraph` of this table, rooted at a node labelled `name`.
+    pub fn to_dot(&self, name: &str) -> String {
+        dot(&table_node(name.to_string(), self))
+    }
+}
+
+impl<T: Debug> MultiTable<T> {
+    /// Mermaid flowchart: root → sub-tables → items.
+    pub fn to_mermaid(&self, name: &str) -> String {
+        mermaid(&multi_node(name, self))
+    }
+
+    /// Graphviz `digraph`: root → sub-tables → items.
+    pub fn to_dot(&self, name: &str) -> String {
+        dot(&multi_node(name, self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_edges_with_probabilities() {
+        let dt = DropTable::from_pairs([("bones", 3.0), ("pet", 1.0)]).unwrap();
+        assert_eq!(
+            dt.to_mermaid("boss"),
+            "flowchart LR\n    root[\"boss\"]\n    \
+             root -->|\"75.00%\"| n0[\"#quot;bones#quot;\"]\n    \
+             root -->|\"25.00%\"| n1[\"#quot;pet#quot;\"]\n"
+        );
+
+        let rare = DropTable::from_pairs([("hilt", 1.0)]).unwrap();
+        let multi = MultiTable::from_tables([(dt, 4999.0), (rare, 1.0)]).unwrap();
+        let dot = multi.to_dot("graardor");
+        assert!(dot.contains("root -> n3 [label=\"1 in 5000\"];"), "{dot}");
+        assert!(dot.contains("n4 [label=\"\\\"hilt\\\"\"];"), "{dot}");
+    }
+}
@@ -0,0 +1,186 @@
+//! Fixed-point alias tables for lockstep simulations (feature
+//! `deterministic`).
+//!
+//! [`WeightedSampler`](crate::WeightedSampler) builds and samples in `f32`,
+//! which is reproducible in practice but leaves room for compiler and
+//! platform differences. [`FixedAliasTable`] uses floats for nothing:
+//!
+//! * each `f32` weight is decoded from its bits into an integer mantissa
+//!   and exponent and aligned to the largest weight, keeping the top
+//!   `64 - bitlen(n)` bits (weights smaller than that relative to the largest
+//!   quantize to zero);
+//! * Vose's pairing runs on those integers exactly, with no residual drift;
+//! * each bucket stores a `u64` threshold and a draw compares one
+//!   `next_u64()` against it.
+//!
+//! Given the same weights and the same RNG stream, x86, ARM and WASM builds
+//! return the same indices.
+
+use rand::{Rng, RngCore};
+
+use crate::IndexSampler;
+use crate::error::ProbError;
+use crate::walker::{check_len, validate_weights};
+
+/// One bucket: keep index `i` when `u < threshold`, otherwise take `alias`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FixedBucket {
+    threshold: u64,
+    alias: u32,
+}
+
+/// Alias table built and sampled entirely in integer arithmetic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedAliasTable {
+    buckets: Vec<FixedBucket>,
+}
+
+/// `value = mantissa * 2^exponent` for a finite, non-negative `f32`.
+fn decode(w: f32) -> (u64, i32) {
+    let bits = w.to_bits();
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let frac = u64::from(bits & 0x7f_ffff);
+    if exp == 0 {
+        (frac, -149)
+    } else {
+        (frac | 1 << 23, exp - 150)
+    }
+}
+
+impl FixedAliasTable {
+    /// # Errors
+    /// As [`WeightedSampler::new`](crate::WeightedSampler::new).
+    pub fn new(weights: &[f32]) -> Result<Self, ProbError> {
+        let n = weights.len();
+        check_len(n, (u32::MAX as usize).saturating_add(1))?;
+        validate_weights(weights)?;
+        let decoded: Vec<(u64, i32)> = weights.iter().map(|&w| decode(w)).collect();
+
+        // Align every weight to the largest one, keeping `bits` bits so that
+        // n * max < 2^64.
+        let bits = 64 - (usize::BITS - n.leading_zeros()) as i32;
+        let top = decoded
+            .iter()
+            .filter(|(m, _)| *m != 0)
+            .map(|&(m, e)| e + (64 - m.leading_zeros()) as i32)
+            .max()
+            .expect("validated weights have a non-zero entry");
+        let quantized: Vec<u64> = decoded
+            .iter()
+            .map(|&(m, e)| {
+                let shift = e + bits - top;
+                match shift {
+                    s if s >= 0 => m << s,
+                    s if s > -64 => m >> -s,
+                    _ => 0,
+                }
+            })
+            .collect();
+
+        let total: u64 = quantized.iter().sum();
+        let mut scaled: Vec<u64> = quantized.iter().map(|&q| q * n as u64).collect();
+        let mut buckets: Vec<FixedBucket> = (0..n as u32)
+            .map(|i| FixedBucket {
+                threshold: u64::MAX,
+                alias: i,
+            })
+            .collect();
+
+        let (mut small, mut large): (Vec<u32>, Vec<u32>) =
+            (0..n as u32).partition(|&i| scaled[i as usize] < total);
+        while let (Some(s), Some(l)) = (small.pop(), large.pop()) {
+            let (si, li) = (s as usize, l as usize);
+            buckets[si] = FixedBucket {
+                threshold: ((u128::from(scaled[si]) << 64) / u128::from(total)) as u64,
+                alias: l,
+            };
+            scaled[li] -= total - scaled[si];
+            if scaled[li] < total {
+                small.push(l);
+            } else {
+                large.push(l);
+            }
+        }
+        // Exact arithmetic: every leftover holds exactly `total` and keeps
+        // its default self-alias.
+        Ok(Self { buckets })
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    #[inline]
+    pub fn sample_index<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let i = rng.random_range(0..self.buckets.len() as u64) as usize;
+        let b = self.buckets[i];
+        if rng.next_u64() < b.threshold {
+            i
+        } else {
+            b.alias as usize
+        }
+    }
+
+    /// The distribution the table encodes, for inspection.
+    pub fn probabilities(&self) -> Vec<f64> {
+        let n = self.buckets.len() as f64;
+        let mut mass = vec![0.0f64; self.buckets.len()];
+        for (i, b) in self.buckets.iter().enumerate() {
+            let keep = if b.alias as usize == i {
+                1.0
+            } else {
+                b.threshold as f64 / 2f64.powi(64)
+            };
+            mass[i] += keep / n;
+            mass[b.alias as usize] += (1.0 - keep) / n;
+        }
+        mass
+    }
+}
+
+impl IndexSampler for FixedAliasTable {
+    #[inline]
+    fn len(&self) -> usize {
+        self.buckets.len()
+    }
+    #[inline]
+    fn sample_index<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        FixedAliasTable::sample_index(self, rng)
+    }
+    #[inline]
+    fn sample_index_dyn(&self, rng: &mut dyn RngCore) -> usize {
+        FixedAliasTable::sample_index(self, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn exact_masses_and_pinned_stream() {
+        let table = FixedAliasTable::new(&[1.0, 2.0, 0.0, 5.0]).unwrap();
+        for (p, want) in table.probabilities().iter().zip([0.125, 0.25, 0.0, 0.625]) {
+            assert!((p - want).abs() < 1e-15, "{p} vs {want}");
+        }
+        // Same scale-free weights give the same table bit for bit.
+        assert_eq!(table, FixedAliasTable::new(&[0.5, 1.0, 0.0, 2.5]).unwrap());
+
+        let mut rng = StdRng::seed_from_u64(1352);
+        let got: Vec<usize> = (0..8).map(|_| table.sample_index(&mut rng)).collect();
+        assert_eq!(got, [3, 3, 1, 3, 3, 0, 1, 3]);
+    }
+
+    #[test]
+    fn tiny_weights_survive_quantization() {
+        let table = FixedAliasTable::new(&[1.0, 1e-9, f32::MIN_POSITIVE]).unwrap();
+        let p = table.probabilities();
+        assert!((p[1] / 1e-9 - 1.0).abs() < 1e-6, "{p:?}");
+        assert_eq!(p[2], 0.0);
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use rand::Rng;
+
+use crate::error::ProbError;
+use crate::walker::WeightedSampler;
+
+/// Alias tables kept by [`FlaggedTable::new`].
+const DEFAULT_CACHE_SIZE: usize = 16;
+
+type Requirement<F> = Box<dyn Fn(&F) -> bool + Send + Sync>;
+
+/// One cached alias table, keyed by which entries were eligible.
+type CacheEntry = (Vec<u64>, Result<WeightedSampler, ProbError>);
+
+/// A drop table whose entries are gated on a caller-supplied flag set:
+/// quest offers, dialogue lines and encounters that only appear once the
+/// player has met some condition.
+///
+/// The flag set `F` is whatever the game already tracks: a `u64` bitset
+/// (with the [`requires`](FlaggedTable::requires) shorthand), a struct of
+/// quest state, or `dyn Fn(&str) -> bool` to look flags up by name. Each
+/// entry carries a requirement over it, and [`sample`](Self::sample) draws
+/// by weight among the entries whose requirements hold.
+///
+/// Requirements are evaluated on every draw, which is cheap; building the
+/// alias table is not, so one is cached per distinct set of eligible
+/// entries, keeping the `capacity` most recently used. Flag sets that gate
+/// the same entries share a table.
+///
+/// ```
+/// use droptables::FlaggedTable;
+///
+/// const MET_WIZARD: u64 = 1 << 0;
+/// const HAS_SWORD: u64 = 1 << 1;
+///
+/// let lines = FlaggedTable::new()
+///     .always("Nice weather today.", 5.0)
+///     .requires("The wizard sent you?", 3.0, MET_WIZARD, 0)
+///     .requires("Put that sword away!", 2.0, HAS_SWORD, MET_WIZARD);
+///
+/// let mut rng = rand::rng();
+/// assert_eq!(lines.sample(&0, &mut rng), Ok(&"Nice weather today."));
+/// let line = lines.sample(&(MET_WIZARD | HAS_SWORD), &mut rng).unwrap();
+/// assert_ne!(*line, "Put that sword away!");
+/// ```
+pub struct FlaggedTable<T, F: ?Sized = u64> {
+    items: Vec<T>,
+    weights: Vec<f32>,
+    requirements: Vec<Requirement<F>>,
+    capacity: usize,
+    /// Most recently used first.
+    cache: Mutex<VecDeque<CacheEntry>>,
+}
+
+impl<T: std::fmt::Debug, F: ?Sized> std::fmt::Debug for FlaggedTable<T, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FlaggedTable")
+            .field("items", &self.items)
+            .field("weights", &self.weights)
+            .field("capacity", &self.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, F: ?Sized> Default for FlaggedTable<T, F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, F: ?Sized> FlaggedTable<T, F> {
+    /// An empty table caching up to 16 alias tables.
+    pub fn new() -> Self {
+        Self::with_cache_size(DEFAULT_CACHE_SIZE)
+    }
+
+    /// An empty table caching up to `capacity` alias tables (clamped to at
+    /// least 1).
+    pub fn with_cache_size(capacity: usize) -> Self {
+        Self {
+            items: Vec::new(),
+            weights: Vec::new(),
+            requirements: Vec::new(),
+            capacity: capacity.max(1),
+            cache: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Add an entry that is always eligible.
+    pub fn always(self, item: T, weight: f32) -> Self {
+        self.when(item, weight, |_| true)
+    }
+
+    /// Add an entry eligible while `requirement(flags)` holds.
+    pub fn when<P>(mut self, item: T, weight: f32, requirement: P) -> Self
+    where
+        P: Fn(&F) -> bool + Send + Sync + 'static,
+    {
+        self.items.push(item);
+        self.weights.push(weight);
+        self.requirements.push(Box::new(requirement));
+        self.cache
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+        self
+    }
+
+    /// Whether entry `index` is eligible under `flags`.
+    pub fn is_eligible(&self, index: usize, flags: &F) -> bool {
+        (self.requirements[index])(flags)
+    }
+
+    /// Items eligible under `flags`, in insertion order.
+    pub fn eligible<'a>(&'a self, flags: &'a F) -> impl Iterator<Item = &'a T> + 'a {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(move |&(i, _)| self.is_eligible(i, flags))
+            .map(|(_, item)| item)
+    }
+
+    /// Draw by weight among the entries eligible under `flags`.
+    ///
+    /// # Errors
+    /// Whatever [`WeightedSampler::new`] reports for the eligible weights,
+    /// e.g. [`ProbError::ZeroSum`] when nothing is eligible.
+    pub fn sample<R: Rng + ?Sized>(&self, flags: &F, rng: &mut R) -> Result<&T, ProbError> {
+        self.sample_index(flags, rng).map(|i| &self.items[i])
+    }
+
+    /// [`sample`](Self::sample), returning the entry's index.
+    ///
+    /// # Errors
+    /// As [`sample`](Self::sample).
+    pub fn sample_index<R: Rng + ?Sized>(
+        &self,
+        flags: &F,
+        rng: &mut R,
+    ) -> Result<usize, ProbError> {
+        let mut key = vec![0u64; self.items.len().div_ceil(64)];
+        for i in (0..self.items.len()).filter(|&i| self.is_eligible(i, flags)) {
+            key[i / 64] |= 1 << (i % 64);
+        }
+
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        match cache.iter().position(|(k, _)| *k == key) {
+            Some(0) => {}
+            Some(hit) => {
+                let entry = cache.remove(hit).expect("position is in bounds");
+                cache.push_front(entry);
+            }
+            None => {
+                let masked: Vec<f32> = self
+                    .weights
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &w)| {
+                        if key[i / 64] >> (i % 64) & 1 == 1 {
+                            w
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect();
+                cache.push_front((key, WeightedSampler::new(&masked)));
+                cache.truncate(self.capacity);
+            }
+        }
+        let sampler = cache[0].1.as_ref().map_err(Clone::clone)?;
+        Ok(sampler.sample_index(rng))
+    }
+
+    /// Alias tables currently cached.
+    pub fn cached(&self) -> usize {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Items in insertion order.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether the table has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl<T> FlaggedTable<T, u64> {
+    /// Add an entry eligible while every bit of `all` is set and no bit of
+    /// `none` is.
+    pub fn requires(self, item: T, weight: f32, all: u64, none: u64) -> Self {
+        self.when(item, weight, move |flags| {
+            flags & all == all && flags & none == 0
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn gates_entries_and_caches_by_eligibility() {
+        let table = FlaggedTable::with_cache_size(2)
+            .always("rumour", 1.0)
+            .requires("quest", 1.0, 0b01, 0)
+            .requires("reward", 1.0, 0b11, 0);
+        let mut rng = StdRng::seed_from_u64(1404);
+
+        for _ in 0..200 {
+            assert_ne!(table.sample(&0b01, &mut rng), Ok(&"reward"));
+        }
+        // 0b01 and 0b101 gate the same entries, so share a table
+        table.sample(&0b101, &mut rng).unwrap();
+        assert_eq!(table.cached(), 1);
+        table.sample(&0b11, &mut rng).unwrap();
+        table.sample(&0, &mut rng).unwrap();
+        assert_eq!(table.cached(), 2);
+        assert_eq!(table.eligible(&0b11).count(), 3);
+
+        let gated = FlaggedTable::new().requires("secret", 1.0, 0b1, 0);
+        assert_eq!(gated.sample(&0, &mut rng), Err(ProbError::ZeroSum));
+    }
+
+    #[test]
+    fn flags_can_be_a_lookup() {
+        type Has = dyn Fn(&str) -> bool;
+        let table = FlaggedTable::new()
+            .when("greeting", 1.0, |has: &Has| !has("angry"))
+            .when("threat", 1.0, |has: &Has| has("angry"));
+        let angry = |flag: &str| flag == "angry";
+        let mut rng = StdRng::seed_from_u64(1404);
+        for _ in 0..50 {
+            assert_eq!(table.sample(&angry, &mut rng), Ok(&"threat"));
+        }
+    }
+}
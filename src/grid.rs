@@ -0,0 +1,188 @@
+use std::fmt;
+
+use rand::Rng;
+
+use crate::DropTable;
+
+/// A cell of a [`GridPicker::fill`] had no tile compatible with its
+/// neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridContradiction {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl fmt::Display for GridContradiction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no tile fits the cell at ({}, {})", self.x, self.y)
+    }
+}
+
+impl std::error::Error for GridContradiction {}
+
+/// Weighted tile (or biome) picker for grid fills with neighbour rules, a
+/// lightweight take on wave function collapse.
+///
+/// Tiles come from a [`DropTable`]; a rule says which pairs may sit side
+/// by side, and is precomputed into an n × n matrix. Each cell is drawn by
+/// weight from the tiles its mask allows, using the same rejection and
+/// exact-fallback sampling as [`DropTable::sample_where`], so the allowed
+/// tiles keep their relative odds.
+///
+/// ```
+/// use droptables::{DropTable, GridPicker};
+///
+/// let tiles = DropTable::from_pairs([("water", 3.0), ("sand", 1.0), ("grass", 4.0)]).unwrap();
+/// // the beach goes between water and grass
+/// let picker = GridPicker::new(tiles, |a, b| !matches!((*a, *b), ("water", "grass")));
+///
+/// let (w, h) = (16, 8);
+/// let cells = picker.fill(&mut rand::rng(), w, h).unwrap();
+/// for y in 0..h {
+///     for x in 1..w {
+///         assert!(picker.compatible(cells[y * w + x - 1], cells[y * w + x]));
+///     }
+/// }
+/// ```
+///
+/// [`fill`](Self::fill) goes in row-major order and does not backtrack: a
+/// cell whose left and upper neighbours leave no tile fails with
+/// [`GridContradiction`], and the caller fills again. Rules where every
+/// tile has some compatible partner for any pair of neighbours never fail.
+#[derive(Debug, Clone)]
+pub struct GridPicker<T> {
+    table: DropTable<T>,
+    /// `compatible[a * n + b]`: tiles `a` and `b` may be neighbours.
+    compatible: Vec<bool>,
+}
+
+impl<T> GridPicker<T> {
+    /// Precompute `rule` for every pair of tiles. Adjacency is symmetric:
+    /// `a` and `b` may be neighbours when `rule(a, b) && rule(b, a)`, so a
+    /// rule only needs to state each ban one way round.
+    pub fn new(table: DropTable<T>, rule: impl Fn(&T, &T) -> bool) -> Self {
+        let items = table.items();
+        let compatible = items
+            .iter()
+            .flat_map(|a| {
+                items
+                    .iter()
+                    .map(|b| rule(a, b) && rule(b, a))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        Self { table, compatible }
+    }
+
+    pub fn table(&self) -> &DropTable<T> {
+        &self.table
+    }
+
+    /// Whether tiles `a` and `b` (table indices) may be neighbours.
+    pub fn compatible(&self, a: usize, b: usize) -> bool {
+        self.compatible[a * self.table.len() + b]
+    }
+
+    /// Draw a tile index among those `allowed` accepts, by weight; `None` if
+    /// none of them has positive weight.
+    pub fn pick_masked<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        allowed: impl Fn(usize) -> bool,
+    ) -> Option<usize> {
+        self.table.sample_index_where(rng, allowed)
+    }
+
+    /// Draw a tile index compatible with every tile in `neighbors`.
+    pub fn pick_next_to<R: Rng + ?Sized>(&self, rng: &mut R, neighbors: &[usize]) -> Option<usize> {
+        self.pick_masked(rng, |i| neighbors.iter().all(|&n| self.compatible(n, i)))
+    }
+
+    /// Fill a `width` × `height` grid, returning tile indices in row-major
+    /// order.
+    ///
+    /// # Errors
+    /// [`GridContradiction`] at the first cell with no compatible tile.
+    pub fn fill<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        width: usize,
+        height: usize,
+    ) -> Result<Vec<usize>, GridContradiction> {
+        self.fill_with(rng, width, height, |_, _, _| true)
+    }
+
+    /// [`fill`](Self::fill) with a per-cell mask as well: `cell(x, y, tile)`
+    /// bans tiles from single cells, e.g. water-only borders.
+    ///
+    /// # Errors
+    /// As [`fill`](Self::fill).
+    pub fn fill_with<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        width: usize,
+        height: usize,
+        cell: impl Fn(usize, usize, usize) -> bool,
+    ) -> Result<Vec<usize>, GridContradiction> {
+        let mut cells: Vec<usize> = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let left = (x > 0).then(|| cells[cells.len() - 1]);
+                let up = (y > 0).then(|| cells[cells.len() - width]);
+                let tile = self
+                    .pick_masked(rng, |i| {
+                        cell(x, y, i)
+                            && left.is_none_or(|l| self.compatible(l, i))
+                            && up.is_none_or(|u| self.compatible(u, i))
+                    })
+                    .ok_or(GridContradiction { x, y })?;
+                cells.push(tile);
+            }
+        }
+        Ok(cells)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn fills_respect_neighbours_and_masks() {
+        let tiles = DropTable::from_pairs([("water", 3.0), ("sand", 1.0), ("grass", 4.0)]).unwrap();
+        let picker = GridPicker::new(tiles, |a, b| !matches!((*a, *b), ("water", "grass")));
+        assert!(!picker.compatible(2, 0));
+        assert!(picker.compatible(1, 1));
+
+        let mut rng = StdRng::seed_from_u64(1397);
+        let (w, h) = (12, 12);
+        let border = |x: usize, y: usize, tile: usize| {
+            let edge = x == 0 || y == 0 || x == w - 1 || y == h - 1;
+            // sand fits next to anything, so the fill can't get stuck
+            !edge || tile == 1
+        };
+        let cells = picker.fill_with(&mut rng, w, h, border).unwrap();
+        for y in 0..h {
+            for x in 0..w {
+                let c = cells[y * w + x];
+                assert!(border(x, y, c));
+                if x > 0 {
+                    assert!(picker.compatible(cells[y * w + x - 1], c));
+                }
+                if y > 0 {
+                    assert!(picker.compatible(cells[(y - 1) * w + x], c));
+                }
+            }
+        }
+
+        assert_eq!(picker.pick_next_to(&mut rng, &[0, 2]), Some(1));
+        assert_eq!(picker.pick_masked(&mut rng, |_| false), None);
+
+        let lonely = GridPicker::new(DropTable::from_pairs([("x", 1.0)]).unwrap(), |_, _| false);
+        assert_eq!(
+            lonely.fill(&mut rng, 3, 1),
+            Err(GridContradiction { x: 1, y: 0 })
+        );
+    }
+}
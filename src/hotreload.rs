@@ -0,0 +1,207 @@
+//! Tables that follow a weights file on disk (feature `hotreload`).
+//!
+//! [`WatchedDropTable`] loads a file in the [text format](crate::parse) and
+//! swaps in a rebuilt table whenever the file's modification time or size
+//! changes, so drop rates can be tuned during a playtest without a restart.
+//! Readers clone an [`Arc`] snapshot under a read lock and sample outside
+//! it, and a reload takes the write lock only to swap that pointer, so a
+//! reload never waits on or tears an in-flight sample. A file that fails to
+//! parse leaves the last good table in place.
+//!
+//! ```no_run
+//! use droptables::hotreload::WatchedDropTable;
+//!
+//! let table = WatchedDropTable::open("loot/goblin.txt").unwrap();
+//! let drop = table.sample(&mut rand::rng());
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use rand::Rng;
+
+use crate::DropTable;
+use crate::parse::ParseError;
+
+/// How often [`WatchedDropTable::open`] checks the file.
+pub const DEFAULT_POLL: Duration = Duration::from_millis(500);
+
+/// Why loading or reloading the file failed.
+#[derive(Debug)]
+pub enum WatchError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for WatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatchError::Io(e) => write!(f, "read failed: {e}"),
+            WatchError::Parse(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for WatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WatchError::Io(e) => Some(e),
+            WatchError::Parse(e) => Some(e),
+        }
+    }
+}
+
+/// What a change is detected by.
+type Stamp = (Option<SystemTime>, u64);
+
+#[derive(Debug)]
+struct Shared {
+    path: PathBuf,
+    table: RwLock<Arc<DropTable<String>>>,
+    stamp: Mutex<Stamp>,
+    error: Mutex<Option<WatchError>>,
+}
+
+fn stamp(path: &Path) -> io::Result<Stamp> {
+    let meta = fs::metadata(path)?;
+    Ok((meta.modified().ok(), meta.len()))
+}
+
+fn load(path: &Path) -> Result<(DropTable<String>, Stamp), WatchError> {
+    // stamp first: a write racing the read is then seen again next poll
+    let stamp = stamp(path).map_err(WatchError::Io)?;
+    let src = fs::read_to_string(path).map_err(WatchError::Io)?;
+    let table = DropTable::parse(&src).map_err(WatchError::Parse)?;
+    Ok((table, stamp))
+}
+
+impl Shared {
+    fn reload_if_changed(&self) -> Result<bool, WatchError> {
+        let mut current = self.stamp.lock().unwrap();
+        if stamp(&self.path).map_err(WatchError::Io)? == *current {
+            return Ok(false);
+        }
+        let (table, new) = load(&self.path)?;
+        // the old table is freed after the write lock is released
+        let _old = std::mem::replace(&mut *self.table.write().unwrap(), Arc::new(table));
+        *current = new;
+        Ok(true)
+    }
+}
+
+/// A [`DropTable<String>`] kept in sync with a weights file; see the
+/// [module docs](self).
+#[derive(Debug, Clone)]
+pub struct WatchedDropTable {
+    shared: Arc<Shared>,
+}
+
+impl WatchedDropTable {
+    /// Load `path` and check it for changes every [`DEFAULT_POLL`] on a
+    /// background thread, which exits once every clone of the table is
+    /// dropped.
+    ///
+    /// # Errors
+    /// If the file can't be read or parsed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, WatchError> {
+        let table = Self::open_manual(path)?;
+        let weak = Arc::downgrade(&table.shared);
+        thread::spawn(move || poll(weak, DEFAULT_POLL));
+        Ok(table)
+    }
+
+    /// Load `path` without a background thread; call
+    /// [`reload_if_changed`](Self::reload_if_changed) yourself, e.g. once
+    /// per frame or tick.
+    ///
+    /// # Errors
+    /// If the file can't be read or parsed.
+    pub fn open_manual(path: impl AsRef<Path>) -> Result<Self, WatchError> {
+        let path = path.as_ref().to_path_buf();
+        let (table, stamp) = load(&path)?;
+        Ok(Self {
+            shared: Arc::new(Shared {
+                path,
+                table: RwLock::new(Arc::new(table)),
+                stamp: Mutex::new(stamp),
+                error: Mutex::new(None),
+            }),
+        })
+    }
+
+    /// Rebuild the table if the file changed since the last load. `Ok(true)`
+    /// if a new table was swapped in.
+    ///
+    /// # Errors
+    /// If the file changed but can't be read or parsed; the previous table
+    /// stays in use and the next call tries again.
+    pub fn reload_if_changed(&self) -> Result<bool, WatchError> {
+        self.shared.reload_if_changed()
+    }
+
+    /// The current table. Holding on to it keeps that version alive even
+    /// if a newer one is swapped in.
+    pub fn load(&self) -> Arc<DropTable<String>> {
+        Arc::clone(&self.shared.table.read().unwrap())
+    }
+
+    /// Draw one item from the current table.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        self.load().sample(rng).clone()
+    }
+
+    /// The most recent error from the background poller, if any, clearing
+    /// it.
+    pub fn take_error(&self) -> Option<WatchError> {
+        self.shared.error.lock().unwrap().take()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.shared.path
+    }
+}
+
+fn poll(shared: Weak<Shared>, interval: Duration) {
+    loop {
+        thread::sleep(interval);
+        let Some(shared) = shared.upgrade() else {
+            return;
+        };
+        if let Err(e) = shared.reload_if_changed() {
+            *shared.error.lock().unwrap() = Some(e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reloads_on_change_and_keeps_last_good() {
+        let path = std::env::temp_dir().join(format!("droptables-hot-{}.txt", std::process::id()));
+        fs::write(&path, "coins: 1").unwrap();
+        let table = WatchedDropTable::open_manual(&path).unwrap();
+        let before = table.load();
+        assert!(!table.reload_if_changed().unwrap());
+
+        fs::write(&path, "#! version = 2\nrune: 1").unwrap();
+        assert!(table.reload_if_changed().unwrap());
+        assert_eq!(table.sample(&mut rand::rng()), "rune");
+        assert_eq!(table.load().version(), 2);
+        assert_eq!(before.items(), ["coins"]);
+
+        fs::write(&path, "rune: -1, broken").unwrap();
+        assert!(matches!(
+            table.reload_if_changed(),
+            Err(WatchError::Parse(_))
+        ));
+        assert_eq!(table.sample(&mut rand::rng()), "rune");
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,170 @@
+use rand::Rng;
+
+use crate::DropTable;
+use crate::error::ProbError;
+use crate::walker::WeightedSampler;
+
+/// A joint distribution over pairs `(A, B)`.
+///
+/// One alias table covers every cell of the `A x B` grid, so drawing a
+/// pair is a single O(1) sample.
+#[derive(Debug, Clone)]
+pub struct JointTable<A, B> {
+    a: Vec<A>,
+    b: Vec<B>,
+    // row-major: weights[i * b.len() + j] weights (a[i], b[j])
+    weights: Vec<f32>,
+    alias: WeightedSampler,
+}
+
+impl<A, B> JointTable<A, B> {
+    /// Build from a weight matrix: `matrix[i][j]` weights `(a[i], b[j])`.
+    ///
+    /// # Errors
+    /// * [`ProbError::Shape`] if the matrix is not `a.len() x b.len()`.
+    /// * Anything [`WeightedSampler::new`] rejects (indices are row-major).
+    pub fn from_matrix<M, Row>(a: Vec<A>, b: Vec<B>, matrix: M) -> Result<Self, ProbError>
+    where
+        M: IntoIterator<Item = Row>,
+        Row: AsRef<[f32]>,
+    {
+        let mut weights = Vec::with_capacity(a.len() * b.len());
+        let mut rows = 0;
+        for row in matrix {
+            let row = row.as_ref();
+            if row.len() != b.len() {
+                return Err(ProbError::Shape {
+                    expected: b.len(),
+                    found: row.len(),
+                });
+            }
+            weights.extend_from_slice(row);
+            rows += 1;
+        }
+        if rows != a.len() {
+            return Err(ProbError::Shape {
+                expected: a.len(),
+                found: rows,
+            });
+        }
+        let alias = WeightedSampler::new(&weights)?;
+        Ok(Self {
+            a,
+            b,
+            weights,
+            alias,
+        })
+    }
+
+    /// The product of two independent marginals.
+    ///
+    /// # Errors
+    /// Only if the product underflows to all zeros.
+    pub fn from_marginals(a: &DropTable<A>, b: &DropTable<B>) -> Result<Self, ProbError>
+    where
+        A: Clone,
+        B: Clone,
+    {
+        let pb = b.probabilities();
+        let matrix: Vec<Vec<f32>> = a
+            .probabilities()
+            .into_iter()
+            .map(|pa| pb.iter().map(|&pb| (pa * pb) as f32).collect())
+            .collect();
+        Self::from_matrix(a.items().to_vec(), b.items().to_vec(), matrix)
+    }
+
+    /// Draw a pair. **O(1)**.
+    pub fn sample<'a, R: Rng + ?Sized>(&'a self, rng: &mut R) -> (&'a A, &'a B) {
+        let (i, j) = self.sample_indices(rng);
+        (&self.a[i], &self.b[j])
+    }
+
+    /// Draw a pair of `(row, column)` indices.
+    pub fn sample_indices<R: Rng + ?Sized>(&self, rng: &mut R) -> (usize, usize) {
+        let cell = self.alias.sample_index(rng);
+        (cell / self.b.len(), cell % self.b.len())
+    }
+
+    /// The conditional distribution over `B` given `A = a[i]`.
+    ///
+    /// # Errors
+    /// [`ProbError::ZeroSum`] if row `i` has no mass.
+    ///
+    /// # Panics
+    /// If `i` is out of range.
+    pub fn conditional_index(&self, i: usize) -> Result<DropTable<&B>, ProbError> {
+        let m = self.b.len();
+        let row = &self.weights[i * m..(i + 1) * m];
+        DropTable::from_pairs(self.b.iter().zip(row.iter().copied()))
+    }
+
+    /// The conditional distribution over `B` given `a`, or `None` if `a`
+    /// is not a row of this table.
+    pub fn conditional(&self, a: &A) -> Option<Result<DropTable<&B>, ProbError>>
+    where
+        A: PartialEq,
+    {
+        let i = self.a.iter().position(|x| x == a)?;
+        Some(self.conditional_index(i))
+    }
+
+    /// Marginal distribution over `A` (row sums).
+    pub fn marginal_a(&self) -> Result<DropTable<&A>, ProbError> {
+        let m = self.b.len();
+        DropTable::from_pairs(
+            self.a
+                .iter()
+                .zip(self.weights.chunks(m).map(|r| r.iter().sum::<f32>())),
+        )
+    }
+
+    /// Row labels.
+    pub fn a_items(&self) -> &[A] {
+        &self.a
+    }
+
+    /// Column labels.
+    pub fn b_items(&self) -> &[B] {
+        &self.b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conditional_and_marginals() {
+        let joint = JointTable::from_matrix(
+            vec!["sword", "staff"],
+            vec!["fire", "ice"],
+            [[3.0, 1.0], [0.0, 2.0]],
+        )
+        .unwrap();
+        let given_staff = joint.conditional(&"staff").unwrap().unwrap();
+        assert_eq!(given_staff.probabilities(), vec![0.0, 1.0]);
+        assert_eq!(joint.marginal_a().unwrap().weights(), &[4.0, 2.0]);
+
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            assert_ne!(joint.sample(&mut rng), (&"staff", &"fire"));
+        }
+
+        let a = DropTable::from_pairs([('x', 1.0), ('y', 3.0)]).unwrap();
+        let b = DropTable::from_pairs([(1, 1.0), (2, 1.0)]).unwrap();
+        let product = JointTable::from_marginals(&a, &b).unwrap();
+        assert_eq!(
+            product.conditional(&'y').unwrap().unwrap().probabilities(),
+            vec![0.5, 0.5]
+        );
+
+        assert!(matches!(
+            JointTable::from_matrix(vec![1], vec![1, 2], [[1.0]]),
+            Err(ProbError::Shape {
+                expected: 2,
+                found: 1
+            })
+        ));
+    }
+}
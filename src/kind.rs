@@ -0,0 +1,166 @@
+//! Choosing the index sampler behind a [`DropTable`](crate::DropTable).
+//!
+//! Rough single-threaded costs per draw (Pcg32, release build):
+//!
+//! | n      | alias  | cdf    |
+//! |--------|--------|--------|
+//! | 2      | ~10 ns | ~3 ns  |
+//! | 64     | ~10 ns | ~8 ns  |
+//! | 512    | ~11 ns | ~12 ns |
+//! | 65536  | ~12 ns | ~38 ns |
+//!
+//! Alias sampling is flat in `n` but pays for two RNG calls; a CDF binary
+//! search needs one RNG call and wins until the search gets deep. Up to
+//! [`SmallSampler::MAX_LEN`] entries, three branchless comparisons on one
+//! RNG call beat both (see the `small_table_sample` bench). Equal weights
+//! need no table at all. Reproduce with `cargo bench --features bench-support`.
+
+use std::fmt;
+use std::sync::Arc;
+
+use rand::{Rng, RngCore};
+
+use crate::IndexSampler;
+use crate::cdf::CdfSampler;
+use crate::error::ProbError;
+use crate::sampler::UniformSampler;
+use crate::small::SmallSampler;
+use crate::walker::{WeightedSampler, validate_weights};
+
+/// Which sampler a table is built with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SamplerKind {
+    /// Walker's alias method: O(1) per draw.
+    #[default]
+    Alias,
+    /// Cumulative distribution + binary search: O(log n) per draw.
+    Cdf,
+    /// Equal odds; only valid when every weight is equal.
+    Uniform,
+    /// [`SmallSampler`] for tables of at most [`SmallSampler::MAX_LEN`]
+    /// entries, an alias table beyond that (so tables grown with
+    /// [`extend_pairs`](crate::DropTable::extend_pairs) keep working).
+    /// Opt-in: draws differ from an alias table's for the same seed.
+    Small,
+    /// Pick one of the above from `n` and the weights (never
+    /// [`Small`](Self::Small)).
+    Auto,
+    /// A caller-supplied sampler (see [`DropTable::from_pairs_dyn`]).
+    /// Requesting it from [`DropTable::from_pairs_using`] builds an alias
+    /// table.
+    ///
+    /// [`DropTable::from_pairs_dyn`]: crate::DropTable::from_pairs_dyn
+    /// [`DropTable::from_pairs_using`]: crate::DropTable::from_pairs_using
+    Custom,
+}
+
+impl SamplerKind {
+    /// Largest `n` for which [`Auto`](Self::Auto) prefers a CDF search.
+    pub const AUTO_CDF_MAX_LEN: usize = 64;
+
+    /// What [`Auto`](Self::Auto) resolves to for these weights; other kinds
+    /// resolve to themselves.
+    pub fn resolve(self, weights: &[f32]) -> SamplerKind {
+        match self {
+            SamplerKind::Auto => {
+                let first = weights.first().copied().unwrap_or(0.0);
+                if first > 0.0 && weights.iter().all(|&w| w == first) {
+                    SamplerKind::Uniform
+                } else if weights.len() <= Self::AUTO_CDF_MAX_LEN {
+                    SamplerKind::Cdf
+                } else {
+                    SamplerKind::Alias
+                }
+            }
+            kind => kind,
+        }
+    }
+}
+
+/// The sampler actually stored in a table.
+#[derive(Clone)]
+pub(crate) enum TableSampler {
+    Alias(WeightedSampler),
+    Cdf(CdfSampler),
+    Uniform(UniformSampler),
+    Small(SmallSampler),
+    Dyn(Arc<dyn IndexSampler + Send + Sync>),
+}
+
+impl fmt::Debug for TableSampler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableSampler::Alias(s) => f.debug_tuple("Alias").field(s).finish(),
+            TableSampler::Cdf(s) => f.debug_tuple("Cdf").field(s).finish(),
+            TableSampler::Uniform(s) => f.debug_tuple("Uniform").field(s).finish(),
+            TableSampler::Small(s) => f.debug_tuple("Small").field(s).finish(),
+            TableSampler::Dyn(s) => f.debug_struct("Dyn").field("len", &s.len()).finish(),
+        }
+    }
+}
+
+impl TableSampler {
+    pub(crate) fn build(weights: &[f32], kind: SamplerKind) -> Result<Self, ProbError> {
+        Ok(match kind.resolve(weights) {
+            SamplerKind::Cdf => TableSampler::Cdf(CdfSampler::new(weights)?),
+            SamplerKind::Uniform => {
+                validate_weights(weights)?;
+                if let Some(index) = weights.iter().position(|&w| w != weights[0]) {
+                    return Err(ProbError::NotUniform { index });
+                }
+                TableSampler::Uniform(UniformSampler::new(weights.len())?)
+            }
+            SamplerKind::Small if weights.len() <= SmallSampler::MAX_LEN => {
+                TableSampler::Small(SmallSampler::new(weights)?)
+            }
+            _ => TableSampler::Alias(WeightedSampler::new(weights)?),
+        })
+    }
+
+    pub(crate) fn kind(&self) -> SamplerKind {
+        match self {
+            TableSampler::Alias(_) => SamplerKind::Alias,
+            TableSampler::Cdf(_) => SamplerKind::Cdf,
+            TableSampler::Uniform(_) => SamplerKind::Uniform,
+            TableSampler::Small(_) => SamplerKind::Small,
+            TableSampler::Dyn(_) => SamplerKind::Custom,
+        }
+    }
+
+    /// Heap bytes held by the sampler; custom samplers count as zero.
+    #[cfg(feature = "registry")]
+    pub(crate) fn heap_bytes(&self) -> usize {
+        match self {
+            TableSampler::Alias(s) => std::mem::size_of_val(s.as_buckets()),
+            TableSampler::Cdf(s) => s.len() * std::mem::size_of::<u64>(),
+            TableSampler::Uniform(_) | TableSampler::Small(_) | TableSampler::Dyn(_) => 0,
+        }
+    }
+}
+
+impl IndexSampler for TableSampler {
+    #[inline]
+    fn len(&self) -> usize {
+        match self {
+            TableSampler::Alias(s) => IndexSampler::len(s),
+            TableSampler::Cdf(s) => s.len(),
+            TableSampler::Uniform(s) => s.len(),
+            TableSampler::Small(s) => s.len(),
+            TableSampler::Dyn(s) => s.len(),
+        }
+    }
+    #[inline]
+    fn sample_index<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        match self {
+            TableSampler::Alias(s) => s.sample_index(rng),
+            TableSampler::Cdf(s) => s.sample_index(rng),
+            TableSampler::Uniform(s) => s.sample_index(rng),
+            TableSampler::Small(s) => s.sample_index(rng),
+            TableSampler::Dyn(s) => s.sample_index(rng),
+        }
+    }
+    #[inline]
+    fn sample_index_dyn(&self, rng: &mut dyn RngCore) -> usize {
+        self.sample_index(rng)
+    }
+}
@@ -58,7 +58,9 @@
 //! ## Performance
 //! * **Build**: O(n) to construct an alias table from weights.
 //! * **Sample**: O(1) per draw (2 random numbers, 1 branch).
-//! * **Space**: 2 vectors of length `n` (f32 + usize).
+//! * **Space**: one vector of `n` [`Bucket`]s, each a 53-bit `u64`
+//!   threshold plus a `u32` alias (`u64` with feature `u64-alias`): 16
+//!   bytes per entry either way, counting padding.
 //!
 //! ## Gotchas
 //! * Weights must be **non-negative** and not all zero; `NaN`/∞ are rejected.
@@ -72,43 +74,540 @@
 //!
 //! `rand` integration uses the modern `Rng::random()` / `random_range()` APIs
 
+// lets the derive macros' `droptables::` paths resolve inside this crate
+extern crate self as droptables;
+
+mod arraytable;
+pub mod behavior;
+#[cfg(feature = "bench-support")]
+pub mod bench_support;
+mod binomial;
+mod boxed;
+mod budget;
+mod builder;
+pub mod calibrate;
+#[cfg(feature = "capi")]
+pub mod capi;
+mod cdf;
+mod chance;
+pub mod chunked;
+mod combine;
+mod compiled;
+mod constraints;
+mod correlated;
+#[cfg(feature = "csv")]
+pub mod csv;
+mod depletable;
+mod diff;
+mod empirical;
 mod error;
+mod export;
+#[cfg(feature = "deterministic")]
+mod fixed;
+mod flagged;
+mod grid;
+#[cfg(feature = "hotreload")]
+pub mod hotreload;
+mod joint;
+mod kind;
+mod mapped;
+pub mod markov;
+#[cfg(feature = "debug-metrics")]
+mod metrics;
+mod mixture;
+pub mod modifiers;
+mod multi;
+mod nested;
+mod normalize;
+mod odds;
+pub mod packs;
+pub mod parse;
+mod picker;
+mod piecewise;
+#[cfg(feature = "registry")]
+pub mod registry;
+#[cfg(feature = "remote")]
+pub mod remote;
+mod report;
+#[cfg(feature = "smallrng")]
+mod rng;
+mod sampleable;
 mod sampler;
+mod scheduled;
+mod shadow;
+mod simulate;
+mod small;
+mod sparse;
 mod staticdt;
+pub mod stats;
+mod sticky;
+#[cfg(feature = "proptest-support")]
+pub mod strategies;
+mod tableref;
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
+mod tempered;
+pub mod testing;
 mod uniform;
+pub mod verify;
+mod version;
 mod walker;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod without_replacement;
 
 /// A minimal interface for “index samplers”.
 /// Implemented by `WeightedSampler` (weighted) and `UniformSampler` (equal odds).
-#[allow(clippy::len_without_is_empty)]
+///
+/// The trait is object safe: [`sample_index_dyn`](Self::sample_index_dyn)
+/// takes a `&mut dyn RngCore`, so heterogeneous samplers can live behind
+/// `Box<dyn IndexSampler>`. Concrete samplers also override the generic
+/// [`sample_index`](Self::sample_index) to keep the statically dispatched
+/// path free of virtual calls.
+///
+/// ```
+/// use droptables::{IndexSampler, StaticDropTable, UniformSampler, WeightedSampler};
+///
+/// let samplers: Vec<Box<dyn IndexSampler>> = vec![
+///     Box::new(UniformSampler::new(3).unwrap()),
+///     Box::new(WeightedSampler::new(&[0.0, 0.0, 1.0]).unwrap()),
+/// ];
+/// let mut rng = rand::rng();
+/// assert!(samplers.iter().all(|s| s.sample_index_dyn(&mut rng) < 3));
+///
+/// static ITEMS: [&str; 3] = ["a", "b", "c"];
+/// let table = StaticDropTable::new(samplers.into_iter().last().unwrap(), &ITEMS);
+/// assert_eq!(*table.sample(&mut rng), "c");
+/// ```
 pub trait IndexSampler {
     fn len(&self) -> usize;
-    fn sample_index<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> usize;
+
+    /// `len() == 0`. No sampler in this crate can be built empty, but
+    /// custom ones may be.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Object-safe sampling entry point.
+    fn sample_index_dyn(&self, rng: &mut dyn rand::RngCore) -> usize;
+
+    /// Draw an index in `0..len()`. Defaults to
+    /// [`sample_index_dyn`](Self::sample_index_dyn).
+    #[inline]
+    fn sample_index<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> usize
+    where
+        Self: Sized,
+    {
+        let mut rng = rng;
+        self.sample_index_dyn(&mut rng)
+    }
+}
+
+impl<S: IndexSampler + ?Sized> IndexSampler for Box<S> {
+    #[inline]
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+    #[inline]
+    fn sample_index_dyn(&self, rng: &mut dyn rand::RngCore) -> usize {
+        (**self).sample_index_dyn(rng)
+    }
 }
 
-pub use error::ProbError;
+impl<S: IndexSampler + ?Sized> IndexSampler for std::sync::Arc<S> {
+    #[inline]
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+    #[inline]
+    fn sample_index_dyn(&self, rng: &mut dyn rand::RngCore) -> usize {
+        (**self).sample_index_dyn(rng)
+    }
+}
+
+impl<S: IndexSampler + ?Sized> IndexSampler for &S {
+    #[inline]
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+    #[inline]
+    fn sample_index_dyn(&self, rng: &mut dyn rand::RngCore) -> usize {
+        (**self).sample_index_dyn(rng)
+    }
+}
+
+pub use arraytable::DropTableN;
+pub use builder::{DropTableBuilder, LootTable, RollResult};
+pub use cdf::CdfSampler;
+pub use chance::Chance;
+pub use compiled::{CompiledError, CompiledTable};
+pub use constraints::{ConstraintError, ConstraintSet, roll_conditioned, roll_with_constraints};
+pub use correlated::CorrelatedRolls;
+pub use depletable::DepletableDropTable;
+pub use diff::TableDiff;
+pub use droptables_odds::OddsParseError;
+pub use error::{InvariantError, ProbError, UnknownVariant};
+#[cfg(feature = "deterministic")]
+pub use fixed::FixedAliasTable;
+pub use flagged::FlaggedTable;
+pub use grid::{GridContradiction, GridPicker};
+pub use joint::JointTable;
+pub use kind::SamplerKind;
+pub use mapped::MappedTable;
+#[cfg(feature = "debug-metrics")]
+pub use metrics::AliasMetrics;
+pub use mixture::MixtureSampler;
+pub use multi::MultiTable;
+pub use nested::{Drop, NestedEntry, NestedTable};
+pub use normalize::{BuildOptions, NormalizationReport, ZeroPolicy};
+pub use odds::Odds;
+pub use parse::{ParseError, ParseErrorKind};
+pub use picker::Picker;
+pub use piecewise::{Piecewise, Shape, ShapedBin};
+pub use report::ProbabilityReport;
+#[cfg(feature = "smallrng")]
+pub use rng::DropRng;
+pub use sampleable::Sampleable;
 pub use sampler::UniformSampler;
+pub use scheduled::ScheduledDropTable;
+pub use shadow::{ShadowStats, ShadowTable};
+pub use simulate::{SimulationReport, SimulationRow, simulate};
+pub use small::SmallSampler;
+pub use sparse::SparseDropTable;
 pub use staticdt::StaticDropTable;
+pub use sticky::StickyTable;
+pub use tableref::DropTableRef;
+pub use tempered::TemperedDropTable;
 pub use uniform::{UniformEnum, UniformTable};
-pub use walker::WeightedSampler;
+pub use walker::{
+    AliasTable, Bucket, BucketCorrection, BuildReport, NegativeZero, WeightedSampler,
+};
+#[cfg(feature = "wasm")]
+pub use wasm::JsDropTable;
 
 use rand::Rng;
 
+/// Re-export so generated code (and callers) can name `rand` types without
+/// adding their own dependency.
+pub use rand;
+
 /// A generic “drop table”: associates items with weights and samples them
-/// using an internal [`WeightedSampler`].
+/// using an internal [`WeightedSampler`] (or another [`SamplerKind`]).
 ///
 /// Build it from any iterator of `(item, weight)` where `weight >= 0`.
+///
+/// ## Ordering
+/// Index `i` always refers to the `i`-th pair given at construction:
+/// [`items`](Self::items), [`weights`](Self::weights), [`iter`](Self::iter)
+/// and [`probabilities`](Self::probabilities) all follow insertion order, so
+/// identical inputs produce identical tables. Entries with equal weights are
+/// sampled with equal probability (up to `f32` rounding of the alias
+/// thresholds).
 #[derive(Debug, Clone)]
 pub struct DropTable<T> {
-    alias: WeightedSampler,
+    sampler: kind::TableSampler,
     items: Vec<T>,
+    weights: Vec<f32>,
+    version: u32,
 }
 
 pub use droptables_macros::UniformEnum;
 /// Derive macro imported from `droptables_macros`.
 /// See the crate-level example for usage.
+///
+/// Each variant takes exactly one of `#[odds = "A/B"]`,
+/// `#[probability(P)]` (`0.25`, `1 / 4` or `"1/4"`), `#[bp = N]`,
+/// `#[ppm = N]` or `#[rest]`. These must sum to 1 unless the enum has
+/// `#[weighted_enum(relative)]`, in which case they are weights normalized
+/// like [`DropTable::from_pairs`] does (and `#[rest]` is not allowed).
+/// `#[odds]` and string `#[probability]` values take any form
+/// [`Odds::parse`] accepts: `"1/128"`, `"1 in 128"`, `"0.78125%"`, `"0.0078125"`.
+///
+/// The derive sees the enum after `#[cfg]` is applied, so a compiled-out
+/// variant simply isn't in the table: with `#[rest]` its share goes to the
+/// rest variant, with `relative` the others are renormalized, and otherwise
+/// the remaining variants must still sum to 1. Explicit discriminants are
+/// fine; enums with an integer `#[repr]` also get
+/// `DISCRIMINANT_ENTRIES: &[(repr, f32)]`, keyed by `Variant as repr`.
+///
+/// `#[extends(Other, scale = 0.1)]` splices another `WeightedEnum`'s
+/// entries in at `scale`: `Enum::extended_droptable()` draws a `{Enum}Drop`
+/// wrapper, with one variant for the enum itself and one per extended table,
+/// named after its path (`Rare`, or `TablesRare` for `tables::Rare`) unless
+/// given `as = "Name"`. The enum's own variants then sum to `1 - scale`
+/// rather than 1, so its plain `droptable()` and `probability_table()` are
+/// not the full distribution; only `extended_droptable()` is.
+///
+/// ```
+/// use droptables::WeightedEnum;
+///
+/// mod gems {
+///     #[derive(Copy, Clone, Debug, droptables::WeightedEnum)]
+///     pub enum Rare {
+///         #[rest]
+///         Sapphire,
+///     }
+/// }
+/// mod runes {
+///     #[derive(Copy, Clone, Debug, droptables::WeightedEnum)]
+///     pub enum Rare {
+///         #[rest]
+///         Death,
+///     }
+/// }
+///
+/// #[derive(Copy, Clone, Debug, WeightedEnum)]
+/// #[extends(gems::Rare, scale = 0.05)]
+/// #[extends(runes::Rare, scale = 0.05, as = "Runes")]
+/// enum Goblin {
+///     #[rest]
+///     Bones,
+/// }
+///
+/// let drop = Goblin::extended_droptable().unwrap();
+/// assert_eq!(drop.len(), 3);
+/// assert!(matches!(drop.items()[1], GoblinDrop::GemsRare(_)));
+/// assert!(matches!(drop.items()[2], GoblinDrop::Runes(_)));
+/// ```
+///
+/// `#[weighted_enum(denominator = N)]` checks that every `#[odds]`,
+/// `#[probability]`, `#[bp]` and `#[ppm]` has in lowest terms a
+/// denominator dividing `N` (so with `4096`, `"3/4096"`, `"1 in 128"` and
+/// `#[bp = 625]` pass but a typo like `"600/4086"` is a compile error).
+/// Decimals are read exactly, so `#[probability(0.001)]` is 1/1000; a
+/// `#[probability]` expression that isn't a literal or a quotient of
+/// literals is rejected there, since it can't be checked.
+///
+/// With `#[weighted_enum(doc_odds)]`, a variant's odds can come from an
+/// `@odds A/B` line in its doc comment instead, so the documented rate is
+/// the one sampled:
+///
+/// ```
+/// use droptables::WeightedEnum;
+///
+/// #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+/// #[weighted_enum(doc_odds)]
+/// enum Boss {
+///     /// The boss pet.
+///     /// @odds 1/5000
+///     Pet,
+///     #[rest]
+///     Nothing,
+/// }
+/// assert_eq!(Boss::ENTRIES[0].1, 1.0 / 5000.0);
+/// ```
+///
+/// A variant with both a tag and an odds attribute must give the same
+/// rate in each.
+///
+/// `#[retired]` takes a variant out of rotation without removing it, so
+/// save games and serialized data that name it still load: it gets weight
+/// 0 and its nominal odds (if it still carries them) go to the `#[rest]`
+/// variant, which the enum must have.
+///
+/// Each variant also gets a text name, its identifier unless overridden with
+/// `#[name = "..."]`: `as_str()` returns it, and the generated `FromStr` and
+/// `TryFrom<&str>` impls parse it back (failing with [`UnknownVariant`]), so
+/// names in text configs map onto the enum without a hand-written parser.
+///
+/// `weight()` returns a variant's weight from `ENTRIES` and `odds()` its
+/// resolved probability as an [`Odds`] (`#[rest]` included), both by
+/// `match`, so a drop can be shown with "this had a 1/508 chance" cheaply.
+///
+/// Misplaced or malformed attributes are compile errors rather than being
+/// ignored:
+///
+/// ```compile_fail
+/// use droptables::WeightedEnum;
+///
+/// #[derive(Copy, Clone, WeightedEnum)]
+/// enum Loot {
+///     #[odds = "1/2"]
+///     #[weighted_enum(selftest)] // belongs on the enum
+///     A,
+///     #[rest]
+///     B,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use droptables::WeightedEnum;
+///
+/// #[derive(Copy, Clone, WeightedEnum)]
+/// enum Loot {
+///     #[odds = "1/2"]
+///     A,
+///     #[retired] // no #[rest] to take its odds
+///     #[odds = "1/2"]
+///     B,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use droptables::WeightedEnum;
+///
+/// #[derive(Copy, Clone, WeightedEnum)]
+/// enum Loot {
+///     #[odds = "1/2"]
+///     A,
+///     #[rest = "1/2"] // takes no value
+///     B,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use droptables::WeightedEnum;
+///
+/// #[derive(Copy, Clone, WeightedEnum)]
+/// #[weighted_enum(doc_odds)]
+/// enum Loot {
+///     /// @odds 1/64
+///     #[odds = "1/128"] // drifted from the doc
+///     A,
+///     #[rest]
+///     B,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use droptables::WeightedEnum;
+///
+/// #[derive(Copy, Clone, WeightedEnum)]
+/// #[weighted_enum(denominator = 4096)]
+/// enum Loot {
+///     #[odds = "600/4086"] // typo: 4086 doesn't divide 4096
+///     A,
+///     #[rest]
+///     B,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use droptables::WeightedEnum;
+///
+/// #[derive(Copy, Clone, WeightedEnum)]
+/// #[weighted_enum(denominator = 4096)]
+/// enum Loot {
+///     #[probability("600/4086")]
+///     A,
+///     #[rest]
+///     B,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use droptables::WeightedEnum;
+///
+/// #[derive(Copy, Clone, WeightedEnum)]
+/// #[weighted_enum(denominator = 4096)]
+/// enum Loot {
+///     #[probability(600 / 4086)]
+///     A,
+///     #[rest]
+///     B,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use droptables::WeightedEnum;
+///
+/// #[derive(Copy, Clone, WeightedEnum)]
+/// #[weighted_enum(denominator = 4096)]
+/// enum Loot {
+///     #[probability(0.001)] // 1/1000
+///     A,
+///     #[rest]
+///     B,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use droptables::WeightedEnum;
+///
+/// #[derive(Copy, Clone, WeightedEnum)]
+/// #[weighted_enum(denominator = 4096)]
+/// enum Loot {
+///     #[bp = 125] // 1/80
+///     A,
+///     #[rest]
+///     B,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use droptables::WeightedEnum;
+///
+/// #[derive(Copy, Clone, WeightedEnum)]
+/// #[weighted_enum(denominator = 4096)]
+/// enum Loot {
+///     #[ppm = 300] // 3/10000
+///     A,
+///     #[rest]
+///     B,
+/// }
+/// ```
 pub use droptables_macros::WeightedEnum;
 
+/// Compile-time check that a drop-rate config file agrees with a
+/// [`WeightedEnum`], for projects that keep rates in both places.
+///
+/// `assert_table_matches!("loot/rarity.toml", Rarity)` reads the file
+/// (relative to the crate's `Cargo.toml`) while compiling and fails the
+/// build if an entry names no variant, gives a different rate than the
+/// derive resolved (within `f32` rounding; `#[rest]` included), or if a
+/// variant with nonzero weight is missing. Retired variants may be left out.
+///
+/// The file is flat TOML: `Name = value` lines, names matching
+/// `as_str()`, values as numbers or quoted strings in the [`Odds::parse`]
+/// grammar. `table = "name"` reads the entries under a `[name]` header
+/// instead of the top level:
+///
+/// ```toml
+/// [rarity]
+/// Mythic = "1/1000"
+/// Legendary = "1 in 100"
+/// Uncommon = "20%"
+/// Common = 0.789
+/// ```
+///
+/// ```
+/// use droptables::{WeightedEnum, assert_table_matches};
+///
+/// #[derive(Copy, Clone, WeightedEnum)]
+/// enum Rarity {
+///     #[odds = "1/1000"]
+///     Mythic,
+///     #[odds = "1/100"]
+///     Legendary,
+///     #[odds = "20/100"]
+///     Uncommon,
+///     #[rest]
+///     Common,
+/// }
+///
+/// assert_table_matches!("examples/rarity.toml", Rarity);
+/// ```
+///
+/// ```compile_fail
+/// use droptables::{WeightedEnum, assert_table_matches};
+///
+/// #[derive(Copy, Clone, WeightedEnum)]
+/// enum Rarity {
+///     #[odds = "1/1000"]
+///     Mythic,
+///     #[odds = "1/128"] // the config says 1 in 100
+///     Legendary,
+///     #[odds = "20/100"]
+///     Uncommon,
+///     #[rest]
+///     Common,
+/// }
+///
+/// assert_table_matches!("examples/rarity.toml", Rarity);
+/// ```
+pub use droptables_macros::assert_table_matches;
+
 /// Trait implemented by the `WeightedEnum` derive macro.
 ///
 /// Each variant and its weight is exposed via [`WeightedEnum::ENTRIES`],
@@ -117,6 +616,10 @@ pub trait WeightedEnum: Sized + 'static {
     /// All `(variant, weight)` pairs for the enum.
     const ENTRIES: &'static [(Self, f32)];
 
+    /// Independent draws per roll, set with `#[weighted_enum(rolls = N)]`;
+    /// the derive then also generates `roll(rng) -> [Self; N]`.
+    const ROLLS: usize = 1;
+
     /// Convenience constructor that builds a [`DropTable`] from the enum entries.
     ///
     /// # Errors
@@ -128,6 +631,31 @@ pub trait WeightedEnum: Sized + 'static {
     {
         DropTable::from_pairs(Self::ENTRIES.iter().copied())
     }
+
+    /// This variant's weight in [`ENTRIES`](Self::ENTRIES). The default
+    /// searches by [`discriminant`](std::mem::discriminant), so it needs no
+    /// `PartialEq`; the derive answers by `match` instead.
+    fn weight(&self) -> f32 {
+        let this = std::mem::discriminant(self);
+        Self::ENTRIES
+            .iter()
+            .find(|(v, _)| std::mem::discriminant(v) == this)
+            .map_or(0.0, |&(_, w)| w)
+    }
+
+    /// This variant's probability as the simplest fraction (`1/508`), for
+    /// showing the odds of a drop. The derive uses the resolved
+    /// probability, including a computed `#[rest]` remainder.
+    fn odds(&self) -> Odds {
+        let total: f64 = Self::ENTRIES.iter().map(|&(_, w)| f64::from(w)).sum();
+        let p = f64::from(self.weight()) / total;
+        Odds::from_prob(if p.is_finite() {
+            p.clamp(0.0, 1.0)
+        } else {
+            0.0
+        })
+        .expect("clamped to [0, 1]")
+    }
 }
 
 impl<T> DropTable<T> {
@@ -139,8 +667,36 @@ impl<T> DropTable<T> {
     /// * [`ProbError::ZeroSum`] if the sum of weights is zero or not finite.
     ///
     /// # Complexity
-    /// O(n) time / O(n) space.
+    /// O(n) time / O(n) space. Always an alias table, so seeded draws
+    /// don't change with the table's size; tiny tables can opt into
+    /// [`SamplerKind::Small`] through [`from_pairs_using`](Self::from_pairs_using).
     pub fn from_pairs<I>(pairs: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = (T, f32)>,
+    {
+        Self::from_pairs_using(pairs, SamplerKind::Alias)
+    }
+
+    /// Build from a map, assigning indices in ascending key order.
+    ///
+    /// Identical maps always produce identical tables (same item order,
+    /// same alias buckets), regardless of insertion order or platform, so a
+    /// table rebuilt from serialized data samples exactly like the original
+    /// for the same RNG stream.
+    ///
+    /// # Errors
+    /// As [`from_pairs`](Self::from_pairs).
+    pub fn from_map(map: std::collections::BTreeMap<T, f32>) -> Result<Self, ProbError> {
+        Self::from_pairs(map)
+    }
+
+    /// Build with a specific [`SamplerKind`]; use [`SamplerKind::Auto`] to
+    /// let the table pick based on size and weights.
+    ///
+    /// # Errors
+    /// As [`from_pairs`](Self::from_pairs), plus [`ProbError::NotUniform`]
+    /// if [`SamplerKind::Uniform`] is requested for unequal weights.
+    pub fn from_pairs_using<I>(pairs: I, kind: SamplerKind) -> Result<Self, ProbError>
     where
         I: IntoIterator<Item = (T, f32)>,
     {
@@ -150,8 +706,117 @@ impl<T> DropTable<T> {
             items.push(t);
             weights.push(w);
         }
-        let alias = WeightedSampler::new(&weights)?;
-        Ok(Self { alias, items })
+        let sampler = kind::TableSampler::build(&weights, kind)?;
+        Ok(Self {
+            sampler,
+            items,
+            weights,
+            version: 0,
+        })
+    }
+
+    /// Build with a caller-supplied sampler, e.g. one of several
+    /// heterogeneous samplers kept as `Box<dyn IndexSampler>`. The weights
+    /// are still recorded for [`weights`](Self::weights) and friends, but
+    /// draws come from `sampler` alone.
+    ///
+    /// # Errors
+    /// [`ProbError::Empty`] for no items, [`ProbError::Shape`] if
+    /// `sampler.len()` differs from the number of items.
+    pub fn from_pairs_dyn<I, S>(pairs: I, sampler: S) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = (T, f32)>,
+        S: IndexSampler + Send + Sync + 'static,
+    {
+        let (items, weights): (Vec<T>, Vec<f32>) = pairs.into_iter().unzip();
+        if items.is_empty() {
+            return Err(ProbError::Empty);
+        }
+        let placeholder = kind::TableSampler::Uniform(UniformSampler::new(items.len())?);
+        Self {
+            sampler: placeholder,
+            items,
+            weights,
+            version: 0,
+        }
+        .with_sampler(sampler)
+    }
+
+    /// Swap in a different sampler, keeping items and weights; mostly for
+    /// forcing outcomes in tests with [`testing::ForcedSampler`].
+    ///
+    /// # Errors
+    /// [`ProbError::Shape`] if `sampler.len()` differs from [`len`](Self::len).
+    pub fn with_sampler<S>(mut self, sampler: S) -> Result<Self, ProbError>
+    where
+        S: IndexSampler + Send + Sync + 'static,
+    {
+        if sampler.len() != self.items.len() {
+            return Err(ProbError::Shape {
+                expected: self.items.len(),
+                found: sampler.len(),
+            });
+        }
+        self.sampler = kind::TableSampler::Dyn(std::sync::Arc::new(sampler));
+        Ok(self)
+    }
+
+    /// The sampler this table was built with (never [`SamplerKind::Auto`]).
+    pub fn sampler_kind(&self) -> SamplerKind {
+        self.sampler.kind()
+    }
+
+    /// Freeze into a [`StaticDropTable`] by leaking the items, for tables
+    /// that live for the rest of the program (loaded once at startup, then
+    /// sampled forever). The memory is never reclaimed.
+    ///
+    /// # Panics
+    /// Only for tables from [`from_pairs_dyn`](Self::from_pairs_dyn) whose
+    /// recorded weights would be rejected by [`WeightedSampler::new`].
+    pub fn leak(self) -> StaticDropTable<WeightedSampler, T> {
+        let sampler = match self.sampler {
+            kind::TableSampler::Alias(alias) => alias,
+            _ => WeightedSampler::new(&self.weights)
+                .expect("DropTable::leak: recorded weights are invalid"),
+        };
+        StaticDropTable::new(sampler, Box::leak(self.items.into_boxed_slice()))
+    }
+
+    /// Append `(item, weight)` entries and rebuild the sampler with the same
+    /// [`SamplerKind`]. Existing items keep their indices; new ones follow in
+    /// iteration order. On error the table is left unchanged.
+    ///
+    /// # Errors
+    /// As [`from_pairs_using`](Self::from_pairs_using) for the combined
+    /// weights, or [`ProbError::CustomSampler`] if the table uses a
+    /// [`SamplerKind::Custom`] sampler, which cannot be rebuilt.
+    ///
+    /// # Complexity
+    /// O(n + m): the sampler is rebuilt from scratch.
+    pub fn extend_pairs<I>(&mut self, pairs: I) -> Result<(), ProbError>
+    where
+        I: IntoIterator<Item = (T, f32)>,
+    {
+        let old_len = self.items.len();
+        for (t, w) in pairs {
+            self.items.push(t);
+            self.weights.push(w);
+        }
+        let rebuilt = match self.sampler.kind() {
+            SamplerKind::Custom => Err(ProbError::CustomSampler),
+            kind => kind::TableSampler::build(&self.weights, kind),
+        };
+        match rebuilt {
+            Ok(sampler) => {
+                self.sampler = sampler;
+                Ok(())
+            }
+            Err(e) => {
+                self.items.truncate(old_len);
+                self.weights.truncate(old_len);
+                Err(e)
+            }
+        }
     }
 
     /// Sample an item **by reference** (no `Clone` bound).
@@ -167,7 +832,7 @@ impl<T> DropTable<T> {
     /// let s = table.sample(&mut rng); // &str
     /// ```
     pub fn sample<'a, R: Rng + ?Sized>(&'a self, rng: &mut R) -> &'a T {
-        let idx = self.alias.sample_index(rng);
+        let idx = self.sampler.sample_index(rng);
         &self.items[idx]
     }
 
@@ -178,23 +843,352 @@ impl<T> DropTable<T> {
     where
         T: Clone,
     {
-        self.items[self.alias.sample_index(rng)].clone()
+        self.items[self.sampler.sample_index(rng)].clone()
+    }
+
+    /// Draws [`sample_where`](Self::sample_where) rejects before switching
+    /// to an exact pass.
+    pub const SAMPLE_WHERE_RETRIES: usize = 16;
+
+    /// Sample from the table conditioned on `pred`: items it rejects are
+    /// excluded and the rest keep their relative weights, e.g. a
+    /// weapon-only roll on a general drop table.
+    ///
+    /// Draws from the full table and redraws on a rejected item; after
+    /// [`SAMPLE_WHERE_RETRIES`](Self::SAMPLE_WHERE_RETRIES) misses (most of
+    /// the weight is excluded) it falls back to one O(n) pass over the
+    /// accepted weights, so the result is exact and the cost bounded.
+    /// Returns `None` if no accepted item has positive weight.
+    ///
+    /// ```
+    /// # use droptables::DropTable;
+    /// let table = DropTable::from_pairs([("sword", 1.0), ("bones", 98.0), ("bow", 1.0)]).unwrap();
+    /// let weapon = table.sample_where(&mut rand::rng(), |i| *i != "bones");
+    /// assert!(matches!(weapon, Some(&"sword" | &"bow")));
+    /// ```
+    pub fn sample_where<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        pred: impl Fn(&T) -> bool,
+    ) -> Option<&T> {
+        self.sample_index_where(rng, |i| pred(&self.items[i]))
+            .map(|i| &self.items[i])
+    }
+
+    /// [`sample_where`](Self::sample_where) over indices.
+    pub(crate) fn sample_index_where<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        allowed: impl Fn(usize) -> bool,
+    ) -> Option<usize> {
+        for _ in 0..Self::SAMPLE_WHERE_RETRIES {
+            let i = self.sampler.sample_index(rng);
+            if allowed(i) {
+                return Some(i);
+            }
+        }
+        constraints::draw_masked_index(&self.weights, rng, allowed)
     }
 
     /// Number of items in the table.
     pub fn len(&self) -> usize {
-        self.alias.len()
+        self.sampler.len()
     }
 
     /// Whether the table is empty.
     pub fn is_empty(&self) -> bool {
-        self.alias.is_empty()
+        self.sampler.is_empty()
+    }
+
+    /// Items in insertion order.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Weights as given at construction, parallel to [`items`](Self::items).
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /// `(item, weight)` pairs in insertion order.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&T, f32)> + '_ {
+        self.items.iter().zip(self.weights.iter().copied())
+    }
+
+    /// Normalized probabilities in insertion order (computed in `f64`).
+    pub fn probabilities(&self) -> Vec<f64> {
+        let sum: f64 = self.weights.iter().map(|&w| w as f64).sum();
+        self.weights.iter().map(|&w| w as f64 / sum).collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    // the derive emits a `#[test]` that checks these weights
+    #[allow(dead_code)]
+    #[derive(Copy, Clone, Debug, WeightedEnum)]
+    #[weighted_enum(selftest)]
+    enum SelfTested {
+        #[odds = "3/10"]
+        A,
+        #[bp = 7000]
+        B,
+    }
+
+    #[test]
+    fn probability_attribute_forms() {
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        enum Loot {
+            #[probability(0.25)]
+            A,
+            #[probability(1 / 8)]
+            B,
+            #[probability("1/8")]
+            C,
+            #[rest]
+            D,
+        }
+        let probs: Vec<f64> = Loot::probability_table().iter().map(|e| e.1).collect();
+        assert_eq!(probs, [0.25, 0.125, 0.125, 0.5]);
+    }
+
+    #[test]
+    fn relative_weights_are_normalized() {
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        #[weighted_enum(relative)]
+        enum Tier {
+            #[probability(60.0)]
+            Common,
+            #[probability(30)]
+            Uncommon,
+            #[probability(9)]
+            Rare,
+            #[probability(1)]
+            Legendary,
+        }
+        let probs: Vec<f64> = Tier::probability_table().iter().map(|e| e.1).collect();
+        assert_eq!(probs, [0.6, 0.3, 0.09, 0.01]);
+    }
+
+    #[test]
+    fn derived_rolls_draw_n_times() {
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        #[weighted_enum(rolls = 2)]
+        enum Boss {
+            #[odds = "1/1"]
+            Coins,
+        }
+        assert_eq!(<Boss as WeightedEnum>::ROLLS, 2);
+        assert_eq!(<SelfTested as WeightedEnum>::ROLLS, 1);
+        let drops: [Boss; 2] = Boss::roll(&mut rand::rng());
+        assert_eq!(drops, [Boss::Coins; 2]);
+    }
+
+    #[test]
+    fn variants_know_their_odds() {
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        enum Graardor {
+            #[odds = "1/508"]
+            Hilt,
+            #[odds = "1 in 128"]
+            Chestplate,
+            #[retired]
+            #[odds = "1/64"]
+            Tassets,
+            #[rest]
+            Bones,
+        }
+        assert_eq!(Graardor::Hilt.odds(), Odds::new(1, 508).unwrap());
+        assert_eq!(Graardor::Chestplate.weight(), 1.0 / 128.0);
+        assert_eq!(Graardor::Tassets.odds(), Odds::NEVER);
+        // 1 - 1/508 - 1/128
+        assert_eq!(Graardor::Bones.odds(), Odds::new(16097, 16256).unwrap());
+        for (v, w) in Graardor::ENTRIES {
+            assert_eq!(v.weight(), *w);
+        }
+
+        // an enum's own `weight` doesn't clash with the trait's
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        enum Armour {
+            #[odds = "1/4"]
+            Plate,
+            #[rest]
+            Leather,
+        }
+        impl Armour {
+            fn weight(&self) -> u32 {
+                match self {
+                    Armour::Plate => 20,
+                    Armour::Leather => 5,
+                }
+            }
+        }
+        assert_eq!(Armour::Plate.weight(), 20);
+        assert_eq!(WeightedEnum::weight(&Armour::Plate), 0.25);
+        assert_eq!(
+            WeightedEnum::odds(&Armour::Leather),
+            Odds::new(3, 4).unwrap()
+        );
+
+        // neither the derive nor the trait defaults need `PartialEq`
+        #[derive(Copy, Clone, Debug, WeightedEnum)]
+        enum Gem {
+            #[odds = "1/8"]
+            Ruby,
+            #[rest]
+            Opal,
+        }
+        assert_eq!(Gem::Ruby.weight(), 0.125);
+        assert_eq!(Gem::Opal.odds(), Odds::new(7, 8).unwrap());
+
+        #[derive(Copy, Clone, Debug)]
+        enum Manual {
+            A,
+            B,
+        }
+        impl WeightedEnum for Manual {
+            const ENTRIES: &'static [(Self, f32)] = &[(Manual::A, 1.0), (Manual::B, 3.0)];
+        }
+        assert_eq!(Manual::B.weight(), 3.0);
+        assert_eq!(Manual::A.odds(), Odds::new(1, 4).unwrap());
+    }
+
+    #[test]
+    fn variant_names_round_trip() {
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        enum Drop {
+            #[odds = "1/2"]
+            Coins,
+            #[name = "rune-scimitar"]
+            #[rest]
+            RuneScimitar,
+        }
+        assert_eq!(Drop::Coins.as_str(), "Coins");
+        assert_eq!(Drop::RuneScimitar.as_str(), "rune-scimitar");
+        assert_eq!("rune-scimitar".parse(), Ok(Drop::RuneScimitar));
+        assert_eq!(Drop::try_from("Coins"), Ok(Drop::Coins));
+        assert_eq!(
+            "RuneScimitar".parse::<Drop>(),
+            Err(UnknownVariant {
+                name: "RuneScimitar".into()
+            })
+        );
+    }
+
+    #[test]
+    fn odds_share_the_declared_denominator() {
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        #[weighted_enum(denominator = 4096)]
+        enum Herb {
+            #[odds = "600/4096"]
+            Guam,
+            #[odds = "1 in 128"]
+            Ranarr,
+            #[odds = "0.78125%"]
+            Snapdragon,
+            #[probability(3 / 4096)]
+            Torstol,
+            #[probability("1/2048")]
+            Lantadyme,
+            #[probability(0.0625)]
+            Cadantine,
+            #[bp = 625]
+            Dwarf,
+            #[ppm = 15625]
+            Avantoe,
+            #[rest]
+            Nothing,
+        }
+        assert_eq!(Herb::ENTRIES[1], (Herb::Ranarr, 32.0 / 4096.0));
+        assert_eq!(Herb::ENTRIES[2], (Herb::Snapdragon, 32.0 / 4096.0));
+    }
+
+    #[test]
+    fn doc_comments_carry_odds() {
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        #[weighted_enum(doc_odds, denominator = 512)]
+        enum Clue {
+            /// Elite clue scroll. @odds 1/256
+            Elite,
+            /// @odds 1/128
+            #[odds = "4/512"]
+            Hard,
+            /// Plain docs without a tag
+            #[odds = "1/512"]
+            Medium,
+            #[rest]
+            Nothing,
+        }
+        assert_eq!(Clue::ENTRIES[0], (Clue::Elite, 1.0 / 256.0));
+        assert_eq!(Clue::ENTRIES[1], (Clue::Hard, 1.0 / 128.0));
+        assert_eq!(Clue::ENTRIES[3].1, 1.0 - 7.0 / 512.0);
+    }
+
+    #[test]
+    fn retired_variants_feed_rest() {
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        enum Reward {
+            #[odds = "1/10"]
+            Hat,
+            #[retired]
+            #[odds = "1/5"]
+            EventCape,
+            #[retired]
+            OldBadge,
+            #[rest]
+            Coins,
+        }
+        assert_eq!(
+            Reward::ENTRIES,
+            [
+                (Reward::Hat, 0.1),
+                (Reward::EventCape, 0.0),
+                (Reward::OldBadge, 0.0),
+                (Reward::Coins, 0.9),
+            ]
+        );
+        assert_eq!("EventCape".parse(), Ok(Reward::EventCape));
+        let table = Reward::droptable().unwrap();
+        let mut rng = rand::rng();
+        assert!((0..1_000).all(|_| *table.sample(&mut rng) != Reward::EventCape));
+    }
+
+    #[test]
+    fn repr_and_cfg_variants() {
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        #[repr(u8)]
+        enum Gem {
+            #[odds = "1/4"]
+            Ruby = 3,
+            #[cfg(any())]
+            #[odds = "1/4"]
+            Opal = 7,
+            #[rest]
+            Jade = 9,
+        }
+        // Opal's share went to #[rest]
+        assert_eq!(Gem::DISCRIMINANT_ENTRIES, [(3, 0.25), (9, 0.75)]);
+        assert_eq!(Gem::droptable().unwrap().len(), 2);
+
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        #[weighted_enum(relative)]
+        enum Ore {
+            #[probability(1)]
+            Iron,
+            #[cfg(any())]
+            #[probability(2)]
+            Mithril,
+            #[probability(3)]
+            Coal,
+        }
+        assert_eq!(
+            Ore::probability_table(),
+            [(Ore::Iron, 0.25), (Ore::Coal, 0.75)]
+        );
+    }
 
     #[test]
     fn smoke_pairs() {
@@ -202,4 +1196,253 @@ mod tests {
         let mut rng = rand::rng();
         let _ = dt.sample(&mut rng);
     }
+
+    #[cfg(feature = "cache")]
+    #[test]
+    fn cached_droptable_is_shared() {
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        enum Coin {
+            #[odds = "1/2"]
+            Heads,
+            #[rest]
+            Tails,
+        }
+        let a = Coin::droptable_cached();
+        assert!(std::ptr::eq(a, Coin::droptable_cached()));
+        assert_eq!(a.items(), &[Coin::Heads, Coin::Tails]);
+    }
+
+    #[test]
+    fn derived_one_line_sample() {
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        enum Gate {
+            #[odds = "1/1"]
+            Open,
+            #[rest]
+            Shut,
+        }
+        #[derive(Copy, Clone, Debug, PartialEq, UniformEnum)]
+        enum Only {
+            One,
+        }
+        let mut rng = rand::rng();
+        assert_eq!(Gate::sample(&mut rng), Gate::Open);
+        assert_eq!(Only::sample(&mut rng), Only::One);
+        assert_eq!(
+            Gate::droptable_unchecked().sample_owned(&mut rng),
+            Gate::Open
+        );
+        assert_eq!(
+            Only::droptable_unchecked().sample_owned(&mut rng),
+            Only::One
+        );
+        assert!(std::ptr::eq(
+            Gate::droptable_unchecked(),
+            Gate::droptable_unchecked()
+        ));
+
+        #[derive(Copy, Clone, Debug, PartialEq, UniformEnum)]
+        enum Never {}
+        assert_eq!(Never::droptable().unwrap_err(), ProbError::Empty);
+    }
+
+    #[test]
+    fn derived_probability_table_resolves_rest() {
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        enum Drop {
+            #[odds = "1/8"]
+            Rare,
+            #[rest]
+            Junk,
+        }
+        assert_eq!(
+            Drop::probability_table(),
+            &[(Drop::Rare, 0.125), (Drop::Junk, 0.875)]
+        );
+        let report = Drop::probability_report().to_string();
+        assert!(report.contains("Junk") && report.contains("1 in 8.00"));
+    }
+
+    #[test]
+    fn derived_basis_points_and_ppm() {
+        #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+        enum Gacha {
+            #[ppm = 300]
+            Ssr,
+            #[bp = 125]
+            Sr,
+            #[rest]
+            R,
+        }
+        let t = Gacha::probability_table();
+        assert_eq!(t[0].1, 0.0003);
+        assert_eq!(t[1].1, 0.0125);
+        assert!((t[2].1 - (1.0 - 0.0003 - 0.0125)).abs() < 1e-12);
+    }
+
+    #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+    enum SharedRare {
+        #[odds = "1/2"]
+        Key,
+        #[rest]
+        Rune,
+    }
+
+    #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+    #[extends(SharedRare, scale = 0.1)]
+    #[weighted_enum(selftest)]
+    enum Boss {
+        #[odds = "1/10"]
+        Hilt,
+        #[rest]
+        Bones,
+    }
+
+    #[test]
+    fn derived_extends_splices_scaled_entries() {
+        let table = Boss::extended_droptable().unwrap();
+        let probs: Vec<f64> = table.probabilities();
+        let want = [0.1, 0.8, 0.05, 0.05];
+        for (p, w) in probs.iter().zip(want) {
+            assert!((p - w).abs() < 1e-6, "{probs:?}");
+        }
+        let sources: Vec<String> = table
+            .items()
+            .iter()
+            .map(|d| match d {
+                BossDrop::Boss(b) => format!("boss:{b:?}"),
+                BossDrop::SharedRare(r) => format!("rare:{r:?}"),
+            })
+            .collect();
+        assert_eq!(
+            sources,
+            ["boss:Hilt", "boss:Bones", "rare:Key", "rare:Rune"]
+        );
+    }
+
+    #[test]
+    fn auto_sampler_kind() {
+        let uniform = DropTable::from_pairs_using([(1, 2.0), (2, 2.0)], SamplerKind::Auto).unwrap();
+        assert_eq!(uniform.sampler_kind(), SamplerKind::Uniform);
+        let small = DropTable::from_pairs_using([(1, 1.0), (2, 2.0)], SamplerKind::Auto).unwrap();
+        assert_eq!(small.sampler_kind(), SamplerKind::Cdf);
+        let big = DropTable::from_pairs_using((0..1000).map(|i| (i, i as f32)), SamplerKind::Auto)
+            .unwrap();
+        assert_eq!(big.sampler_kind(), SamplerKind::Alias);
+        assert_eq!(
+            DropTable::from_pairs_using([(1, 1.0), (2, 2.0)], SamplerKind::Uniform).unwrap_err(),
+            ProbError::NotUniform { index: 1 }
+        );
+
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            assert_ne!(*small.sample(&mut rng), 0);
+        }
+    }
+
+    #[test]
+    fn from_map_orders_by_key() {
+        use std::collections::BTreeMap;
+        let a = BTreeMap::from([("zeta", 1.0), ("alpha", 2.0), ("mid", 0.5)]);
+        let b = BTreeMap::from([("mid", 0.5), ("zeta", 1.0), ("alpha", 2.0)]);
+        let (ta, tb) = (
+            DropTable::from_map(a).unwrap(),
+            DropTable::from_map(b).unwrap(),
+        );
+        assert_eq!(ta.items(), ["alpha", "mid", "zeta"]);
+        assert_eq!(ta.weights(), tb.weights());
+
+        let (mut ra, mut rb) = (StdRng::seed_from_u64(1351), StdRng::seed_from_u64(1351));
+        for _ in 0..100 {
+            assert_eq!(ta.sample(&mut ra), tb.sample(&mut rb));
+        }
+    }
+
+    #[test]
+    fn leak_keeps_distribution() {
+        let dt = DropTable::from_pairs_using([("a", 0.0), ("b", 1.0)], SamplerKind::Cdf).unwrap();
+        let st: StaticDropTable<WeightedSampler, &str> = dt.leak();
+        assert_eq!(st.items(), ["a", "b"]);
+        assert_eq!(*st.sample(&mut rand::rng()), "b");
+    }
+
+    #[test]
+    fn extend_pairs_keeps_indices() {
+        let mut dt = DropTable::from_pairs([("base", 1.0)]).unwrap();
+        dt.extend_pairs([("dlc", 0.0), ("mod", 3.0)]).unwrap();
+        assert_eq!(dt.items(), ["base", "dlc", "mod"]);
+        assert_eq!(dt.probabilities(), [0.25, 0.0, 0.75]);
+
+        let err = dt.extend_pairs([("bad", -1.0)]).unwrap_err();
+        assert!(matches!(err, ProbError::Negative { index: 3, .. }));
+        assert_eq!(dt.len(), 3);
+
+        let sampler = WeightedSampler::new(&[1.0]).unwrap();
+        let mut custom = DropTable::from_pairs_dyn([("base", 1.0)], sampler).unwrap();
+        let err = custom.extend_pairs([("dlc", 1.0)]).unwrap_err();
+        assert_eq!(err, ProbError::CustomSampler);
+        assert_eq!(custom.len(), 1);
+    }
+
+    #[test]
+    fn sample_where_is_conditional_and_exact() {
+        let table =
+            DropTable::from_pairs([("bones", 997.0), ("sword", 1.0), ("bow", 2.0)]).unwrap();
+        let mut rng = rand::rng();
+        let mut bows = 0;
+        for _ in 0..6_000 {
+            // almost every draw is rejected, so this exercises the exact pass
+            let w = table.sample_where(&mut rng, |i| *i != "bones").unwrap();
+            assert_ne!(*w, "bones");
+            bows += usize::from(*w == "bow");
+        }
+        assert!((bows as f64 / 6_000.0 - 2.0 / 3.0).abs() < 0.03, "{bows}");
+        assert_eq!(table.sample_where(&mut rng, |i| i.is_empty()), None);
+    }
+
+    #[test]
+    fn len_and_is_empty_agree() {
+        static ITEMS: [u8; 2] = [1, 2];
+        let st = StaticDropTable::new(UniformSampler::new(2).unwrap(), &ITEMS);
+        assert_eq!((st.len(), st.is_empty()), (2, false));
+        let dt = DropTable::from_pairs([(1, 1.0)]).unwrap();
+        assert_eq!((dt.len(), dt.is_empty()), (1, false));
+        let boxed: Box<dyn IndexSampler> = Box::new(WeightedSampler::new(&[1.0]).unwrap());
+        assert!(!boxed.is_empty());
+    }
+
+    #[test]
+    fn boxed_dyn_sampler() {
+        let boxed: Box<dyn IndexSampler + Send + Sync> =
+            Box::new(WeightedSampler::new(&[0.0, 1.0]).unwrap());
+        let dt = DropTable::from_pairs_dyn([("a", 1.0), ("b", 1.0)], boxed).unwrap();
+        assert_eq!(dt.sampler_kind(), SamplerKind::Custom);
+        let mut rng = rand::rng();
+        assert_eq!(*dt.sample(&mut rng), "b");
+
+        let wrong = UniformSampler::new(3).unwrap();
+        assert_eq!(
+            DropTable::from_pairs_dyn([("a", 1.0)], wrong).unwrap_err(),
+            ProbError::Shape {
+                expected: 1,
+                found: 3
+            }
+        );
+    }
+
+    #[test]
+    fn insertion_order_and_equal_weights() {
+        let dt = DropTable::from_pairs([("z", 2.0), ("a", 1.0), ("m", 2.0), ("b", 2.0)]).unwrap();
+        let order: Vec<_> = dt.iter().map(|(t, _)| *t).collect();
+        assert_eq!(order, ["z", "a", "m", "b"]);
+        assert_eq!(
+            dt.probabilities(),
+            vec![2.0 / 7.0, 1.0 / 7.0, 2.0 / 7.0, 2.0 / 7.0]
+        );
+
+        let effective = WeightedSampler::new(dt.weights()).unwrap().probabilities();
+        for i in [2, 3] {
+            assert!((effective[i] - effective[0]).abs() < 1e-6, "{effective:?}");
+        }
+    }
 }
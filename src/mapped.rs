@@ -0,0 +1,68 @@
+use rand::Rng;
+
+use crate::DropTable;
+
+impl<T> DropTable<T> {
+    /// Transform every item, keeping weights and the built sampler.
+    ///
+    /// O(n) for the items; the sampler is moved, not rebuilt.
+    pub fn map_items<U, F: FnMut(T) -> U>(self, f: F) -> DropTable<U> {
+        DropTable {
+            sampler: self.sampler,
+            items: self.items.into_iter().map(f).collect(),
+            weights: self.weights,
+            version: self.version,
+        }
+    }
+
+    /// A view whose [`sample`](MappedTable::sample) returns `f(item)`;
+    /// nothing is copied or rebuilt.
+    ///
+    /// ```
+    /// use droptables::DropTable;
+    ///
+    /// struct ItemDef { name: &'static str }
+    /// let defs = [ItemDef { name: "sword" }, ItemDef { name: "shield" }];
+    ///
+    /// let ids = DropTable::from_pairs([(0usize, 0.0), (1, 1.0)]).unwrap();
+    /// let view = ids.mapped(|&id| &defs[id]);
+    /// assert_eq!(view.sample(&mut rand::rng()).name, "shield");
+    /// ```
+    pub fn mapped<'a, U, F: Fn(&'a T) -> U>(&'a self, f: F) -> MappedTable<'a, T, U, F> {
+        MappedTable { table: self, f }
+    }
+}
+
+/// Borrowing view over a [`DropTable`] that maps each sampled item through
+/// `F`. Created by [`DropTable::mapped`].
+#[derive(Clone, Copy)]
+pub struct MappedTable<'a, T, U, F: Fn(&'a T) -> U> {
+    table: &'a DropTable<T>,
+    f: F,
+}
+
+impl<'a, T, U, F: Fn(&'a T) -> U> MappedTable<'a, T, U, F> {
+    #[inline]
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> U {
+        (self.f)(self.table.sample(rng))
+    }
+
+    /// The underlying table.
+    pub fn base(&self) -> &'a DropTable<T> {
+        self.table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_items_keeps_weights() {
+        let dt = DropTable::from_pairs([(1, 0.0), (2, 3.0)]).unwrap();
+        let strs = dt.map_items(|n| n.to_string());
+        assert_eq!(strs.items(), ["1", "2"]);
+        assert_eq!(strs.weights(), [0.0, 3.0]);
+        assert_eq!(*strs.sample(&mut rand::rng()), "2");
+    }
+}
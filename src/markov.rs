@@ -0,0 +1,182 @@
+//! Markov chains with one alias table per row: every transition is an O(1)
+//! categorical draw.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+use rand::Rng;
+
+use crate::error::ProbError;
+use crate::walker::WeightedSampler;
+
+/// Why a [`MarkovChain`] could not be built.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkovError {
+    /// The matrix is not `states.len()` square.
+    Shape {
+        row: Option<usize>,
+        expected: usize,
+        found: usize,
+    },
+    /// The same state appears twice.
+    DuplicateState { index: usize },
+    /// A row's weights were rejected.
+    Row { row: usize, error: ProbError },
+}
+
+impl fmt::Display for MarkovError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarkovError::Shape {
+                row: None,
+                expected,
+                found,
+            } => write!(f, "expected {expected} rows, found {found}"),
+            MarkovError::Shape {
+                row: Some(row),
+                expected,
+                found,
+            } => write!(f, "row {row}: expected {expected} columns, found {found}"),
+            MarkovError::DuplicateState { index } => write!(f, "duplicate state at index {index}"),
+            MarkovError::Row { row, error } => write!(f, "row {row}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for MarkovError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MarkovError::Row { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// A finite Markov chain over states `S`.
+#[derive(Debug, Clone)]
+pub struct MarkovChain<S> {
+    states: Vec<S>,
+    index: HashMap<S, usize>,
+    rows: Vec<WeightedSampler>,
+}
+
+impl<S: Hash + Eq + Clone> MarkovChain<S> {
+    /// Build from the states and an `n x n` transition weight matrix where
+    /// `matrix[i][j]` weights the move from `states[i]` to `states[j]`.
+    /// Rows need not be normalized.
+    ///
+    /// # Errors
+    /// A [`MarkovError`] naming the offending row or state.
+    pub fn from_matrix<M, Row>(states: Vec<S>, matrix: M) -> Result<Self, MarkovError>
+    where
+        M: IntoIterator<Item = Row>,
+        Row: AsRef<[f32]>,
+    {
+        let n = states.len();
+        let mut index = HashMap::with_capacity(n);
+        for (i, s) in states.iter().enumerate() {
+            if index.insert(s.clone(), i).is_some() {
+                return Err(MarkovError::DuplicateState { index: i });
+            }
+        }
+        let mut rows = Vec::with_capacity(n);
+        for (row, weights) in matrix.into_iter().enumerate() {
+            let weights = weights.as_ref();
+            if weights.len() != n {
+                return Err(MarkovError::Shape {
+                    row: Some(row),
+                    expected: n,
+                    found: weights.len(),
+                });
+            }
+            rows.push(
+                WeightedSampler::new(weights).map_err(|error| MarkovError::Row { row, error })?,
+            );
+        }
+        if rows.len() != n {
+            return Err(MarkovError::Shape {
+                row: None,
+                expected: n,
+                found: rows.len(),
+            });
+        }
+        Ok(Self {
+            states,
+            index,
+            rows,
+        })
+    }
+}
+
+impl<S: Hash + Eq> MarkovChain<S> {
+    /// Next state after `state`, or `None` if `state` is not in the chain.
+    pub fn step<R: Rng + ?Sized>(&self, state: &S, rng: &mut R) -> Option<&S> {
+        let i = *self.index.get(state)?;
+        Some(&self.states[self.step_index(i, rng)])
+    }
+
+    /// Next state index after state index `i`.
+    ///
+    /// # Panics
+    /// If `i` is out of range.
+    pub fn step_index<R: Rng + ?Sized>(&self, i: usize, rng: &mut R) -> usize {
+        self.rows[i].sample_index(rng)
+    }
+
+    /// The `n` states visited after `start` (excluding `start`), or `None`
+    /// if `start` is not in the chain.
+    pub fn walk<R: Rng + ?Sized>(&self, start: &S, n: usize, rng: &mut R) -> Option<Vec<&S>> {
+        let mut i = *self.index.get(start)?;
+        Some(
+            (0..n)
+                .map(|_| {
+                    i = self.step_index(i, rng);
+                    &self.states[i]
+                })
+                .collect(),
+        )
+    }
+
+    /// States in index order.
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    /// Number of states.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Whether the chain has no states.
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic_cycle_and_errors() {
+        let chain = MarkovChain::from_matrix(
+            vec!['a', 'b', 'c'],
+            [[0.0, 1.0, 0.0], [0.0, 0.0, 2.0], [5.0, 0.0, 0.0]],
+        )
+        .unwrap();
+        let mut rng = rand::rng();
+        let walk: String = chain.walk(&'a', 4, &mut rng).unwrap().into_iter().collect();
+        assert_eq!(walk, "bcab");
+        assert_eq!(chain.step(&'z', &mut rng), None);
+
+        let err = MarkovChain::from_matrix(vec![1, 2], [vec![1.0, 0.0], vec![0.0, 0.0]]);
+        assert_eq!(
+            err.unwrap_err(),
+            MarkovError::Row {
+                row: 1,
+                error: ProbError::ZeroSum
+            }
+        );
+    }
+}
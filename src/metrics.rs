@@ -0,0 +1,123 @@
+//! Branch counters for [`AliasTable`](crate::AliasTable) (feature
+//! `debug-metrics`).
+//!
+//! Every draw picks a bucket and then either keeps it (the *primary* branch)
+//! or jumps to its alias. A well-conditioned table takes the alias branch
+//! about as often as `1 - prob` predicts; a bucket that aliases far more
+//! often than its [`Bucket::prob`](crate::Bucket::prob) suggests points at a
+//! construction problem. Counting costs two relaxed atomic adds per draw, so
+//! leave the feature off in release builds.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Per-bucket counters stored inside the sampler.
+#[derive(Debug, Default)]
+pub(crate) struct BranchCounters {
+    primary: Vec<AtomicU64>,
+    alias: Vec<AtomicU64>,
+}
+
+impl BranchCounters {
+    pub(crate) fn new(n: usize) -> Self {
+        Self {
+            primary: (0..n).map(|_| AtomicU64::new(0)).collect(),
+            alias: (0..n).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn record(&self, bucket: usize, took_alias: bool) {
+        let slot = if took_alias {
+            &self.alias[bucket]
+        } else {
+            &self.primary[bucket]
+        };
+        slot.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> AliasMetrics {
+        let load = |v: &[AtomicU64]| v.iter().map(|c| c.load(Ordering::Relaxed)).collect();
+        AliasMetrics {
+            primary: load(&self.primary),
+            alias: load(&self.alias),
+        }
+    }
+
+    pub(crate) fn reset(&self) {
+        for c in self.primary.iter().chain(&self.alias) {
+            c.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// A clone starts from the current counts but counts independently.
+impl Clone for BranchCounters {
+    fn clone(&self) -> Self {
+        let copy = |v: &[AtomicU64]| {
+            v.iter()
+                .map(|c| AtomicU64::new(c.load(Ordering::Relaxed)))
+                .collect()
+        };
+        Self {
+            primary: copy(&self.primary),
+            alias: copy(&self.alias),
+        }
+    }
+}
+
+/// Snapshot of branch counts, indexed by bucket (the uniformly drawn column,
+/// not the returned index).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AliasMetrics {
+    pub primary: Vec<u64>,
+    pub alias: Vec<u64>,
+}
+
+impl AliasMetrics {
+    /// Total draws recorded.
+    pub fn draws(&self) -> u64 {
+        self.primary.iter().chain(&self.alias).sum()
+    }
+
+    /// Fraction of all draws that took the alias branch.
+    pub fn alias_rate(&self) -> f64 {
+        let draws = self.draws();
+        if draws == 0 {
+            return 0.0;
+        }
+        self.alias.iter().sum::<u64>() as f64 / draws as f64
+    }
+
+    /// Fraction of draws landing in `bucket` that took its alias; compare
+    /// with `1 - prob(bucket)`.
+    pub fn bucket_alias_rate(&self, bucket: usize) -> f64 {
+        let (p, a) = (self.primary[bucket], self.alias[bucket]);
+        if p + a == 0 {
+            return 0.0;
+        }
+        a as f64 / (p + a) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AliasTable;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn alias_rate_tracks_bucket_probs() {
+        let table = AliasTable::new(&[1.0, 3.0]).unwrap();
+        let mut rng = StdRng::seed_from_u64(1343);
+        for _ in 0..40_000 {
+            table.sample_index(&mut rng);
+        }
+        let m = table.metrics();
+        assert_eq!(m.draws(), 40_000);
+        for i in 0..2 {
+            let expected = 1.0 - f64::from(table.prob(i));
+            assert!((m.bucket_alias_rate(i) - expected).abs() < 0.02);
+        }
+        table.reset_metrics();
+        assert_eq!(table.metrics().draws(), 0);
+    }
+}
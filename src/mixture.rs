@@ -0,0 +1,148 @@
+use rand::{Rng, RngCore};
+
+use crate::IndexSampler;
+use crate::error::ProbError;
+use crate::walker::WeightedSampler;
+
+/// How a mixture maps its components' indices into its own.
+#[derive(Debug, Clone)]
+enum IndexSpace {
+    /// Every component samples the same `0..n`.
+    Shared(usize),
+    /// Component `k`'s index `i` becomes `offsets[k] + i`.
+    Concat(Vec<usize>),
+}
+
+/// A mixture of index samplers: pick a component by mixing weight (itself an
+/// alias table), then sample an index from that component.
+///
+/// Useful for percentage rollouts, e.g. A/B-testing two loot economies.
+#[derive(Debug, Clone)]
+pub struct MixtureSampler<S> {
+    mix: WeightedSampler,
+    components: Vec<S>,
+    space: IndexSpace,
+}
+
+impl<S: IndexSampler> MixtureSampler<S> {
+    /// Components that share one index space (all the same `len()`).
+    ///
+    /// # Errors
+    /// * [`ProbError::Shape`] if the components' lengths differ.
+    /// * Anything [`WeightedSampler::new`] rejects for the mixing weights.
+    pub fn shared<I>(components: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = (S, f32)>,
+    {
+        let (components, weights): (Vec<S>, Vec<f32>) = components.into_iter().unzip();
+        let mix = WeightedSampler::new(&weights)?;
+        let n = components[0].len();
+        if let Some(c) = components.iter().find(|c| c.len() != n) {
+            return Err(ProbError::Shape {
+                expected: n,
+                found: c.len(),
+            });
+        }
+        Ok(Self {
+            mix,
+            components,
+            space: IndexSpace::Shared(n),
+        })
+    }
+
+    /// Components with their own index spaces, laid end to end: component
+    /// `k`'s indices come after those of components `0..k`.
+    ///
+    /// # Errors
+    /// Anything [`WeightedSampler::new`] rejects for the mixing weights.
+    pub fn concat<I>(components: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = (S, f32)>,
+    {
+        let (components, weights): (Vec<S>, Vec<f32>) = components.into_iter().unzip();
+        let mix = WeightedSampler::new(&weights)?;
+        let mut offsets = Vec::with_capacity(components.len() + 1);
+        let mut acc = 0;
+        for c in &components {
+            offsets.push(acc);
+            acc += c.len();
+        }
+        offsets.push(acc);
+        Ok(Self {
+            mix,
+            components,
+            space: IndexSpace::Concat(offsets),
+        })
+    }
+
+    /// Draw `(component, index within that component)`.
+    pub fn sample_component<R: Rng + ?Sized>(&self, rng: &mut R) -> (usize, usize) {
+        let k = self.mix.sample_index(rng);
+        (k, self.components[k].sample_index(rng))
+    }
+
+    /// The components, in insertion order.
+    pub fn components(&self) -> &[S] {
+        &self.components
+    }
+}
+
+impl<S: IndexSampler> IndexSampler for MixtureSampler<S> {
+    #[inline]
+    fn len(&self) -> usize {
+        match &self.space {
+            IndexSpace::Shared(n) => *n,
+            IndexSpace::Concat(offsets) => offsets[offsets.len() - 1],
+        }
+    }
+    #[inline]
+    fn sample_index<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let (k, i) = self.sample_component(rng);
+        match &self.space {
+            IndexSpace::Shared(_) => i,
+            IndexSpace::Concat(offsets) => offsets[k] + i,
+        }
+    }
+    #[inline]
+    fn sample_index_dyn(&self, rng: &mut dyn RngCore) -> usize {
+        self.sample_index(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UniformSampler;
+
+    #[test]
+    fn shared_and_concat_index_spaces() {
+        let a = WeightedSampler::new(&[1.0, 0.0]).unwrap();
+        let b = WeightedSampler::new(&[0.0, 1.0]).unwrap();
+        let mix = MixtureSampler::shared([(a, 0.0), (b, 1.0)]).unwrap();
+        let mut rng = rand::rng();
+        assert_eq!(mix.len(), 2);
+        assert_eq!(mix.sample_index(&mut rng), 1);
+
+        let mix = MixtureSampler::concat([
+            (UniformSampler::new(2).unwrap(), 0.0),
+            (UniformSampler::new(3).unwrap(), 1.0),
+        ])
+        .unwrap();
+        assert_eq!(mix.len(), 5);
+        for _ in 0..100 {
+            assert!((2..5).contains(&mix.sample_index(&mut rng)));
+        }
+
+        let err = MixtureSampler::shared([
+            (UniformSampler::new(2).unwrap(), 1.0),
+            (UniformSampler::new(3).unwrap(), 1.0),
+        ]);
+        assert!(matches!(
+            err,
+            Err(ProbError::Shape {
+                expected: 2,
+                found: 3
+            })
+        ));
+    }
+}
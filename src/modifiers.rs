@@ -0,0 +1,113 @@
+//! Composable weight adjustments (buffs, magic find, double-drop weekends)
+//! applied on top of a base [`DropTable`] without redefining it.
+//!
+//! ```rust,ignore
+//! use droptables::{DropTable, modifiers::WeightModifier};
+//!
+//! let table = DropTable::from_pairs([("junk", 90.0), ("rare", 10.0)])?;
+//! let magic_find = |i: usize, w: f32| if i == 1 { w * 1.5 } else { w };
+//! let view = table.view_with(&[&magic_find]);
+//! let drop = view.sample(&mut rand::rng())?;
+//! ```
+
+use std::cell::OnceCell;
+
+use rand::Rng;
+
+use crate::DropTable;
+use crate::error::ProbError;
+use crate::walker::WeightedSampler;
+
+/// Adjusts the weight of entry `index`, given its current weight `base`.
+///
+/// Modifiers in a chain are applied in order; each sees the previous
+/// modifier's output as `base`.
+pub trait WeightModifier {
+    fn adjust(&self, index: usize, base: f32) -> f32;
+}
+
+impl<F: Fn(usize, f32) -> f32> WeightModifier for F {
+    #[inline]
+    fn adjust(&self, index: usize, base: f32) -> f32 {
+        self(index, base)
+    }
+}
+
+/// A [`DropTable`] seen through a chain of [`WeightModifier`]s.
+///
+/// The adjusted alias table is built on first use and cached for the life
+/// of the view.
+pub struct ModifiedView<'a, T> {
+    table: &'a DropTable<T>,
+    modifiers: Vec<&'a dyn WeightModifier>,
+    sampler: OnceCell<Result<WeightedSampler, ProbError>>,
+}
+
+impl<T> DropTable<T> {
+    /// View this table with `modifiers` applied to its weights.
+    pub fn view_with<'a>(&'a self, modifiers: &[&'a dyn WeightModifier]) -> ModifiedView<'a, T> {
+        ModifiedView {
+            table: self,
+            modifiers: modifiers.to_vec(),
+            sampler: OnceCell::new(),
+        }
+    }
+}
+
+impl<'a, T> ModifiedView<'a, T> {
+    /// The adjusted weights, in insertion order.
+    pub fn weights(&self) -> Vec<f32> {
+        self.table
+            .weights()
+            .iter()
+            .enumerate()
+            .map(|(i, &w)| self.modifiers.iter().fold(w, |w, m| m.adjust(i, w)))
+            .collect()
+    }
+
+    /// The adjusted sampler, building it on first call.
+    ///
+    /// # Errors
+    /// Whatever [`WeightedSampler::new`] reports for the adjusted weights.
+    pub fn sampler(&self) -> Result<&WeightedSampler, ProbError> {
+        self.sampler
+            .get_or_init(|| WeightedSampler::new(&self.weights()))
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+
+    /// Sample from the adjusted distribution.
+    ///
+    /// # Errors
+    /// See [`sampler`](Self::sampler).
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<&'a T, ProbError> {
+        let i = self.sampler()?.sample_index(rng);
+        Ok(&self.table.items()[i])
+    }
+
+    /// The unmodified table.
+    pub fn base(&self) -> &'a DropTable<T> {
+        self.table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modifiers_compose_in_order() {
+        let table = DropTable::from_pairs([("junk", 9.0), ("rare", 1.0)]).unwrap();
+        let double_rare = |i: usize, w: f32| if i == 1 { w * 2.0 } else { w };
+        let plus_one = |_: usize, w: f32| w + 1.0;
+        let view = table.view_with(&[&double_rare, &plus_one]);
+        assert_eq!(view.weights(), vec![10.0, 3.0]);
+
+        let ban_all = |_: usize, _: f32| 0.0;
+        let view = table.view_with(&[&ban_all]);
+        assert_eq!(
+            view.sample(&mut rand::rng()).unwrap_err(),
+            ProbError::ZeroSum
+        );
+    }
+}
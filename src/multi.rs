@@ -0,0 +1,115 @@
+use rand::Rng;
+
+use crate::DropTable;
+use crate::error::ProbError;
+use crate::walker::WeightedSampler;
+
+/// Several [`DropTable`]s, each with a meta-weight.
+///
+/// Sampling first picks a table by meta-weight, then an item from it. Handy
+/// for region-based pools where zones share tables with different emphasis.
+#[derive(Debug, Clone)]
+pub struct MultiTable<T> {
+    meta: WeightedSampler,
+    meta_weights: Vec<f32>,
+    tables: Vec<DropTable<T>>,
+}
+
+impl<T> MultiTable<T> {
+    /// Build from `(table, meta_weight)` pairs.
+    ///
+    /// # Errors
+    /// See [`WeightedSampler::new`]; the meta-weights are validated like
+    /// ordinary weights.
+    pub fn from_tables<I>(tables: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = (DropTable<T>, f32)>,
+    {
+        let (tables, meta_weights): (Vec<_>, Vec<_>) = tables.into_iter().unzip();
+        let meta = WeightedSampler::new(&meta_weights)?;
+        Ok(Self {
+            meta,
+            meta_weights,
+            tables,
+        })
+    }
+
+    /// Pick a table, then an item from it. Two O(1) draws.
+    pub fn sample<'a, R: Rng + ?Sized>(&'a self, rng: &mut R) -> &'a T {
+        self.tables[self.meta.sample_index(rng)].sample(rng)
+    }
+
+    /// `rolls` independent [`sample`](Self::sample)s, for tables rolled
+    /// several times per kill.
+    pub fn roll<'a, R: Rng + ?Sized>(&'a self, rng: &mut R, rolls: usize) -> Vec<&'a T> {
+        (0..rolls).map(|_| self.sample(rng)).collect()
+    }
+
+    /// Like [`sample`](Self::sample), also returning the index of the table used.
+    pub fn sample_with_table<'a, R: Rng + ?Sized>(&'a self, rng: &mut R) -> (usize, &'a T) {
+        let t = self.meta.sample_index(rng);
+        (t, self.tables[t].sample(rng))
+    }
+
+    /// Overall probability of drawing `item`, summed across every table
+    /// (and every entry equal to `item` within a table).
+    pub fn probability_of(&self, item: &T) -> f64
+    where
+        T: PartialEq,
+    {
+        let meta_sum: f64 = self.meta_weights.iter().map(|&w| w as f64).sum();
+        self.tables
+            .iter()
+            .zip(&self.meta_weights)
+            .map(|(table, &mw)| {
+                let within: f64 = table
+                    .items()
+                    .iter()
+                    .zip(table.probabilities())
+                    .filter(|(t, _)| *t == item)
+                    .map(|(_, p)| p)
+                    .sum();
+                mw as f64 / meta_sum * within
+            })
+            .sum()
+    }
+
+    /// Normalized meta-weights: the chance of picking each member table.
+    pub fn meta_probabilities(&self) -> Vec<f64> {
+        let meta_sum: f64 = self.meta_weights.iter().map(|&w| w as f64).sum();
+        self.meta_weights
+            .iter()
+            .map(|&w| w as f64 / meta_sum)
+            .collect()
+    }
+
+    /// The member tables, in insertion order.
+    pub fn tables(&self) -> &[DropTable<T>] {
+        &self.tables
+    }
+
+    /// Number of member tables.
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    /// Whether there are no member tables.
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probability_composes_across_tables() {
+        let forest = DropTable::from_pairs([("herb", 3.0), ("coin", 1.0)]).unwrap();
+        let cave = DropTable::from_pairs([("ore", 1.0), ("coin", 1.0)]).unwrap();
+        let multi = MultiTable::from_tables([(forest, 1.0), (cave, 3.0)]).unwrap();
+        assert!((multi.probability_of(&"coin") - (0.25 * 0.25 + 0.75 * 0.5)).abs() < 1e-12);
+        assert!((multi.probability_of(&"ore") - 0.375).abs() < 1e-12);
+        assert_eq!(multi.probability_of(&"gem"), 0.0);
+    }
+}
@@ -0,0 +1,165 @@
+use rand::Rng;
+
+use crate::error::ProbError;
+use crate::walker::WeightedSampler;
+
+/// One entry of a [`NestedTable`]: an item, or a table rolled in its place.
+#[derive(Debug, Clone)]
+pub enum NestedEntry<T> {
+    Item(T),
+    Table(NestedTable<T>),
+}
+
+impl<T> From<NestedTable<T>> for NestedEntry<T> {
+    fn from(table: NestedTable<T>) -> Self {
+        NestedEntry::Table(table)
+    }
+}
+
+/// A sampled item plus the names of the tables rolled to reach it, outermost
+/// first, as returned by [`NestedTable::sample_with_path`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drop<'a, T> {
+    pub item: &'a T,
+    pub path: Vec<&'a str>,
+}
+
+impl<'a, T> Drop<'a, T> {
+    /// Name of the table the item came from (the last in
+    /// [`path`](Self::path)).
+    pub fn source(&self) -> &'a str {
+        self.path.last().copied().unwrap_or_default()
+    }
+}
+
+/// A named table whose entries may themselves be tables, like a monster's
+/// main table with a 1/128 slot that rolls the "rare drop table".
+///
+/// [`sample`](Self::sample) returns just the item; use
+/// [`sample_with_path`](Self::sample_with_path) when analytics or a kill
+/// log need to know which sub-table produced it.
+///
+/// ```
+/// use droptables::{NestedEntry, NestedTable};
+///
+/// let rdt = NestedTable::new("rare_drop_table", [(NestedEntry::Item("dragon spear"), 1.0)]).unwrap();
+/// let main = NestedTable::new("goblin", [
+///     (NestedEntry::Item("bones"), 127.0),
+///     (rdt.into(), 1.0),
+/// ])
+/// .unwrap();
+///
+/// let drop = main.sample_with_path(&mut rand::rng());
+/// if *drop.item == "dragon spear" {
+///     assert_eq!(drop.path, ["goblin", "rare_drop_table"]);
+///     assert_eq!(drop.source(), "rare_drop_table");
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct NestedTable<T> {
+    name: String,
+    sampler: WeightedSampler,
+    entries: Vec<NestedEntry<T>>,
+}
+
+impl<T> NestedTable<T> {
+    /// Build from `(entry, weight)` pairs.
+    ///
+    /// # Errors
+    /// As [`WeightedSampler::new`] for the weights at this level (nested
+    /// tables were checked when they were built).
+    pub fn new<I>(name: impl Into<String>, entries: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = (NestedEntry<T>, f32)>,
+    {
+        let (entries, weights): (Vec<_>, Vec<f32>) = entries.into_iter().unzip();
+        let sampler = WeightedSampler::new(&weights)?;
+        Ok(Self {
+            name: name.into(),
+            sampler,
+            entries,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn entries(&self) -> &[NestedEntry<T>] {
+        &self.entries
+    }
+
+    /// Chance of each entry at this level, in order.
+    pub fn probabilities(&self) -> Vec<f64> {
+        self.sampler.probabilities()
+    }
+
+    /// Roll down to an item.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        let mut table = self;
+        loop {
+            match &table.entries[table.sampler.sample_index(rng)] {
+                NestedEntry::Item(item) => return item,
+                NestedEntry::Table(sub) => table = sub,
+            }
+        }
+    }
+
+    /// Roll down to an item, recording every table rolled on the way,
+    /// starting with this one. Same draws as [`sample`](Self::sample).
+    pub fn sample_with_path<R: Rng + ?Sized>(&self, rng: &mut R) -> Drop<'_, T> {
+        let mut table = self;
+        let mut path = vec![self.name.as_str()];
+        loop {
+            match &table.entries[table.sampler.sample_index(rng)] {
+                NestedEntry::Item(item) => return Drop { item, path },
+                NestedEntry::Table(sub) => {
+                    path.push(&sub.name);
+                    table = sub;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn paths_name_every_table_rolled() {
+        let gems = NestedTable::new(
+            "gem_table",
+            [
+                (NestedEntry::Item("sapphire"), 1.0),
+                (NestedEntry::Item("ruby"), 1.0),
+            ],
+        )
+        .unwrap();
+        let rdt = NestedTable::new(
+            "rare_drop_table",
+            [(NestedEntry::Item("dragon spear"), 1.0), (gems.into(), 3.0)],
+        )
+        .unwrap();
+        let main = NestedTable::new(
+            "goblin",
+            [(NestedEntry::Item("bones"), 1.0), (rdt.into(), 1.0)],
+        )
+        .unwrap();
+
+        let mut a = StdRng::seed_from_u64(1392);
+        let mut b = a.clone();
+        for _ in 0..200 {
+            let drop = main.sample_with_path(&mut a);
+            assert_eq!(main.sample(&mut b), drop.item);
+            let expected: &[&str] = match *drop.item {
+                "bones" => &["goblin"],
+                "dragon spear" => &["goblin", "rare_drop_table"],
+                _ => &["goblin", "rare_drop_table", "gem_table"],
+            };
+            assert_eq!(drop.path, expected);
+            assert_eq!(drop.source(), *expected.last().unwrap());
+        }
+    }
+}
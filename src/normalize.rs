@@ -0,0 +1,190 @@
+use crate::DropTable;
+use crate::error::ProbError;
+use crate::kind::SamplerKind;
+use crate::walker::{WeightedSampler, validate_weights};
+
+/// What to do with zero and negative weights under [`BuildOptions`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ZeroPolicy {
+    /// Zero weights are kept (never drawn); `-0.0` counts as zero and is
+    /// reported as clamped; other negatives are an error.
+    #[default]
+    Allow,
+    /// Any zero weight, including `-0.0`, is a [`ProbError::ZeroWeight`]:
+    /// for configs where a zero is always a mistake.
+    Reject,
+    /// Negative weights, e.g. from a computed `base - penalty`, become zero
+    /// and are reported as clamped.
+    ClampNegative,
+}
+
+/// How strictly [`WeightedSampler::new_with`] and
+/// [`DropTable::from_pairs_with`] treat their weights.
+///
+/// The defaults match [`WeightedSampler::new`]: any positive sum is
+/// silently rescaled. Set `renormalize: false` to make a sum outside
+/// `1 ± sum_epsilon` an error instead, for weights that are meant to be
+/// probabilities.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BuildOptions {
+    /// How far the sum may be from 1 and still count as normalized.
+    pub sum_epsilon: f64,
+    /// Rescale a sum outside the tolerance (`true`) or reject it with
+    /// [`ProbError::NotNormalized`] (`false`).
+    pub renormalize: bool,
+    pub on_zero: ZeroPolicy,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        Self {
+            sum_epsilon: WeightedSampler::DEFAULT_EPSILON,
+            renormalize: true,
+            on_zero: ZeroPolicy::default(),
+        }
+    }
+}
+
+/// What a build under [`BuildOptions`] did to the weights.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NormalizationReport {
+    /// Sum of the weights after clamping, before scaling.
+    pub original_sum: f64,
+    /// Factor every weight was multiplied by to sum to 1 (`1 / original_sum`).
+    pub scale: f64,
+    /// Whether the sum was outside `1 ± sum_epsilon`, i.e. the weights were
+    /// not already probabilities.
+    pub renormalized: bool,
+    /// Indices of weights replaced by zero (`-0.0`, or negatives under
+    /// [`ZeroPolicy::ClampNegative`]).
+    pub clamped: Vec<usize>,
+}
+
+impl BuildOptions {
+    /// Apply the zero policy and check the sum, returning the weights to
+    /// build from.
+    pub(crate) fn apply(
+        &self,
+        weights: &[f32],
+    ) -> Result<(Vec<f32>, NormalizationReport), ProbError> {
+        let mut clamped = Vec::new();
+        let mut cleaned = Vec::with_capacity(weights.len());
+        for (index, &w) in weights.iter().enumerate() {
+            let negative = w < 0.0 || (w == 0.0 && w.is_sign_negative());
+            let w = match self.on_zero {
+                ZeroPolicy::Reject if w == 0.0 => return Err(ProbError::ZeroWeight { index }),
+                ZeroPolicy::ClampNegative if negative => 0.0,
+                _ if w == 0.0 && negative => 0.0,
+                _ if negative => return Err(ProbError::Negative { index, value: w }),
+                _ => w,
+            };
+            if negative {
+                clamped.push(index);
+            }
+            cleaned.push(w);
+        }
+        let sum = validate_weights(&cleaned)?;
+        let renormalized = (sum - 1.0).abs() > self.sum_epsilon;
+        if renormalized && !self.renormalize {
+            return Err(ProbError::NotNormalized {
+                sum,
+                epsilon: self.sum_epsilon,
+            });
+        }
+        Ok((
+            cleaned,
+            NormalizationReport {
+                original_sum: sum,
+                scale: 1.0 / sum,
+                renormalized,
+                clamped,
+            },
+        ))
+    }
+}
+
+impl WeightedSampler {
+    /// [`new`](Self::new) under explicit [`BuildOptions`], also reporting
+    /// how the weights were normalized.
+    ///
+    /// # Errors
+    /// As [`new`](Self::new), plus [`ProbError::ZeroWeight`] under
+    /// [`ZeroPolicy::Reject`] and [`ProbError::NotNormalized`] when
+    /// renormalizing is off.
+    pub fn new_with(
+        weights: &[f32],
+        options: &BuildOptions,
+    ) -> Result<(Self, NormalizationReport), ProbError> {
+        let (weights, report) = options.apply(weights)?;
+        Ok((Self::new(&weights)?, report))
+    }
+}
+
+impl<T> DropTable<T> {
+    /// [`from_pairs`](Self::from_pairs) under explicit [`BuildOptions`];
+    /// the table stores the weights after clamping.
+    ///
+    /// ```
+    /// use droptables::{BuildOptions, DropTable, ProbError};
+    ///
+    /// let strict = BuildOptions { renormalize: false, ..Default::default() };
+    /// // meant to be probabilities, but a typo makes them sum to 1.09
+    /// let typo = DropTable::from_pairs_with([("a", 0.6), ("b", 0.49)], &strict);
+    /// assert!(matches!(typo, Err(ProbError::NotNormalized { .. })));
+    /// ```
+    ///
+    /// # Errors
+    /// As [`WeightedSampler::new_with`].
+    pub fn from_pairs_with<I>(
+        pairs: I,
+        options: &BuildOptions,
+    ) -> Result<(Self, NormalizationReport), ProbError>
+    where
+        I: IntoIterator<Item = (T, f32)>,
+    {
+        let (items, weights): (Vec<T>, Vec<f32>) = pairs.into_iter().unzip();
+        let (weights, report) = options.apply(&weights)?;
+        let table = Self::from_pairs_using(items.into_iter().zip(weights), SamplerKind::Alias)?;
+        Ok((table, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_scale_and_clamps() {
+        let (table, report) = DropTable::from_pairs_with(
+            [("a", 3.0), ("b", -0.0), ("c", 1.0)],
+            &BuildOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(table.weights(), [3.0, 0.0, 1.0]);
+        assert_eq!(report.original_sum, 4.0);
+        assert_eq!(report.scale, 0.25);
+        assert!(report.renormalized);
+        assert_eq!(report.clamped, [1]);
+
+        let clamp = BuildOptions {
+            on_zero: ZeroPolicy::ClampNegative,
+            ..Default::default()
+        };
+        let (_, report) = WeightedSampler::new_with(&[0.5, -2.0, 0.5], &clamp).unwrap();
+        assert_eq!(report.clamped, [1]);
+        assert!(!report.renormalized);
+
+        let reject = BuildOptions {
+            on_zero: ZeroPolicy::Reject,
+            ..Default::default()
+        };
+        assert_eq!(
+            WeightedSampler::new_with(&[1.0, 0.0], &reject).unwrap_err(),
+            ProbError::ZeroWeight { index: 1 }
+        );
+        assert!(matches!(
+            WeightedSampler::new_with(&[1.0, -1.0], &BuildOptions::default()),
+            Err(ProbError::Negative { index: 1, .. })
+        ));
+    }
+}
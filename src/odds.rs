@@ -0,0 +1,358 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Mul;
+use std::str::FromStr;
+
+use droptables_odds::{OddsParseError, parse_odds};
+
+use crate::DropTable;
+use crate::chance::Chance;
+use crate::error::ProbError;
+
+/// Largest denominator [`Odds::from_prob`] picks.
+const MAX_APPROX_DENOMINATOR: u64 = 1_000_000_000;
+
+/// An exact probability written the way designers do: `1/128`, `3/1000`.
+///
+/// Always stored in lowest terms, so `Odds::new(2, 256)` equals
+/// `Odds::new(1, 128)` and displays as `1/128`. Multiplying two odds gives
+/// the odds of passing both of two independent gates, e.g. a 1/64 rare
+/// table roll followed by a 1/2 pick within it:
+///
+/// ```
+/// use droptables::Odds;
+///
+/// let rare_table = Odds::new(1, 64).unwrap();
+/// let pick = Odds::new(1, 2).unwrap();
+/// assert_eq!((rare_table * pick).to_string(), "1/128");
+/// assert_eq!(Odds::from_prob(0.0078125).unwrap(), Odds::new(1, 128).unwrap());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Odds {
+    num: u64,
+    den: u64,
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl Odds {
+    /// Certain: `1/1`.
+    pub const ALWAYS: Odds = Odds { num: 1, den: 1 };
+    /// Impossible: `0/1`.
+    pub const NEVER: Odds = Odds { num: 0, den: 1 };
+
+    /// `num / den`, reduced.
+    ///
+    /// # Errors
+    /// [`ProbError::ChanceOutOfRange`] if `den == 0` or `num > den`.
+    pub fn new(num: u64, den: u64) -> Result<Self, ProbError> {
+        if den == 0 || num > den {
+            return Err(ProbError::ChanceOutOfRange {
+                value: num as f64 / den as f64,
+            });
+        }
+        Ok(Self::reduced(u128::from(num), u128::from(den)))
+    }
+
+    /// Parse odds the way designers write them, exactly:
+    ///
+    /// | form      | example      |
+    /// |-----------|--------------|
+    /// | ratio     | `"1/128"`    |
+    /// | one in N  | `"1 in 128"` |
+    /// | percent   | `"0.78%"`    |
+    /// | decimal   | `"0.0078"`   |
+    ///
+    /// Numbers are plain decimals of up to 18 digits, read exactly (no
+    /// float rounding). The same grammar is accepted by `#[odds = "..."]`
+    /// in the `WeightedEnum` derive and for weights in the text loaders.
+    ///
+    /// ```
+    /// use droptables::Odds;
+    ///
+    /// let hilt = Odds::parse("1 in 508").unwrap();
+    /// assert_eq!(hilt, Odds::parse("1/508").unwrap());
+    /// assert_eq!(Odds::parse("0.78%").unwrap().to_string(), "39/5000");
+    /// assert!(Odds::parse("1/0").is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// An [`OddsParseError`] saying what is wrong with `s`.
+    pub fn parse(s: &str) -> Result<Self, OddsParseError> {
+        let (num, den) = parse_odds(s)?;
+        Ok(Self::reduced(num, den))
+    }
+
+    /// `1 / n`.
+    ///
+    /// # Errors
+    /// [`ProbError::ChanceOutOfRange`] if `n == 0`.
+    pub fn one_in(n: u64) -> Result<Self, ProbError> {
+        Self::new(1, n)
+    }
+
+    /// Lowest terms of a fraction known to be in `[0, 1]`; falls back to the
+    /// closest approximation if it doesn't fit in `u64`.
+    fn reduced(num: u128, den: u128) -> Self {
+        let g = gcd(num, den).max(1);
+        let (num, den) = (num / g, den / g);
+        match (u64::try_from(num), u64::try_from(den)) {
+            (Ok(num), Ok(den)) => Self { num, den },
+            _ => Self::approximate(num as f64 / den as f64),
+        }
+    }
+
+    /// Closest fraction to `p` with a denominator up to
+    /// `MAX_APPROX_DENOMINATOR`, by continued fractions.
+    fn approximate(p: f64) -> Self {
+        let (mut h0, mut h1, mut k0, mut k1) = (0u64, 1u64, 1u64, 0u64);
+        let mut x = p;
+        loop {
+            let a = x.floor();
+            if a > MAX_APPROX_DENOMINATOR as f64 {
+                break;
+            }
+            let a = a as u64;
+            let (Some(h2), Some(k2)) = (
+                a.checked_mul(h1).and_then(|v| v.checked_add(h0)),
+                a.checked_mul(k1).and_then(|v| v.checked_add(k0)),
+            ) else {
+                break;
+            };
+            if k2 > MAX_APPROX_DENOMINATOR {
+                break;
+            }
+            (h0, h1, k0, k1) = (h1, h2, k1, k2);
+            let frac = x - a as f64;
+            if frac.abs() < 1e-12 || (h1 as f64 / k1 as f64 - p).abs() < f64::EPSILON * p {
+                break;
+            }
+            x = 1.0 / frac;
+        }
+        Self::reduced(u128::from(h1), u128::from(k1.max(1)))
+    }
+
+    /// The simplest fraction within rounding of `p`, with a denominator of
+    /// at most one billion: `0.0078125` becomes `1/128`, `0.3` becomes
+    /// `3/10`.
+    ///
+    /// # Errors
+    /// [`ProbError::ChanceOutOfRange`] unless `0 <= p <= 1`.
+    pub fn from_prob(p: f64) -> Result<Self, ProbError> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(ProbError::ChanceOutOfRange { value: p });
+        }
+        Ok(Self::approximate(p))
+    }
+
+    pub fn numerator(&self) -> u64 {
+        self.num
+    }
+
+    pub fn denominator(&self) -> u64 {
+        self.den
+    }
+
+    pub fn probability(&self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+
+    /// `N` in "1 in N": `den / num`, infinite for [`NEVER`](Self::NEVER).
+    pub fn one_in_n(&self) -> f64 {
+        self.den as f64 / self.num as f64
+    }
+
+    /// The odds of the event *not* happening.
+    pub fn complement(&self) -> Self {
+        Self {
+            num: self.den - self.num,
+            den: self.den,
+        }
+    }
+
+    /// The odds of at least one of two independent events.
+    pub fn either(self, other: Odds) -> Odds {
+        (self.complement() * other.complement()).complement()
+    }
+
+    pub fn to_chance(&self) -> Chance {
+        Chance::new(self.probability()).expect("odds are always within [0, 1]")
+    }
+}
+
+impl FromStr for Odds {
+    type Err = OddsParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Odds::parse(s)
+    }
+}
+
+/// A weight from a text loader: any float `str::parse` accepts (relative
+/// weights like `"127"`), otherwise odds in the [`Odds::parse`] grammar.
+/// Fails with `None` when `s` is no kind of number at all, and with the
+/// odds error when it is shaped like odds but invalid.
+pub(crate) fn parse_weight(s: &str) -> Result<f32, Option<OddsParseError>> {
+    let s = s.trim();
+    s.parse::<f32>().or_else(|_| match Odds::parse(s) {
+        Ok(o) => Ok(o.probability() as f32),
+        Err(OddsParseError::BadNumber(n)) if n == s => Err(None),
+        Err(e) => Err(Some(e)),
+    })
+}
+
+/// Both of two independent gates pass.
+impl Mul for Odds {
+    type Output = Odds;
+
+    fn mul(self, rhs: Odds) -> Odds {
+        Odds::reduced(
+            u128::from(self.num) * u128::from(rhs.num),
+            u128::from(self.den) * u128::from(rhs.den),
+        )
+    }
+}
+
+impl PartialOrd for Odds {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Odds {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (u128::from(self.num) * u128::from(other.den))
+            .cmp(&(u128::from(other.num) * u128::from(self.den)))
+    }
+}
+
+impl fmt::Display for Odds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.num, self.den)
+    }
+}
+
+impl From<Odds> for f64 {
+    fn from(o: Odds) -> f64 {
+        o.probability()
+    }
+}
+
+impl<T> DropTable<T> {
+    /// Build from `(item, odds)` pairs; each entry's weight is its odds'
+    /// probability, normalized like [`from_pairs`](Self::from_pairs), so
+    /// odds that sum to 1 keep their exact meaning.
+    ///
+    /// # Errors
+    /// Whatever [`from_pairs`](Self::from_pairs) reports.
+    pub fn from_odds_pairs<I>(pairs: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = (T, Odds)>,
+    {
+        Self::from_pairs(
+            pairs
+                .into_iter()
+                .map(|(item, odds)| (item, odds.probability() as f32)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduces_combines_and_orders() {
+        let a = Odds::new(2, 256).unwrap();
+        assert_eq!(a, Odds::one_in(128).unwrap());
+        assert_eq!(a.to_string(), "1/128");
+        assert_eq!((a * Odds::new(3, 4).unwrap()).to_string(), "3/512");
+        assert_eq!(
+            Odds::new(1, 2)
+                .unwrap()
+                .either(Odds::new(1, 2).unwrap())
+                .to_string(),
+            "3/4"
+        );
+        assert!(Odds::new(1, 3).unwrap() > Odds::new(33, 100).unwrap());
+        assert_eq!(Odds::NEVER.complement(), Odds::ALWAYS);
+        assert!(Odds::new(3, 2).is_err());
+        assert!(Odds::one_in(0).is_err());
+
+        let tiny = Odds::one_in(u64::MAX).unwrap() * Odds::one_in(u64::MAX).unwrap();
+        assert!(tiny.probability() < 1e-30);
+    }
+
+    #[test]
+    fn from_prob_finds_designer_fractions() {
+        for (p, s) in [
+            (0.0078125, "1/128"),
+            (0.3, "3/10"),
+            (1.0 / 5000.0, "1/5000"),
+            (0.0, "0/1"),
+            (1.0, "1/1"),
+        ] {
+            assert_eq!(Odds::from_prob(p).unwrap().to_string(), s);
+        }
+        assert!(Odds::from_prob(1.5).is_err());
+
+        let t = DropTable::from_odds_pairs([
+            ("rare", Odds::one_in(4).unwrap()),
+            ("common", Odds::new(3, 4).unwrap()),
+        ])
+        .unwrap();
+        assert_eq!(t.weights(), [0.25, 0.75]);
+    }
+
+    #[test]
+    fn parses_every_written_form() {
+        let one_in_128 = Odds::one_in(128).unwrap();
+        for s in [
+            "1/128",
+            " 1 / 128 ",
+            "1 in 128",
+            "1 IN 128",
+            "0.78125%",
+            "0.0078125",
+            "2/256",
+            ".5/64",
+        ] {
+            assert_eq!(s.parse::<Odds>(), Ok(one_in_128), "{s:?}");
+        }
+        assert_eq!(Odds::parse("100%"), Ok(Odds::ALWAYS));
+        assert_eq!(Odds::parse("0"), Ok(Odds::NEVER));
+
+        for (s, e) in [
+            ("", OddsParseError::Empty),
+            ("1/0", OddsParseError::ZeroDenominator),
+            ("3/2", OddsParseError::AboveOne),
+            ("150%", OddsParseError::AboveOne),
+            ("-1/2", OddsParseError::BadNumber("-1".into())),
+            ("1e-3", OddsParseError::BadNumber("1e-3".into())),
+            ("NaN", OddsParseError::BadNumber("NaN".into())),
+            ("1/2/3", OddsParseError::BadNumber("2/3".into())),
+            ("1 in", OddsParseError::BadNumber("1 in".into())),
+            (".", OddsParseError::BadNumber(".".into())),
+        ] {
+            assert_eq!(Odds::parse(s), Err(e), "{s:?}");
+        }
+        let long = format!("1/{}", "9".repeat(19));
+        assert!(matches!(
+            Odds::parse(&long),
+            Err(OddsParseError::TooManyDigits(_))
+        ));
+
+        assert_eq!(parse_weight("127"), Ok(127.0));
+        assert_eq!(parse_weight("1 in 4"), Ok(0.25));
+        assert_eq!(parse_weight("lots"), Err(None));
+        assert_eq!(
+            parse_weight("1/0"),
+            Err(Some(OddsParseError::ZeroDenominator))
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Card-pack style opening: fixed slots, each rolled on its own table,
+//! with "at least N of ..." guarantees.
+//!
+//! ```
+//! use droptables::DropTable;
+//! use droptables::packs::PackSpec;
+//!
+//! let common = DropTable::from_pairs([("goblin", 60.0), ("orc", 35.0), ("dragon", 5.0)]).unwrap();
+//! let pack = PackSpec::new(vec![common; 5])
+//!     .at_least(1, |card| *card == "dragon")
+//!     .unwrap();
+//! let cards = pack.open(&mut rand::rng());
+//! assert_eq!(cards.len(), 5);
+//! assert!(cards.contains(&"dragon"));
+//! ```
+
+use std::fmt;
+
+use rand::Rng;
+
+use crate::walker::WeightedSampler;
+use crate::{DropTable, IndexSampler};
+
+/// Why a [`PackSpec`] guarantee was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackError {
+    /// Only `possible` slots can ever produce a matching item, fewer than
+    /// the `needed` the guarantee asks for.
+    Unsatisfiable { needed: usize, possible: usize },
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackError::Unsatisfiable { needed, possible } => write!(
+                f,
+                "guarantee needs {needed} matching slots but only {possible} can match"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PackError {}
+
+type Predicate<T> = Box<dyn Fn(&T) -> bool + Send + Sync>;
+
+struct Guarantee<T> {
+    at_least: usize,
+    matches: Predicate<T>,
+    /// Per slot, that slot's table restricted to matching items (`None` if
+    /// it has none with positive weight).
+    restricted: Vec<Option<WeightedSampler>>,
+}
+
+/// Slots and guarantees for one kind of pack; see the [module docs](self).
+///
+/// [`open`](Self::open) rolls every slot independently, then enforces each
+/// guarantee in the order added: if too few cards match, slots are rerolled
+/// from the *last* one backwards (the usual "the last card is upgraded to a
+/// rare"), each on its own table restricted to matching items, so the
+/// upgraded card keeps that table's relative odds. Slots holding a card that
+/// matches another guarantee are left alone, so guarantees don't undo each
+/// other when enough slots can match; when they overlap too tightly, the
+/// later guarantee wins what it can.
+pub struct PackSpec<T> {
+    slots: Vec<DropTable<T>>,
+    guarantees: Vec<Guarantee<T>>,
+}
+
+impl<T> fmt::Debug for PackSpec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PackSpec")
+            .field("slots", &self.slots.len())
+            .field(
+                "guarantees",
+                &self
+                    .guarantees
+                    .iter()
+                    .map(|g| g.at_least)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl<T> PackSpec<T> {
+    /// A pack with one card per table, in order.
+    pub fn new(slots: Vec<DropTable<T>>) -> Self {
+        Self {
+            slots,
+            guarantees: Vec::new(),
+        }
+    }
+
+    /// Require at least `n` cards matching `matches` in every pack.
+    ///
+    /// # Errors
+    /// [`PackError::Unsatisfiable`] if fewer than `n` slots have a matching
+    /// item with positive weight.
+    pub fn at_least<F>(mut self, n: usize, matches: F) -> Result<Self, PackError>
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        let restricted: Vec<Option<WeightedSampler>> = self
+            .slots
+            .iter()
+            .map(|table| {
+                let masked: Vec<f32> = table
+                    .iter()
+                    .map(|(item, w)| if matches(item) { w } else { 0.0 })
+                    .collect();
+                WeightedSampler::new(&masked).ok()
+            })
+            .collect();
+        let possible = restricted.iter().filter(|r| r.is_some()).count();
+        if possible < n {
+            return Err(PackError::Unsatisfiable {
+                needed: n,
+                possible,
+            });
+        }
+        self.guarantees.push(Guarantee {
+            at_least: n,
+            matches: Box::new(matches),
+            restricted,
+        });
+        Ok(self)
+    }
+
+    /// One pack as item indices: entry `s` indexes into slot `s`'s table.
+    pub fn open_indices<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<usize> {
+        let mut picks: Vec<usize> = self
+            .slots
+            .iter()
+            .map(|t| t.sampler.sample_index(rng))
+            .collect();
+        let item = |slot: usize, picks: &[usize]| &self.slots[slot].items()[picks[slot]];
+
+        for (gi, g) in self.guarantees.iter().enumerate() {
+            let mut have = (0..picks.len())
+                .filter(|&s| (g.matches)(item(s, &picks)))
+                .count();
+            for slot in (0..picks.len()).rev() {
+                if have >= g.at_least {
+                    break;
+                }
+                let Some(restricted) = &g.restricted[slot] else {
+                    continue;
+                };
+                let current = item(slot, &picks);
+                let locked = (g.matches)(current)
+                    || self
+                        .guarantees
+                        .iter()
+                        .enumerate()
+                        .any(|(oi, o)| oi != gi && (o.matches)(current));
+                if locked {
+                    continue;
+                }
+                picks[slot] = restricted.sample_index(rng);
+                have += 1;
+            }
+        }
+        picks
+    }
+
+    /// Open one pack: one card per slot, in slot order.
+    pub fn open<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.open_indices(rng)
+            .into_iter()
+            .enumerate()
+            .map(|(slot, i)| self.slots[slot].items()[i].clone())
+            .collect()
+    }
+
+    pub fn slots(&self) -> &[DropTable<T>] {
+        &self.slots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn guarantees_upgrade_from_the_last_slot() {
+        let common =
+            DropTable::from_pairs([("c1", 50.0), ("c2", 49.0), ("r1", 0.5), ("r2", 0.5)]).unwrap();
+        let pack = PackSpec::new(vec![common.clone(); 5])
+            .at_least(1, |c: &&str| c.starts_with('r'))
+            .unwrap();
+        let mut rng = StdRng::seed_from_u64(1378);
+        let (mut r1, mut last) = (0, 0);
+        for _ in 0..2_000 {
+            let cards = pack.open(&mut rng);
+            let rares = cards.iter().filter(|c| c.starts_with('r')).count();
+            assert!(rares >= 1, "{cards:?}");
+            r1 += cards.iter().filter(|c| **c == "r1").count();
+            last += usize::from(cards[4].starts_with('r'));
+        }
+        // almost always the upgraded slot, and r1:r2 stays even
+        assert!(last > 1_900, "{last}");
+        assert!((r1 as f64 / 2_000.0 - 0.5).abs() < 0.1, "{r1}");
+
+        let err = PackSpec::new(vec![common; 2])
+            .at_least(3, |c: &&str| c.starts_with('r'))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            PackError::Unsatisfiable {
+                needed: 3,
+                possible: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,331 @@
+//! A tiny text format for quick prototyping:
+//!
+//! ```text
+//! # tiers
+//! common: 60, uncommon: 30
+//! rare: 9
+//! legendary: 1   # the good stuff
+//! ```
+//!
+//! Entries are `name:weight` pairs separated by commas or newlines; a
+//! weight is a number or odds in the [`Odds::parse`](crate::Odds::parse)
+//! grammar (`1/128`, `1 in 128`, `0.78%`). `#`
+//! starts a comment that runs to the end of the line. Names are trimmed;
+//! blank entries are skipped. A line `#! version = N` sets the table's
+//! [`version`](crate::DropTable::version).
+//!
+//! [`parse_toml_pairs`] reads the same pairs from flat TOML instead:
+//! `name = weight` lines, where a weight is a number or a quoted odds
+//! string (`"1/128"`, `"5%"`). [`parse_version`] reads the pragma from
+//! either format.
+
+use std::ops::Range;
+
+use droptables_odds::{OddsParseError, TomlLine, TomlLineError, toml_line, unquote};
+
+use crate::DropTable;
+use crate::error::ProbError;
+use crate::odds::{Odds, parse_weight};
+use crate::version::version_pragma;
+
+/// What went wrong while parsing a table definition.
+#[derive(Debug)]
+pub enum ParseErrorKind {
+    /// An entry had no `:` separating name and weight.
+    MissingColon,
+    /// The name before `:` was empty.
+    EmptyName,
+    /// The weight after `:` is not a number.
+    BadWeight,
+    /// The weight after `:` is written as odds (`1/128`, `1 in 128`,
+    /// `0.78%`) but they don't parse.
+    BadOdds(OddsParseError),
+    /// A `#!` pragma line is not `#! version = N`.
+    BadVersion,
+    /// A TOML line is neither blank nor `name = weight`.
+    Toml(TomlLineError),
+    /// A TOML `[table]` header; weights must be listed at the top level.
+    TomlTable,
+    /// The entries parsed, but the weights were rejected.
+    Table(ProbError),
+}
+
+/// A parse failure with the byte span and 1-based line/column it refers to.
+#[derive(Debug)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub span: Range<usize>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match &self.kind {
+            ParseErrorKind::MissingColon => write!(f, "expected `name:weight`"),
+            ParseErrorKind::EmptyName => write!(f, "entry name is empty"),
+            ParseErrorKind::BadWeight => write!(f, "weight is not a number"),
+            ParseErrorKind::BadOdds(e) => write!(f, "{e}"),
+            ParseErrorKind::BadVersion => write!(f, "expected `#! version = N`"),
+            ParseErrorKind::Toml(e) => write!(f, "{e}"),
+            ParseErrorKind::TomlTable => {
+                write!(f, "tables are not supported, list weights at the top level")
+            }
+            ParseErrorKind::Table(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ParseErrorKind::Table(e) => Some(e),
+            ParseErrorKind::Toml(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// One parsed `name:weight` entry and where its weight sits in the source.
+struct Entry<'a> {
+    name: &'a str,
+    weight: f32,
+    weight_span: Range<usize>,
+}
+
+fn error_at(src: &str, kind: ParseErrorKind, span: Range<usize>) -> ParseError {
+    let before = &src[..span.start];
+    let line = before.matches('\n').count() + 1;
+    let column = span.start - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    ParseError {
+        kind,
+        span,
+        line,
+        column,
+    }
+}
+
+/// Trim `s` (which starts at byte `start` of the source) and return its new span.
+fn trimmed(s: &str, start: usize) -> (&str, Range<usize>) {
+    let lead = s.len() - s.trim_start().len();
+    let t = s.trim();
+    (t, start + lead..start + lead + t.len())
+}
+
+fn parse_entries(src: &str) -> Result<Vec<Entry<'_>>, ParseError> {
+    let mut entries = Vec::new();
+    let mut line_start = 0;
+    for line in src.split_inclusive('\n') {
+        let body = line.split('#').next().unwrap_or("");
+        let mut offset = line_start;
+        for raw in body.split(',') {
+            let (entry, span) = trimmed(raw.trim_end_matches(['\r', '\n']), offset);
+            offset += raw.len() + 1;
+            if entry.is_empty() {
+                continue;
+            }
+            let Some(colon) = entry.find(':') else {
+                return Err(error_at(src, ParseErrorKind::MissingColon, span));
+            };
+            let (name, name_span) = trimmed(&entry[..colon], span.start);
+            if name.is_empty() {
+                return Err(error_at(src, ParseErrorKind::EmptyName, name_span));
+            }
+            let (weight, weight_span) = trimmed(&entry[colon + 1..], span.start + colon + 1);
+            let weight = match parse_weight(weight) {
+                Ok(w) => w,
+                Err(e) => {
+                    let kind = e.map_or(ParseErrorKind::BadWeight, ParseErrorKind::BadOdds);
+                    return Err(error_at(src, kind, weight_span));
+                }
+            };
+            entries.push(Entry {
+                name,
+                weight,
+                weight_span,
+            });
+        }
+        line_start += line.len();
+    }
+    Ok(entries)
+}
+
+/// Parse the text format into `(name, weight)` pairs without building a table.
+///
+/// Only syntax is checked here; weights are validated by whatever table you
+/// build from the pairs.
+pub fn parse_pairs(src: &str) -> Result<Vec<(String, f32)>, ParseError> {
+    Ok(parse_entries(src)?
+        .into_iter()
+        .map(|e| (e.name.to_string(), e.weight))
+        .collect())
+}
+
+/// Parse flat TOML (see the [module docs](crate::parse)) into
+/// `(name, weight)` pairs without building a table.
+///
+/// # Errors
+/// A [`ParseError`] at the offending line, or at the weight if it doesn't
+/// parse; `[table]` headers are [`ParseErrorKind::TomlTable`].
+pub fn parse_toml_pairs(src: &str) -> Result<Vec<(String, f32)>, ParseError> {
+    let mut pairs = Vec::new();
+    let mut line_start = 0;
+    for line in src.split_inclusive('\n') {
+        let (_, span) = trimmed(line, line_start);
+        line_start += line.len();
+        let (name, value) = match toml_line(line) {
+            Ok(TomlLine::Blank) => continue,
+            Ok(TomlLine::Header(_)) => {
+                return Err(error_at(src, ParseErrorKind::TomlTable, span));
+            }
+            Ok(TomlLine::Entry { name, value }) => (name, value),
+            Err(e) => return Err(error_at(src, ParseErrorKind::Toml(e), span)),
+        };
+        // `value` is a slice of `src`
+        let start = value.as_ptr() as usize - src.as_ptr() as usize;
+        let value_span = start..start + value.len();
+        let weight = match unquote(value) {
+            Some(odds) => Odds::parse(odds)
+                .map(|o| o.probability() as f32)
+                .map_err(ParseErrorKind::BadOdds),
+            None => value.parse().map_err(|_| ParseErrorKind::BadWeight),
+        };
+        match weight {
+            Ok(w) => pairs.push((name.to_string(), w)),
+            Err(kind) => return Err(error_at(src, kind, value_span)),
+        }
+    }
+    Ok(pairs)
+}
+
+/// The table version set by `#! version = N` lines in `src` (the last one
+/// wins), or `0` if there are none.
+///
+/// # Errors
+/// [`ParseErrorKind::BadVersion`] at a `#!` line that isn't
+/// `#! version = N`.
+pub fn parse_version(src: &str) -> Result<u32, ParseError> {
+    let mut version = 0;
+    let mut line_start = 0;
+    for line in src.split_inclusive('\n') {
+        match version_pragma(line) {
+            Some(Some(v)) => version = v,
+            Some(None) => {
+                let (_, span) = trimmed(line, line_start);
+                return Err(error_at(src, ParseErrorKind::BadVersion, span));
+            }
+            None => {}
+        }
+        line_start += line.len();
+    }
+    Ok(version)
+}
+
+impl DropTable<String> {
+    /// Parse a table from the `name:weight` text format described in the
+    /// [module docs](crate::parse).
+    ///
+    /// # Errors
+    /// A [`ParseError`] pointing at the offending entry. Weight validation
+    /// errors point at the weight in question where possible.
+    ///
+    /// # Examples
+    /// ```rust,ignore
+    /// # use droptables::DropTable;
+    /// let table = DropTable::parse("common:60, rare:9, legendary:1").unwrap();
+    /// ```
+    pub fn parse(src: &str) -> Result<Self, ParseError> {
+        let entries = parse_entries(src)?;
+        let version = parse_version(src)?;
+        let span_of = |index: usize| {
+            entries
+                .get(index)
+                .map_or(0..src.len(), |e| e.weight_span.clone())
+        };
+        let pairs: Vec<(String, f32)> = entries
+            .iter()
+            .map(|e| (e.name.to_string(), e.weight))
+            .collect();
+        DropTable::from_pairs(pairs)
+            .map(|t| t.with_version(version))
+            .map_err(|e| {
+                let span = match &e {
+                    ProbError::Negative { index, .. } => span_of(*index),
+                    _ => 0..src.len(),
+                };
+                error_at(src, ParseErrorKind::Table(e), span)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commas_newlines_and_comments() {
+        let table =
+            DropTable::parse("# tiers\ncommon: 60, rare:9\n\nlegendary : 1 # nice\n").unwrap();
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.version(), 0);
+
+        let table = DropTable::parse("#! version = 4\ncommon: 60").unwrap();
+        assert_eq!(table.version(), 4);
+        let err = DropTable::parse("a: 1\n  #! version 4").unwrap_err();
+        assert!(matches!(err.kind, ParseErrorKind::BadVersion));
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+
+    #[test]
+    fn reports_position_of_bad_weight() {
+        let err = DropTable::parse("a:1\nb: x2").unwrap_err();
+        assert!(matches!(err.kind, ParseErrorKind::BadWeight));
+        assert_eq!((err.line, err.column), (2, 4));
+        assert_eq!(err.span, 7..9);
+
+        let err = DropTable::parse("a:1, b:-3").unwrap_err();
+        assert!(matches!(
+            err.kind,
+            ParseErrorKind::Table(ProbError::Negative { .. })
+        ));
+        assert_eq!(err.span, 7..9);
+
+        let pairs = parse_pairs("pet: 1 in 128, clue: 0.78125%, hilt: 1/128").unwrap();
+        assert!(pairs.iter().all(|(_, w)| *w == 1.0 / 128.0));
+        let err = DropTable::parse("a:1, b: 1/0").unwrap_err();
+        assert!(matches!(
+            err.kind,
+            ParseErrorKind::BadOdds(OddsParseError::ZeroDenominator)
+        ));
+    }
+
+    #[test]
+    fn toml_pairs_and_version() {
+        let src = "#! version = 2\n# boss\nbones = 1 # always\n\"dragon claw\" = \"1/128\"\n";
+        let pairs = parse_toml_pairs(src).unwrap();
+        assert_eq!(
+            pairs,
+            [("bones".into(), 1.0), ("dragon claw".into(), 1.0 / 128.0)]
+        );
+        assert_eq!(parse_version(src).unwrap(), 2);
+        assert_eq!(parse_version("bones = 1").unwrap(), 0);
+
+        let err = parse_toml_pairs("a = 1\n  [boss]\n").unwrap_err();
+        assert!(matches!(err.kind, ParseErrorKind::TomlTable));
+        assert_eq!((err.line, err.column), (2, 3));
+        let err = parse_toml_pairs("a = 1\nb = x2").unwrap_err();
+        assert!(matches!(err.kind, ParseErrorKind::BadWeight));
+        assert_eq!((err.span, err.column), (10..12, 5));
+        let err = parse_toml_pairs("a = \"1/0\"").unwrap_err();
+        assert!(matches!(
+            err.kind,
+            ParseErrorKind::BadOdds(OddsParseError::ZeroDenominator)
+        ));
+        let err = parse_toml_pairs("a 1").unwrap_err();
+        assert!(matches!(
+            err.kind,
+            ParseErrorKind::Toml(TomlLineError::MissingEquals)
+        ));
+    }
+}
@@ -0,0 +1,178 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rand::Rng;
+
+use crate::error::ProbError;
+use crate::{IndexSampler, WeightedSampler};
+
+/// Weighted choice among backends that can be marked unhealthy at runtime,
+/// e.g. picking an upstream server by capacity.
+///
+/// [`pick`](Self::pick) draws from the alias table and redraws when it lands
+/// on an unhealthy entry (rejection sampling, so the healthy entries keep
+/// their relative weights). After `max_retries` misses, which happens when
+/// most of the weight is unhealthy, it falls back to one O(n) pass over the
+/// healthy weights, so a pick is always bounded and still exact.
+///
+/// Health flags are atomics: mark entries from a health-check thread while
+/// request threads pick through a shared `&Picker`.
+///
+/// ```
+/// use droptables::Picker;
+///
+/// let upstreams = Picker::new([("10.0.0.1", 3.0), ("10.0.0.2", 1.0)]).unwrap();
+/// upstreams.mark_unhealthy(0);
+/// assert_eq!(upstreams.pick(&mut rand::rng()), Some(&"10.0.0.2"));
+/// ```
+#[derive(Debug)]
+pub struct Picker<T, S = WeightedSampler> {
+    items: Vec<T>,
+    weights: Vec<f32>,
+    sampler: S,
+    healthy: Vec<AtomicBool>,
+    max_retries: usize,
+}
+
+impl<T> Picker<T> {
+    /// Build from `(item, weight)` pairs; every entry starts healthy.
+    ///
+    /// # Errors
+    /// Whatever [`WeightedSampler::new`] reports for the weights.
+    pub fn new<I>(pairs: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = (T, f32)>,
+    {
+        let (items, weights): (Vec<T>, Vec<f32>) = pairs.into_iter().unzip();
+        let sampler = WeightedSampler::new(&weights)?;
+        Ok(Self::from_parts(items, weights, sampler))
+    }
+}
+
+impl<T, S: IndexSampler> Picker<T, S> {
+    /// Redraws before [`pick`](Self::pick) falls back to a linear scan.
+    pub const DEFAULT_MAX_RETRIES: usize = 8;
+
+    /// Use a prebuilt `sampler` over `weights`.
+    ///
+    /// # Panics
+    /// If `items`, `weights` and `sampler` disagree on length.
+    pub fn from_parts(items: Vec<T>, weights: Vec<f32>, sampler: S) -> Self {
+        assert_eq!(items.len(), weights.len(), "items and weights differ");
+        assert_eq!(items.len(), sampler.len(), "items and sampler differ");
+        let healthy = items.iter().map(|_| AtomicBool::new(true)).collect();
+        Self {
+            items,
+            weights,
+            sampler,
+            healthy,
+            max_retries: Self::DEFAULT_MAX_RETRIES,
+        }
+    }
+
+    /// Redraws before the linear fallback (default
+    /// [`DEFAULT_MAX_RETRIES`](Self::DEFAULT_MAX_RETRIES)).
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn mark_unhealthy(&self, index: usize) {
+        self.healthy[index].store(false, Ordering::Relaxed);
+    }
+
+    pub fn mark_healthy(&self, index: usize) {
+        self.healthy[index].store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_healthy(&self, index: usize) -> bool {
+        self.healthy[index].load(Ordering::Relaxed)
+    }
+
+    pub fn healthy_count(&self) -> usize {
+        (0..self.len()).filter(|&i| self.is_healthy(i)).count()
+    }
+
+    /// Index of a healthy entry drawn by weight, or `None` if no healthy
+    /// entry has positive weight.
+    pub fn pick_index<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<usize> {
+        let mut rng = rng;
+        for _ in 0..self.max_retries {
+            let i = self.sampler.sample_index_dyn(&mut rng);
+            if self.is_healthy(i) {
+                return Some(i);
+            }
+        }
+        // most of the mass is unhealthy: draw from the healthy remainder
+        let mass = |i: usize| {
+            if self.is_healthy(i) {
+                f64::from(self.weights[i])
+            } else {
+                0.0
+            }
+        };
+        let total: f64 = (0..self.len()).map(mass).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut u = rng.random::<f64>() * total;
+        let mut last = None;
+        for i in 0..self.len() {
+            let m = mass(i);
+            if m > 0.0 {
+                if u < m {
+                    return Some(i);
+                }
+                u -= m;
+                last = Some(i);
+            }
+        }
+        // rounding left `u` just past the end
+        last
+    }
+
+    /// A healthy item drawn by weight; see [`pick_index`](Self::pick_index).
+    pub fn pick<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&T> {
+        self.pick_index(rng).map(|i| &self.items[i])
+    }
+
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unhealthy_entries_are_skipped() {
+        let picker = Picker::new([("a", 97.0), ("b", 1.0), ("c", 2.0), ("d", 0.0)])
+            .unwrap()
+            .with_max_retries(2);
+        picker.mark_unhealthy(0);
+        assert_eq!(picker.healthy_count(), 3);
+        let mut rng = rand::rng();
+        let mut c = 0;
+        for _ in 0..30_000 {
+            let i = picker.pick_index(&mut rng).unwrap();
+            assert!(i == 1 || i == 2, "{i}");
+            c += usize::from(i == 2);
+        }
+        // b:c stays 1:2 through both the retry and the fallback path
+        assert!((c as f64 / 30_000.0 - 2.0 / 3.0).abs() < 0.015, "{c}");
+
+        picker.mark_unhealthy(1);
+        picker.mark_unhealthy(2);
+        assert_eq!(picker.pick(&mut rng), None);
+        picker.mark_healthy(1);
+        assert_eq!(picker.pick(&mut rng), Some(&"b"));
+    }
+}
@@ -0,0 +1,335 @@
+use rand::Rng;
+
+use crate::error::ProbError;
+use crate::walker::WeightedSampler;
+
+/// How values are spread inside a [`ShapedBin`]. Each shape maps a uniform
+/// `u` in `[0, 1)` to a position `t` in `[0, 1)` across the bin.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Shape {
+    /// Flat: `t = u`.
+    #[default]
+    Uniform,
+    /// `t = u.powf(gamma)`, `gamma > 0`. Above 1 favors the low end, below
+    /// 1 the high end.
+    Gamma(f64),
+    /// Density `1 + slope * (2t - 1)`, `slope` in `[-1, 1]`: a straight
+    /// ramp, rising toward the high end for positive slopes (at `1`, the
+    /// low end has zero density).
+    Linear(f64),
+    /// Triangle peaking at `mode` in `[0, 1]` across the bin.
+    Triangular(f64),
+}
+
+impl Shape {
+    fn is_valid(self) -> bool {
+        match self {
+            Shape::Uniform => true,
+            Shape::Gamma(g) => g.is_finite() && g > 0.0,
+            Shape::Linear(s) => (-1.0..=1.0).contains(&s),
+            Shape::Triangular(c) => (0.0..=1.0).contains(&c),
+        }
+    }
+
+    /// Position across the bin for a uniform `u`, by inverting the CDF.
+    fn position(self, u: f64) -> f64 {
+        match self {
+            Shape::Uniform => u,
+            Shape::Gamma(g) => u.powf(g),
+            Shape::Linear(s) if s.abs() < 1e-12 => u,
+            // F(t) = s t^2 + (1 - s) t
+            Shape::Linear(s) => {
+                ((1.0 - s).mul_add(1.0 - s, 4.0 * s * u).sqrt() - (1.0 - s)) / (2.0 * s)
+            }
+            Shape::Triangular(c) if u < c => (u * c).sqrt(),
+            Shape::Triangular(c) => 1.0 - ((1.0 - u) * (1.0 - c)).sqrt(),
+        }
+    }
+}
+
+/// A `[lo, hi]` range with a [`Shape`], for [`Piecewise::from_shaped_bins`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapedBin {
+    pub lo: f64,
+    pub hi: f64,
+    pub shape: Shape,
+}
+
+impl ShapedBin {
+    /// A uniform bin.
+    pub fn new(lo: impl Into<f64>, hi: impl Into<f64>) -> Self {
+        Self {
+            lo: lo.into(),
+            hi: hi.into(),
+            shape: Shape::Uniform,
+        }
+    }
+
+    pub fn with_shape(mut self, shape: Shape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// [`Shape::Gamma`].
+    pub fn gamma(self, gamma: f64) -> Self {
+        self.with_shape(Shape::Gamma(gamma))
+    }
+
+    /// [`Shape::Linear`].
+    pub fn linear(self, slope: f64) -> Self {
+        self.with_shape(Shape::Linear(slope))
+    }
+
+    /// [`Shape::Triangular`].
+    pub fn triangular(self, mode: f64) -> Self {
+        self.with_shape(Shape::Triangular(mode))
+    }
+}
+
+/// A weighted piecewise distribution over numbers.
+///
+/// Each bin is a range with a weight: a draw picks a bin with the alias
+/// table, then a value inside it, uniformly unless the bin has a [`Shape`].
+/// This collapses the common "choose a tier, then roll a value in the
+/// tier's range" into one type.
+///
+/// `T` is the type the bins were given in (`u32` stat ranges, `f32`
+/// multipliers); draws are `f64` or `i64` whatever it is.
+///
+/// ```
+/// use droptables::Piecewise;
+///
+/// let stat: Piecewise<u32> = Piecewise::from_bins([((10, 20), 9.0), ((40, 50), 1.0)]).unwrap();
+/// assert_eq!(stat.bins()[1], (40, 50));
+/// let roll = stat.sample_i64(&mut rand::rng());
+/// assert!((10..=20).contains(&roll) || (40..=50).contains(&roll));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Piecewise<T = f64> {
+    alias: WeightedSampler,
+    bins: Vec<(T, T)>,
+    shapes: Vec<Shape>,
+}
+
+impl<T: Into<f64> + Copy> Piecewise<T> {
+    /// Build from `((lo, hi), weight)` bins. `lo == hi` is allowed and
+    /// always yields that value.
+    ///
+    /// # Errors
+    /// * [`ProbError::InvalidRange`] if `lo > hi` or either end is not finite.
+    /// * Anything [`WeightedSampler::new`] rejects for the weights.
+    pub fn from_bins<I>(bins: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = ((T, T), f32)>,
+    {
+        Self::build(
+            bins.into_iter().map(|(bin, w)| (bin, Shape::Uniform, w)),
+            false,
+        )
+    }
+
+    /// [`from_bins`](Self::from_bins) for tables drawn with
+    /// [`sample_i64`](Self::sample_i64): a bin with no integer in it, such
+    /// as `(0.2, 0.8)`, is rejected up front instead of panicking on a draw.
+    /// Bins over integer types always qualify.
+    ///
+    /// # Errors
+    /// As [`from_bins`](Self::from_bins), with [`ProbError::InvalidRange`]
+    /// also for a bin containing no integer.
+    pub fn from_integer_bins<I>(bins: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = ((T, T), f32)>,
+    {
+        Self::build(
+            bins.into_iter().map(|(bin, w)| (bin, Shape::Uniform, w)),
+            true,
+        )
+    }
+
+    fn build<I>(bins: I, integral: bool) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = ((T, T), Shape, f32)>,
+    {
+        let mut ranges = Vec::new();
+        let mut shapes = Vec::new();
+        let mut weights = Vec::new();
+        for (index, ((lo_t, hi_t), shape, w)) in bins.into_iter().enumerate() {
+            let (lo, hi): (f64, f64) = (lo_t.into(), hi_t.into());
+            if !(lo.is_finite() && hi.is_finite() && lo <= hi) {
+                return Err(ProbError::InvalidRange { index });
+            }
+            if integral && lo.ceil() > hi.floor() {
+                return Err(ProbError::InvalidRange { index });
+            }
+            if !shape.is_valid() {
+                return Err(ProbError::InvalidShape { index });
+            }
+            ranges.push((lo_t, hi_t));
+            shapes.push(shape);
+            weights.push(w);
+        }
+        let alias = WeightedSampler::new(&weights)?;
+        Ok(Self {
+            alias,
+            bins: ranges,
+            shapes,
+        })
+    }
+
+    fn range(&self, bin: usize) -> (f64, f64) {
+        let (lo, hi) = self.bins[bin];
+        (lo.into(), hi.into())
+    }
+
+    fn value_in<R: Rng + ?Sized>(&self, bin: usize, rng: &mut R) -> f64 {
+        let (lo, hi) = self.range(bin);
+        lo + (hi - lo) * self.shapes[bin].position(rng.random::<f64>())
+    }
+
+    /// Draw a value in `[lo, hi)` of the chosen bin, spread by its shape.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let i = self.alias.sample_index(rng);
+        self.value_in(i, rng)
+    }
+
+    /// Draw an integer in `ceil(lo)..=floor(hi)` of the chosen bin (bins are
+    /// treated as inclusive integer ranges), uniformly or spread by the
+    /// bin's shape.
+    ///
+    /// # Panics
+    /// If the chosen bin contains no integer, which
+    /// [`from_integer_bins`](Self::from_integer_bins) rules out.
+    pub fn sample_i64<R: Rng + ?Sized>(&self, rng: &mut R) -> i64 {
+        let i = self.alias.sample_index(rng);
+        let (lo, hi) = self.range(i);
+        let (lo, hi) = (lo.ceil() as i64, hi.floor() as i64);
+        assert!(lo <= hi, "bin {i} contains no integer");
+        match self.shapes[i] {
+            Shape::Uniform => rng.random_range(lo..=hi),
+            shape => {
+                let span = (hi - lo + 1) as f64;
+                let t = shape.position(rng.random::<f64>());
+                (lo + (span * t) as i64).min(hi)
+            }
+        }
+    }
+
+    /// Draw the bin index together with a value inside it.
+    pub fn sample_with_bin<R: Rng + ?Sized>(&self, rng: &mut R) -> (usize, f64) {
+        let i = self.alias.sample_index(rng);
+        (i, self.value_in(i, rng))
+    }
+
+    /// Each bin's [`Shape`], in insertion order.
+    pub fn shapes(&self) -> &[Shape] {
+        &self.shapes
+    }
+
+    /// The `(lo, hi)` bins in insertion order.
+    pub fn bins(&self) -> &[(T, T)] {
+        &self.bins
+    }
+
+    /// Number of bins.
+    pub fn len(&self) -> usize {
+        self.bins.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bins.is_empty()
+    }
+}
+
+impl Piecewise {
+    /// Build from `(ShapedBin, weight)` pairs.
+    ///
+    /// ```
+    /// use droptables::{Piecewise, ShapedBin};
+    ///
+    /// // damage rolls: most hits low in their tier, crits peak at 90
+    /// let damage = Piecewise::from_shaped_bins([
+    ///     (ShapedBin::new(10, 50).gamma(2.0), 9.0),
+    ///     (ShapedBin::new(60, 100).triangular(0.75), 1.0),
+    /// ])
+    /// .unwrap();
+    /// let hit = damage.sample(&mut rand::rng());
+    /// assert!((10.0..=100.0).contains(&hit));
+    /// ```
+    ///
+    /// # Errors
+    /// * [`ProbError::InvalidRange`] if `lo > hi` or either end is not finite.
+    /// * [`ProbError::InvalidShape`] if a shape's parameter is outside the
+    ///   range documented on [`Shape`].
+    /// * Anything [`WeightedSampler::new`] rejects for the weights.
+    pub fn from_shaped_bins<I>(bins: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = (ShapedBin, f32)>,
+    {
+        Self::build(
+            bins.into_iter()
+                .map(|(ShapedBin { lo, hi, shape }, w)| ((lo, hi), shape, w)),
+            false,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_stay_in_chosen_bins() {
+        let pw = Piecewise::from_bins([((1, 5), 1.0), ((100, 100), 0.0), ((10, 20), 3.0)]).unwrap();
+        let mut rng = rand::rng();
+        for _ in 0..1000 {
+            let (bin, v) = pw.sample_with_bin(&mut rng);
+            let (lo, hi) = pw.bins()[bin];
+            assert!(v >= f64::from(lo) && v <= f64::from(hi));
+            let n = pw.sample_i64(&mut rng);
+            assert!((1..=5).contains(&n) || (10..=20).contains(&n));
+        }
+        assert!(matches!(
+            Piecewise::from_bins([((2.0, 1.0), 1.0)]),
+            Err(ProbError::InvalidRange { index: 0 })
+        ));
+    }
+
+    #[test]
+    fn integer_bins_must_hold_an_integer() {
+        let fractional = Piecewise::from_bins([((0.2, 0.8), 1.0)]).unwrap();
+        assert!((0.2..0.8).contains(&fractional.sample(&mut rand::rng())));
+        assert!(matches!(
+            Piecewise::from_integer_bins([((0.5, 2.5), 1.0), ((0.2, 0.8), 1.0)]),
+            Err(ProbError::InvalidRange { index: 1 })
+        ));
+        let ok = Piecewise::from_integer_bins([((0.5f32, 2.5), 1.0)]).unwrap();
+        assert!((1..=2).contains(&ok.sample_i64(&mut rand::rng())));
+    }
+
+    #[test]
+    fn shapes_skew_values_within_bins() {
+        let mean_t = |shape: Shape| {
+            let pw = Piecewise::from_shaped_bins([(ShapedBin::new(0, 1).with_shape(shape), 1.0)])
+                .unwrap();
+            let mut rng = rand::rng();
+            (0..40_000).map(|_| pw.sample(&mut rng)).sum::<f64>() / 40_000.0
+        };
+        // E[u^g] = 1/(g+1); ramp: 1/2 + s/6; triangle: (1 + c)/3
+        for (shape, expected) in [
+            (Shape::Uniform, 0.5),
+            (Shape::Gamma(3.0), 0.25),
+            (Shape::Linear(1.0), 2.0 / 3.0),
+            (Shape::Linear(-0.6), 0.4),
+            (Shape::Triangular(0.25), 1.25 / 3.0),
+        ] {
+            let m = mean_t(shape);
+            assert!((m - expected).abs() < 0.01, "{shape:?}: {m}");
+        }
+        assert!(matches!(
+            Piecewise::from_shaped_bins([(ShapedBin::new(0, 1).linear(2.0), 1.0)]),
+            Err(ProbError::InvalidShape { index: 0 })
+        ));
+        let ints = Piecewise::from_shaped_bins([(ShapedBin::new(1, 3).gamma(0.5), 1.0)]).unwrap();
+        assert!((0..1000).all(|_| (1..=3).contains(&ints.sample_i64(&mut rand::rng()))));
+    }
+}
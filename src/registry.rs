@@ -0,0 +1,204 @@
+//! A process-wide directory of named drop tables (feature `registry`).
+//!
+//! Tables of any item type are registered under a path-like name such as
+//! `"boss/graardor"`. Game code fetches them back by name and type; tooling
+//! and debug consoles can list every live table with its size, without
+//! knowing the item types.
+//!
+//! ```
+//! use droptables::DropTable;
+//! use droptables::registry::TableRegistry;
+//!
+//! let registry = TableRegistry::global();
+//! registry.register(
+//!     "boss/graardor",
+//!     DropTable::from_pairs([("bones", 127.0), ("bandos chestplate", 1.0)]).unwrap(),
+//! );
+//!
+//! let table = registry.get::<&str>("boss/graardor").unwrap();
+//! table.sample(&mut rand::rng());
+//!
+//! for info in registry.tables() {
+//!     println!("{} ({}): {} items, {} bytes", info.name, info.item_type, info.len, info.heap_bytes);
+//! }
+//! ```
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use crate::DropTable;
+
+/// What [`TableRegistry::tables`] reports about one table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableInfo {
+    pub name: String,
+    /// `std::any::type_name` of the item type.
+    pub item_type: &'static str,
+    pub len: usize,
+    /// Approximate heap footprint: the item, weight and sampler arrays.
+    /// Heap data owned by the items themselves (e.g. `String` contents) is
+    /// not counted.
+    pub heap_bytes: usize,
+}
+
+struct Entry {
+    table: Arc<dyn Any + Send + Sync>,
+    info: TableInfo,
+}
+
+/// Named drop tables of mixed item types, shareable across threads.
+///
+/// Use [`global`](Self::global) for the process-wide instance, or
+/// [`new`](Self::new) for a scoped one (e.g. per test).
+#[derive(Default)]
+pub struct TableRegistry {
+    tables: RwLock<BTreeMap<String, Entry>>,
+}
+
+impl std::fmt::Debug for TableRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.tables()).finish()
+    }
+}
+
+impl TableRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The process-wide registry.
+    pub fn global() -> &'static TableRegistry {
+        static GLOBAL: OnceLock<TableRegistry> = OnceLock::new();
+        GLOBAL.get_or_init(TableRegistry::new)
+    }
+
+    /// Register `table` under `name`, replacing any table already there,
+    /// and return the shared handle.
+    pub fn register<T: Send + Sync + 'static>(
+        &self,
+        name: impl Into<String>,
+        table: DropTable<T>,
+    ) -> Arc<DropTable<T>> {
+        let name = name.into();
+        let info = TableInfo {
+            name: name.clone(),
+            item_type: std::any::type_name::<T>(),
+            len: table.len(),
+            heap_bytes: table.heap_bytes(),
+        };
+        let table = Arc::new(table);
+        let entry = Entry {
+            table: table.clone(),
+            info,
+        };
+        self.write().insert(name, entry);
+        table
+    }
+
+    /// The table registered under `name`, if there is one with items of
+    /// type `T`.
+    pub fn get<T: Send + Sync + 'static>(&self, name: &str) -> Option<Arc<DropTable<T>>> {
+        let table = self.read().get(name)?.table.clone();
+        table.downcast().ok()
+    }
+
+    /// Unregister `name`; handles already handed out stay valid.
+    pub fn remove(&self, name: &str) -> Option<TableInfo> {
+        self.write().remove(name).map(|e| e.info)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.read().contains_key(name)
+    }
+
+    /// Every registered table, sorted by name.
+    pub fn tables(&self) -> Vec<TableInfo> {
+        self.read().values().map(|e| e.info.clone()).collect()
+    }
+
+    /// Tables whose names start with `prefix`, e.g. `"boss/"`.
+    pub fn tables_under(&self, prefix: &str) -> Vec<TableInfo> {
+        self.read()
+            .range(prefix.to_owned()..)
+            .take_while(|(name, _)| name.starts_with(prefix))
+            .map(|(_, e)| e.info.clone())
+            .collect()
+    }
+
+    /// Sum of [`TableInfo::heap_bytes`] over every table.
+    pub fn memory_usage(&self) -> usize {
+        self.read().values().map(|e| e.info.heap_bytes).sum()
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    // a panic while holding the lock can't leave the map half-updated
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, BTreeMap<String, Entry>> {
+        self.tables.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, BTreeMap<String, Entry>> {
+        self.tables.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<T> DropTable<T> {
+    fn heap_bytes(&self) -> usize {
+        std::mem::size_of_val(self.items.as_slice())
+            + std::mem::size_of_val(self.weights.as_slice())
+            + self.sampler.heap_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookups_are_by_name_and_type() {
+        let registry = TableRegistry::new();
+        registry.register(
+            "boss/graardor",
+            DropTable::from_pairs((0..8u32).map(|i| (i, 1.0 + i as f32))).unwrap(),
+        );
+        registry.register(
+            "boss/kree",
+            DropTable::from_pairs([("feather", 1.0)]).unwrap(),
+        );
+        registry.register("goblin", DropTable::from_pairs([("bones", 1.0)]).unwrap());
+
+        assert!(registry.get::<u32>("boss/graardor").is_some());
+        assert!(registry.get::<&str>("boss/graardor").is_none());
+        assert!(registry.get::<u32>("boss/zilyana").is_none());
+
+        let bosses: Vec<_> = registry
+            .tables_under("boss/")
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(bosses, ["boss/graardor", "boss/kree"]);
+
+        let graardor = &registry.tables()[0];
+        assert_eq!(graardor.item_type, "u32");
+        // items + weights + 16-byte buckets
+        assert_eq!(graardor.heap_bytes, 8 * (4 + 4 + 16));
+        assert_eq!(
+            registry.memory_usage(),
+            registry
+                .tables()
+                .iter()
+                .map(|t| t.heap_bytes)
+                .sum::<usize>()
+        );
+
+        assert_eq!(registry.remove("goblin").map(|t| t.len), Some(1));
+        assert_eq!(registry.len(), 2);
+    }
+}
@@ -0,0 +1,209 @@
+//! Tables fetched from somewhere else, such as a CDN (feature `remote`).
+//!
+//! The crate does no networking itself: implement [`RemoteTableSource`] with
+//! whatever client you already use, and [`RefreshingTable`] takes care of
+//! parsing the bytes (the [text format](crate::parse)), refreshing once they
+//! are older than a TTL, and falling back to the last good table when a
+//! fetch or parse fails.
+//!
+//! ```
+//! use std::time::Duration;
+//! use droptables::remote::RefreshingTable;
+//!
+//! // any `Fn() -> Result<Vec<u8>, E>` is a source
+//! let fetch = || Ok::<_, std::io::Error>(b"coins: 9, rune: 1".to_vec());
+//! let table = RefreshingTable::new(fetch, Duration::from_secs(300)).unwrap();
+//! let drop = table.sample(&mut rand::rng());
+//! ```
+
+use std::fmt;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::DropTable;
+use crate::parse::ParseError;
+
+/// Somewhere table definitions can be fetched from.
+pub trait RemoteTableSource {
+    type Error;
+
+    /// The current table definition, as text-format bytes.
+    fn fetch(&self) -> Result<Vec<u8>, Self::Error>;
+}
+
+impl<F, E> RemoteTableSource for F
+where
+    F: Fn() -> Result<Vec<u8>, E>,
+{
+    type Error = E;
+
+    fn fetch(&self) -> Result<Vec<u8>, E> {
+        self()
+    }
+}
+
+/// Why a fetch didn't produce a table.
+#[derive(Debug)]
+pub enum RemoteError<E> {
+    /// The source failed.
+    Fetch(E),
+    /// The bytes are not valid UTF-8.
+    Utf8,
+    Parse(ParseError),
+}
+
+impl<E: fmt::Display> fmt::Display for RemoteError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoteError::Fetch(e) => write!(f, "fetch failed: {e}"),
+            RemoteError::Utf8 => write!(f, "table is not valid UTF-8"),
+            RemoteError::Parse(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for RemoteError<E> {}
+
+/// Fetch from `source` and parse the result.
+///
+/// # Errors
+/// See [`RemoteError`].
+pub fn fetch_table<S: RemoteTableSource>(
+    source: &S,
+) -> Result<DropTable<String>, RemoteError<S::Error>> {
+    let bytes = source.fetch().map_err(RemoteError::Fetch)?;
+    let src = String::from_utf8(bytes).map_err(|_| RemoteError::Utf8)?;
+    DropTable::parse(&src).map_err(RemoteError::Parse)
+}
+
+/// A table from a [`RemoteTableSource`], refetched once it is older than
+/// `ttl`; see the [module docs](self).
+///
+/// Refreshes happen on the calling thread, inside [`load`](Self::load) or
+/// [`sample`](Self::sample), for whichever caller first finds the table
+/// stale. A failed refresh keeps serving the last good table and waits
+/// another `ttl` before retrying.
+#[derive(Debug)]
+pub struct RefreshingTable<S: RemoteTableSource> {
+    source: S,
+    ttl: Duration,
+    table: RwLock<Arc<DropTable<String>>>,
+    /// `None` until the first fetch.
+    fetched_at: Mutex<Option<Instant>>,
+    error: Mutex<Option<RemoteError<S::Error>>>,
+}
+
+impl<S: RemoteTableSource> RefreshingTable<S> {
+    /// Fetch the first table now.
+    ///
+    /// # Errors
+    /// If the initial fetch fails; there is nothing to fall back to yet.
+    pub fn new(source: S, ttl: Duration) -> Result<Self, RemoteError<S::Error>> {
+        let table = fetch_table(&source)?;
+        let table = Self::with_fallback(source, ttl, table);
+        *table.fetched_at.lock().unwrap() = Some(Instant::now());
+        Ok(table)
+    }
+
+    /// Start from `fallback` (e.g. a table bundled with the build) and
+    /// fetch on first use.
+    pub fn with_fallback(source: S, ttl: Duration, fallback: DropTable<String>) -> Self {
+        Self {
+            source,
+            ttl,
+            table: RwLock::new(Arc::new(fallback)),
+            fetched_at: Mutex::new(None),
+            error: Mutex::new(None),
+        }
+    }
+
+    /// Fetch now regardless of age. On success the new table is swapped in;
+    /// on failure the current one stays.
+    ///
+    /// # Errors
+    /// See [`RemoteError`].
+    pub fn refresh(&self) -> Result<(), RemoteError<S::Error>> {
+        *self.fetched_at.lock().unwrap() = Some(Instant::now());
+        let table = fetch_table(&self.source)?;
+        *self.table.write().unwrap() = Arc::new(table);
+        Ok(())
+    }
+
+    fn refresh_if_stale(&self) {
+        {
+            let mut at = self.fetched_at.lock().unwrap();
+            if at.is_some_and(|t| t.elapsed() < self.ttl) {
+                return;
+            }
+            // claim this refresh so concurrent callers keep the old table
+            *at = Some(Instant::now());
+        }
+        match fetch_table(&self.source) {
+            Ok(table) => *self.table.write().unwrap() = Arc::new(table),
+            Err(e) => *self.error.lock().unwrap() = Some(e),
+        }
+    }
+
+    /// The current table, refreshing first if it is stale.
+    pub fn load(&self) -> Arc<DropTable<String>> {
+        self.refresh_if_stale();
+        Arc::clone(&self.table.read().unwrap())
+    }
+
+    /// Draw one item from the current table, refreshing first if it is
+    /// stale.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        self.refresh_if_stale();
+        self.table.read().unwrap().sample(rng).clone()
+    }
+
+    /// The error from the most recent failed automatic refresh, if any,
+    /// clearing it.
+    pub fn take_error(&self) -> Option<RemoteError<S::Error>> {
+        self.error.lock().unwrap().take()
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn refreshes_when_stale_and_falls_back_on_failure() {
+        let responses = RefCell::new(vec![
+            Err("cdn down"),
+            Ok(b"rune: 1".to_vec()),
+            Ok(b"coins: 1".to_vec()),
+        ]);
+        let source = || responses.borrow_mut().pop().unwrap();
+
+        let table = RefreshingTable::new(source, Duration::ZERO).unwrap();
+        assert_eq!(table.sample(&mut rand::rng()), "rune");
+        // the next fetch fails: keep serving the last good table
+        assert_eq!(table.sample(&mut rand::rng()), "rune");
+        assert!(matches!(
+            table.take_error(),
+            Some(RemoteError::Fetch("cdn down"))
+        ));
+
+        let cached = RefreshingTable::with_fallback(
+            || Ok::<_, ()>(b"pet: 1".to_vec()),
+            Duration::from_secs(3600),
+            DropTable::parse("bones: 1").unwrap(),
+        );
+        assert_eq!(cached.load().items(), ["pet"]);
+        let bad = RefreshingTable::new(|| Ok::<_, ()>(b"pet".to_vec()), Duration::ZERO);
+        assert!(matches!(bad, Err(RemoteError::Parse(_))));
+    }
+}
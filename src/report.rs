@@ -0,0 +1,132 @@
+use std::fmt;
+
+use crate::{DropTable, StaticDropTable, UniformSampler, WeightedSampler};
+
+/// A printable list of `(item, probability)` pairs, as generated by
+/// `WeightedEnum::probability_report()`.
+///
+/// Displays one aligned row per item: probability as a percentage and as
+/// "1 in N" odds.
+#[derive(Debug, Clone, Copy)]
+pub struct ProbabilityReport<T: 'static> {
+    entries: &'static [(T, f64)],
+}
+
+impl<T> ProbabilityReport<T> {
+    pub const fn new(entries: &'static [(T, f64)]) -> Self {
+        Self { entries }
+    }
+
+    /// The underlying `(item, probability)` pairs.
+    pub const fn entries(&self) -> &'static [(T, f64)] {
+        self.entries
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for ProbabilityReport<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<(String, Option<f32>, f64)> = self
+            .entries
+            .iter()
+            .map(|(t, p)| (format!("{t:?}"), None, *p))
+            .collect();
+        write_rows(f, &rows)
+    }
+}
+
+/// Shared printer for reports and tables: one aligned row per
+/// `(name, weight, probability)`. The weight column is only shown when at
+/// least one row has a weight.
+pub(crate) fn write_rows(
+    f: &mut fmt::Formatter<'_>,
+    rows: &[(String, Option<f32>, f64)],
+) -> fmt::Result {
+    let w = rows.iter().map(|r| r.0.len()).max().unwrap_or(0).max(4);
+    let weighted = rows.iter().any(|r| r.1.is_some());
+    write!(f, "{:<w$}", "item")?;
+    if weighted {
+        write!(f, " {:>12}", "weight")?;
+    }
+    writeln!(f, " {:>12} {:>14}", "probability", "odds")?;
+    for (name, weight, p) in rows {
+        let odds = if *p > 0.0 {
+            format!("1 in {:.2}", 1.0 / p)
+        } else {
+            "never".to_string()
+        };
+        write!(f, "{name:<w$}")?;
+        match weight {
+            Some(weight) => write!(f, " {weight:>12}")?,
+            None if weighted => write!(f, " {:>12}", "-")?,
+            None => {}
+        }
+        writeln!(f, " {:>11.6}% {odds:>14}", p * 100.0)?;
+    }
+    Ok(())
+}
+
+impl<T: fmt::Debug> fmt::Display for DropTable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<(String, Option<f32>, f64)> = self
+            .iter()
+            .zip(self.probabilities())
+            .map(|((t, w), p)| (format!("{t:?}"), Some(w), p))
+            .collect();
+        write_rows(f, &rows)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for StaticDropTable<WeightedSampler, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<(String, Option<f32>, f64)> = self
+            .items()
+            .iter()
+            .zip(self.sampler().probabilities())
+            .map(|(t, p)| (format!("{t:?}"), None, p))
+            .collect();
+        write_rows(f, &rows)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for StaticDropTable<UniformSampler, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let p = 1.0 / self.len() as f64;
+        let rows: Vec<(String, Option<f32>, f64)> = self
+            .items()
+            .iter()
+            .map(|t| (format!("{t:?}"), None, p))
+            .collect();
+        write_rows(f, &rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_display_shows_weights_and_odds() {
+        let dt = DropTable::from_pairs([("common", 3.0), ("rare", 1.0), ("never", 0.0)]).unwrap();
+        let out = dt.to_string();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines[0],
+            "item           weight  probability           odds"
+        );
+        assert_eq!(
+            lines[2],
+            "\"rare\"              1   25.000000%      1 in 4.00"
+        );
+        assert!(lines[3].ends_with("never"));
+
+        static ITEMS: [u8; 2] = [1, 2];
+        let st = StaticDropTable::new(UniformSampler::new(2).unwrap(), &ITEMS);
+        assert!(
+            st.to_string()
+                .lines()
+                .nth(1)
+                .unwrap()
+                .contains("50.000000%")
+        );
+    }
+}
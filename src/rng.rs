@@ -0,0 +1,100 @@
+//! A small, seedable default RNG (feature `smallrng`).
+//!
+//! [`DropRng`] wraps PCG32 (`Lcg64Xsh32` from `rand_pcg`): 64-bit state,
+//! 32-bit output, fixed and documented, so a given seed produces the same
+//! draws on every platform and across `droptables` releases that keep this
+//! type. Not cryptographically secure.
+
+use std::cell::RefCell;
+
+use rand::{RngCore, SeedableRng};
+use rand_pcg::Pcg32;
+
+use crate::{DropTable, IndexSampler, StaticDropTable};
+
+/// Seedable RNG for sampling without touching `rand` directly.
+///
+/// ```
+/// use droptables::{DropRng, DropTable};
+///
+/// let table = DropTable::from_pairs([("a", 1.0), ("b", 3.0)]).unwrap();
+/// let mut rng = DropRng::seeded(7);
+/// let first = *table.sample(&mut rng);
+/// assert_eq!(first, *table.sample(&mut DropRng::seeded(7)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropRng(Pcg32);
+
+impl DropRng {
+    /// Deterministic stream for `seed`.
+    pub fn seeded(seed: u64) -> Self {
+        Self(Pcg32::seed_from_u64(seed))
+    }
+
+    /// Seeded from the OS entropy source.
+    pub fn from_entropy() -> Self {
+        Self(Pcg32::from_os_rng())
+    }
+}
+
+impl Default for DropRng {
+    fn default() -> Self {
+        Self::from_entropy()
+    }
+}
+
+impl RngCore for DropRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+    #[inline]
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.0.fill_bytes(dst)
+    }
+}
+
+thread_local! {
+    static DEFAULT_RNG: RefCell<DropRng> = RefCell::new(DropRng::from_entropy());
+}
+
+/// Run `f` with this thread's entropy-seeded [`DropRng`].
+fn with_default<R>(f: impl FnOnce(&mut DropRng) -> R) -> R {
+    DEFAULT_RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+impl<T> DropTable<T> {
+    /// [`sample`](Self::sample) using a thread-local, entropy-seeded
+    /// [`DropRng`]. Use [`DropRng::seeded`] when you need reproducibility.
+    pub fn sample_default(&self) -> &T {
+        with_default(|rng| self.sample(rng))
+    }
+}
+
+impl<S: IndexSampler, T> StaticDropTable<S, T> {
+    /// [`sample`](Self::sample) using a thread-local, entropy-seeded
+    /// [`DropRng`].
+    pub fn sample_default(&self) -> &'static T {
+        with_default(|rng| self.sample(rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_stream_is_stable() {
+        // Pinned so an accidental algorithm change is caught.
+        let mut rng = DropRng::seeded(0);
+        let got: Vec<u32> = (0..3).map(|_| rng.next_u32()).collect();
+        assert_eq!(got, [298703107, 4236525527, 336081875]);
+
+        let table = DropTable::from_pairs([(1, 1.0), (2, 0.0)]).unwrap();
+        assert_eq!(*table.sample_default(), 1);
+    }
+}
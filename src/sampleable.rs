@@ -0,0 +1,131 @@
+use rand::Rng;
+
+use crate::{
+    DropTable, DropTableN, DropTableRef, IndexSampler, NestedTable, StaticDropTable, UniformTable,
+};
+
+/// Anything that draws a `&T`: the item-level counterpart of
+/// [`IndexSampler`], so systems like quest rewards or AI behaviour pickers
+/// can take `impl Sampleable<T>` instead of one concrete table type.
+///
+/// ```
+/// use droptables::{DropTable, Sampleable, UniformTable};
+///
+/// fn reward<S: Sampleable<&'static str>>(table: &S) -> &'static str {
+///     table.sample_owned(&mut rand::rng())
+/// }
+///
+/// let weighted = DropTable::from_pairs([("gold", 9.0), ("gem", 1.0)]).unwrap();
+/// let uniform = UniformTable::from_array(["gold", "gem"]).unwrap();
+/// assert!(["gold", "gem"].contains(&reward(&weighted)));
+/// assert!(["gold", "gem"].contains(&reward(&uniform)));
+/// ```
+///
+/// The tables' inherent `sample` methods take precedence in method-call
+/// syntax and may return longer-lived references (e.g. `&'static T` from
+/// [`StaticDropTable`]); the trait is for generic code.
+pub trait Sampleable<T> {
+    /// Draw an item by reference.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &T;
+
+    /// Draw an item by value (clones).
+    #[inline]
+    fn sample_owned<R: Rng + ?Sized>(&self, rng: &mut R) -> T
+    where
+        T: Clone,
+    {
+        self.sample(rng).clone()
+    }
+}
+
+impl<T, S: Sampleable<T> + ?Sized> Sampleable<T> for &S {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        (**self).sample(rng)
+    }
+}
+
+impl<T> Sampleable<T> for DropTable<T> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        DropTable::sample(self, rng)
+    }
+}
+
+impl<T> Sampleable<T> for UniformTable<T> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        UniformTable::sample(self, rng)
+    }
+}
+
+impl<S: IndexSampler, T> Sampleable<T> for StaticDropTable<S, T> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        StaticDropTable::sample(self, rng)
+    }
+}
+
+impl<T, const N: usize> Sampleable<T> for DropTableN<T, N> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        DropTableN::sample(self, rng)
+    }
+}
+
+impl<T> Sampleable<T> for DropTableRef<'_, T> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        DropTableRef::sample(self, rng)
+    }
+}
+
+impl<T> Sampleable<T> for NestedTable<T> {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        NestedTable::sample(self, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NestedEntry, WeightedSampler};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    fn draw_all<S: Sampleable<u8>>(table: S, rng: &mut StdRng) -> Vec<u8> {
+        (0..50).map(|_| table.sample_owned(rng)).collect()
+    }
+
+    #[test]
+    fn every_table_type_is_sampleable() {
+        static ITEMS: [u8; 2] = [1, 2];
+        let mut rng = StdRng::seed_from_u64(1395);
+        let only_two = |v: Vec<u8>| v.iter().all(|&x| x == 2);
+
+        let table = DropTable::from_pairs([(1, 0.0), (2, 1.0)]).unwrap();
+        assert!(only_two(draw_all(&table, &mut rng)));
+        assert_eq!(table.len(), 2);
+        assert!(only_two(draw_all(
+            UniformTable::from_array([2, 2]).unwrap(),
+            &mut rng
+        )));
+        let sampler = WeightedSampler::new(&[0.0, 1.0]).unwrap();
+        assert!(only_two(draw_all(
+            StaticDropTable::new(sampler, &ITEMS),
+            &mut rng
+        )));
+        assert!(only_two(draw_all(
+            DropTableN::from_arrays([1, 2], [0.0, 1.0]).unwrap(),
+            &mut rng
+        )));
+        assert!(only_two(draw_all(
+            DropTableRef::new(&ITEMS, &[0.0, 1.0]).unwrap(),
+            &mut rng
+        )));
+        let inner = NestedTable::new("inner", [(NestedEntry::Item(2), 1.0)]).unwrap();
+        let outer =
+            NestedTable::new("outer", [(NestedEntry::Item(1), 0.0), (inner.into(), 1.0)]).unwrap();
+        assert!(only_two(draw_all(outer, &mut rng)));
+    }
+}
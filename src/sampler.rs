@@ -1,4 +1,4 @@
-use rand::Rng;
+use rand::{Rng, RngCore};
 
 use crate::{IndexSampler, error::ProbError, walker::WeightedSampler};
 
@@ -26,6 +26,10 @@ impl IndexSampler for UniformSampler {
     fn sample_index<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
         rng.random_range(0..self.n)
     }
+    #[inline]
+    fn sample_index_dyn(&self, rng: &mut dyn RngCore) -> usize {
+        self.sample_index(rng)
+    }
 }
 
 /// `WeightedSampler` is the weighted sampler; wire it into the trait.
@@ -40,4 +44,8 @@ impl IndexSampler for WeightedSampler {
         // call the inherent method explicitly to avoid trait-recursion
         WeightedSampler::sample_index(self, rng)
     }
+    #[inline]
+    fn sample_index_dyn(&self, rng: &mut dyn RngCore) -> usize {
+        WeightedSampler::sample_index(self, rng)
+    }
 }
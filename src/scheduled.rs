@@ -0,0 +1,145 @@
+use std::ops::Range;
+use std::sync::Mutex;
+
+use rand::Rng;
+
+use crate::error::ProbError;
+use crate::walker::WeightedSampler;
+
+/// How an entry's weight depends on time.
+enum Schedule {
+    /// `weight` while `t` is inside `window`, zero otherwise.
+    Window { window: Range<u64>, weight: f32 },
+    /// Arbitrary weight curve.
+    Func(Box<dyn Fn(u64) -> f32 + Send + Sync>),
+}
+
+impl Schedule {
+    fn weight_at(&self, t: u64) -> f32 {
+        match self {
+            Schedule::Window { window, weight } => {
+                if window.contains(&t) {
+                    *weight
+                } else {
+                    0.0
+                }
+            }
+            Schedule::Func(f) => f(t),
+        }
+    }
+}
+
+/// A drop table whose weights depend on a caller-supplied time `t` (game
+/// day, event phase, unix seconds, ...).
+///
+/// Time is bucketed into epochs of `epoch_len`; weights are evaluated at the
+/// start of each epoch and the resulting alias table is cached until a
+/// sample asks for a different epoch.
+pub struct ScheduledDropTable<T> {
+    items: Vec<T>,
+    schedules: Vec<Schedule>,
+    epoch_len: u64,
+    cache: Mutex<Option<(u64, Result<WeightedSampler, ProbError>)>>,
+}
+
+impl<T> ScheduledDropTable<T> {
+    /// An empty table with the given epoch length (clamped to at least 1).
+    pub fn new(epoch_len: u64) -> Self {
+        Self {
+            items: Vec::new(),
+            schedules: Vec::new(),
+            epoch_len: epoch_len.max(1),
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Add an entry active with `weight` for `t` in `window`.
+    pub fn window(mut self, item: T, weight: f32, window: Range<u64>) -> Self {
+        self.push(item, Schedule::Window { window, weight });
+        self
+    }
+
+    /// Add an entry that is always active with a fixed `weight`.
+    pub fn always(self, item: T, weight: f32) -> Self {
+        self.window(item, weight, 0..u64::MAX)
+    }
+
+    /// Add an entry whose weight is `f(t)`.
+    pub fn with_fn<F>(mut self, item: T, f: F) -> Self
+    where
+        F: Fn(u64) -> f32 + Send + Sync + 'static,
+    {
+        self.push(item, Schedule::Func(Box::new(f)));
+        self
+    }
+
+    fn push(&mut self, item: T, schedule: Schedule) {
+        self.items.push(item);
+        self.schedules.push(schedule);
+        *self.cache.get_mut().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// The epoch that `t` falls in.
+    pub fn epoch_of(&self, t: u64) -> u64 {
+        t / self.epoch_len
+    }
+
+    /// Weights in effect for `t` (evaluated at the start of its epoch).
+    pub fn weights_at(&self, t: u64) -> Vec<f32> {
+        let start = self.epoch_of(t) * self.epoch_len;
+        self.schedules.iter().map(|s| s.weight_at(start)).collect()
+    }
+
+    /// Sample at time `t`, rebuilding the alias table only when the epoch changes.
+    ///
+    /// # Errors
+    /// Whatever [`WeightedSampler::new`] reports for the epoch's weights,
+    /// e.g. [`ProbError::ZeroSum`] when nothing is active.
+    pub fn sample_at<R: Rng + ?Sized>(&self, t: u64, rng: &mut R) -> Result<&T, ProbError> {
+        let epoch = self.epoch_of(t);
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        if cache.as_ref().is_none_or(|(e, _)| *e != epoch) {
+            *cache = Some((epoch, WeightedSampler::new(&self.weights_at(t))));
+        }
+        let (_, sampler) = cache.as_ref().expect("cache filled above");
+        let i = sampler.as_ref().map_err(Clone::clone)?.sample_index(rng);
+        Ok(&self.items[i])
+    }
+
+    /// Items in insertion order.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether the table has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_and_functions_follow_time() {
+        let table = ScheduledDropTable::new(10)
+            .always("base", 1.0)
+            .window("event", 1000.0, 100..200)
+            .with_fn("ramp", |t| if t >= 300 { 1e6 } else { 0.0 });
+        let mut rng = rand::rng();
+        assert_eq!(table.weights_at(105), vec![1.0, 1000.0, 0.0]);
+        // epoch 29 starts at 290, before the ramp switches on
+        assert_eq!(table.weights_at(299), vec![1.0, 0.0, 0.0]);
+        assert_eq!(table.sample_at(50, &mut rng), Ok(&"base"));
+        assert_eq!(table.sample_at(299, &mut rng), Ok(&"base"));
+
+        let empty = ScheduledDropTable::new(1).window("x", 1.0, 5..6);
+        assert_eq!(empty.sample_at(0, &mut rng), Err(ProbError::ZeroSum));
+    }
+}
@@ -0,0 +1,212 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rand::{Rng, RngCore};
+
+use crate::{DropTable, IndexSampler};
+
+/// The stream both tables of a [`ShadowTable`] draw from: SplitMix64
+/// seeded with one `u64` taken from the caller's RNG.
+#[derive(Debug, Clone)]
+struct Fork(u64);
+
+impl RngCore for Fork {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for chunk in dst.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes()[..chunk.len()]);
+        }
+    }
+}
+
+/// Divergence between the two tables of a [`ShadowTable`] so far.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowStats {
+    pub draws: u64,
+    /// Draws where the two tables produced different items.
+    pub mismatches: u64,
+    /// Draws per entry of the old table.
+    pub old_counts: Vec<u64>,
+    /// Draws per entry of the new table.
+    pub new_counts: Vec<u64>,
+}
+
+impl ShadowStats {
+    /// `mismatches / draws` (0 before any draw).
+    pub fn mismatch_rate(&self) -> f64 {
+        if self.draws == 0 {
+            0.0
+        } else {
+            self.mismatches as f64 / self.draws as f64
+        }
+    }
+}
+
+/// Dual-run sampling for validating a table migration in production: serve
+/// drops from the `old` table while drawing from the `new` one alongside,
+/// and record how often and how far they diverge before cutting over.
+///
+/// # Forking the RNG
+///
+/// Each draw takes **one** `u64` from the caller's RNG and seeds a
+/// SplitMix64 stream with it; both tables then sample from their own copy
+/// of that stream. The two draws therefore see identical random numbers,
+/// so where the tables agree entry for entry they produce the same item,
+/// and every mismatch is caused by the migration rather than by RNG noise.
+/// The caller's RNG advances by exactly one `u64` per draw, whatever the
+/// tables consume, so adding or removing a shadow never shifts the rest of
+/// a seeded simulation. The served items still follow `old`'s
+/// distribution exactly.
+///
+/// ```
+/// use droptables::{DropTable, ShadowTable};
+///
+/// let old = DropTable::from_pairs([("bones", 127.0), ("spear", 1.0)]).unwrap();
+/// let new = DropTable::from_pairs([("bones", 126.0), ("spear", 2.0)]).unwrap();
+/// let shadow = ShadowTable::new(old, new);
+///
+/// let mut rng = rand::rng();
+/// for _ in 0..1000 {
+///     let _served = shadow.sample(&mut rng);
+/// }
+/// let stats = shadow.stats();
+/// assert_eq!(stats.draws, 1000);
+/// assert!(stats.mismatch_rate() < 0.05);
+/// ```
+///
+/// Statistics are atomics, so a shared `&ShadowTable` can serve many
+/// threads.
+#[derive(Debug)]
+pub struct ShadowTable<T> {
+    old: DropTable<T>,
+    new: DropTable<T>,
+    draws: AtomicU64,
+    mismatches: AtomicU64,
+    old_counts: Vec<AtomicU64>,
+    new_counts: Vec<AtomicU64>,
+}
+
+impl<T: PartialEq> ShadowTable<T> {
+    pub fn new(old: DropTable<T>, new: DropTable<T>) -> Self {
+        let counts = |n: usize| (0..n).map(|_| AtomicU64::new(0)).collect();
+        Self {
+            old_counts: counts(old.len()),
+            new_counts: counts(new.len()),
+            old,
+            new,
+            draws: AtomicU64::new(0),
+            mismatches: AtomicU64::new(0),
+        }
+    }
+
+    /// Draw from both tables on a forked stream, record the pair, and
+    /// return the old table's item.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        self.sample_both(rng).0
+    }
+
+    /// [`sample`](Self::sample), returning `(old, new)`.
+    pub fn sample_both<R: Rng + ?Sized>(&self, rng: &mut R) -> (&T, &T) {
+        let mut a = Fork(rng.next_u64());
+        let mut b = a.clone();
+        let i = self.old.sampler.sample_index(&mut a);
+        let j = self.new.sampler.sample_index(&mut b);
+        let (old, new) = (&self.old.items[i], &self.new.items[j]);
+
+        self.draws.fetch_add(1, Ordering::Relaxed);
+        self.old_counts[i].fetch_add(1, Ordering::Relaxed);
+        self.new_counts[j].fetch_add(1, Ordering::Relaxed);
+        if old != new {
+            self.mismatches.fetch_add(1, Ordering::Relaxed);
+        }
+        (old, new)
+    }
+
+    /// Snapshot of the divergence statistics.
+    pub fn stats(&self) -> ShadowStats {
+        let load = |c: &[AtomicU64]| c.iter().map(|c| c.load(Ordering::Relaxed)).collect();
+        ShadowStats {
+            draws: self.draws.load(Ordering::Relaxed),
+            mismatches: self.mismatches.load(Ordering::Relaxed),
+            old_counts: load(&self.old_counts),
+            new_counts: load(&self.new_counts),
+        }
+    }
+
+    /// Zero the statistics, e.g. after adjusting the new table.
+    pub fn reset(&self) {
+        for c in [&self.draws, &self.mismatches]
+            .into_iter()
+            .chain(&self.old_counts)
+            .chain(&self.new_counts)
+        {
+            c.store(0, Ordering::Relaxed);
+        }
+    }
+
+    pub fn old(&self) -> &DropTable<T> {
+        &self.old
+    }
+
+    pub fn new_table(&self) -> &DropTable<T> {
+        &self.new
+    }
+
+    /// Cut over: keep the new table.
+    pub fn into_new(self) -> DropTable<T> {
+        self.new
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn forked_streams_isolate_the_migration() {
+        let weights = [("a", 5.0), ("b", 3.0), ("c", 2.0)];
+        let same = ShadowTable::new(
+            DropTable::from_pairs(weights).unwrap(),
+            DropTable::from_pairs(weights).unwrap(),
+        );
+        let mut rng = StdRng::seed_from_u64(1399);
+        let mut twin = rng.clone();
+        for _ in 0..1000 {
+            let (old, new) = same.sample_both(&mut rng);
+            assert_eq!(old, new);
+        }
+        // one u64 per draw from the caller's RNG
+        for _ in 0..1000 {
+            twin.next_u64();
+        }
+        assert_eq!(rng.next_u64(), twin.next_u64());
+        assert_eq!(same.stats().mismatches, 0);
+
+        let moved = ShadowTable::new(
+            DropTable::from_pairs(weights).unwrap(),
+            DropTable::from_pairs([("a", 5.0), ("b", 5.0)]).unwrap(),
+        );
+        for _ in 0..10_000 {
+            moved.sample(&mut rng);
+        }
+        let stats = moved.stats();
+        assert_eq!(stats.draws, 10_000);
+        assert_eq!(stats.old_counts.iter().sum::<u64>(), 10_000);
+        assert_eq!(stats.new_counts.len(), 2);
+        // at least the "c" draws, which the new table can't produce
+        assert!(stats.mismatches >= stats.old_counts[2], "{stats:?}");
+        moved.reset();
+        assert_eq!(moved.stats().mismatch_rate(), 0.0);
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::error::ProbError;
+use crate::{WeightedEnum, WeightedSampler};
+
+/// One variant's row in a [`SimulationReport`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationRow<T> {
+    pub item: T,
+    /// Normalized probability from the enum's weights.
+    pub probability: f64,
+    /// `draws * probability`.
+    pub expected: f64,
+    pub observed: u64,
+    /// `(observed - expected) / sqrt(draws * p * (1 - p))`; `0.0` when the
+    /// variance is zero and the counts agree, infinite when they don't.
+    pub z: f64,
+}
+
+/// Expected vs empirical counts for every variant of a [`WeightedEnum`],
+/// as returned by [`simulate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationReport<T> {
+    draws: u64,
+    seed: u64,
+    rows: Vec<SimulationRow<T>>,
+}
+
+impl<T> SimulationReport<T> {
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// One row per variant, in declaration order.
+    pub fn rows(&self) -> &[SimulationRow<T>] {
+        &self.rows
+    }
+
+    /// Largest `|z|` over all variants; a quick "did anything drift" check.
+    pub fn max_abs_z(&self) -> f64 {
+        self.rows.iter().map(|r| r.z.abs()).fold(0.0, f64::max)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for SimulationReport<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<String> = self.rows.iter().map(|r| format!("{:?}", r.item)).collect();
+        let w = names.iter().map(String::len).max().unwrap_or(0).max(4);
+        writeln!(f, "{} draws, seed {}", self.draws, self.seed)?;
+        writeln!(
+            f,
+            "{:<w$} {:>14} {:>12} {:>8}",
+            "item", "expected", "observed", "z"
+        )?;
+        for (name, r) in names.iter().zip(&self.rows) {
+            writeln!(
+                f,
+                "{name:<w$} {:>14.1} {:>12} {:>8.2}",
+                r.expected, r.observed, r.z
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Draw `draws` variants of `E` from a [`StdRng`] seeded with `seed` and
+/// compare the counts against the enum's declared odds.
+///
+/// Counts are kept per index, so `E` needs no `Hash`/`Eq`.
+///
+/// # Errors
+/// Whatever [`WeightedSampler::new`] reports for `E::ENTRIES`.
+///
+/// ```
+/// use droptables::{WeightedEnum, simulate};
+///
+/// #[derive(Copy, Clone, Debug, WeightedEnum)]
+/// enum Coin {
+///     #[odds = "1/2"]
+///     Heads,
+///     #[rest]
+///     Tails,
+/// }
+///
+/// let report = simulate::<Coin>(10_000, 1).unwrap();
+/// assert!(report.max_abs_z() < 5.0);
+/// ```
+pub fn simulate<E>(draws: u64, seed: u64) -> Result<SimulationReport<E>, ProbError>
+where
+    E: WeightedEnum + Copy,
+{
+    let weights: Vec<f32> = E::ENTRIES.iter().map(|&(_, w)| w).collect();
+    let sampler = WeightedSampler::new(&weights)?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut counts = vec![0u64; weights.len()];
+    for _ in 0..draws {
+        counts[sampler.sample_index(&mut rng)] += 1;
+    }
+
+    let total: f64 = weights.iter().map(|&w| f64::from(w)).sum();
+    let n = draws as f64;
+    let rows = E::ENTRIES
+        .iter()
+        .zip(counts)
+        .map(|(&(item, w), observed)| {
+            let probability = f64::from(w) / total;
+            let expected = n * probability;
+            let sd = (n * probability * (1.0 - probability)).sqrt();
+            let diff = observed as f64 - expected;
+            let z = if sd > 0.0 {
+                diff / sd
+            } else if diff.abs() < 0.5 {
+                0.0
+            } else {
+                diff.signum() * f64::INFINITY
+            };
+            SimulationRow {
+                item,
+                probability,
+                expected,
+                observed,
+                z,
+            }
+        })
+        .collect();
+
+    Ok(SimulationReport { draws, seed, rows })
+}
+
+/// `simulate_enum!(Loot, draws, seed)` is shorthand for
+/// [`simulate::<Loot>(draws, seed)`](simulate).
+#[macro_export]
+macro_rules! simulate_enum {
+    ($enum:ty, $draws:expr, $seed:expr $(,)?) => {
+        $crate::simulate::<$enum>($draws, $seed)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::WeightedEnum;
+
+    #[derive(Copy, Clone, Debug, PartialEq, WeightedEnum)]
+    enum Loot {
+        #[odds = "1/10"]
+        Rare,
+        #[rest]
+        Junk,
+    }
+
+    #[test]
+    fn counts_match_odds_and_are_reproducible() {
+        let report = simulate_enum!(Loot, 50_000, 1337).unwrap();
+        assert_eq!(report.rows().len(), 2);
+        assert_eq!(
+            report.rows().iter().map(|r| r.observed).sum::<u64>(),
+            50_000
+        );
+        let rare = report.rows()[0];
+        assert_eq!(rare.item, Loot::Rare);
+        assert!((rare.expected - 5_000.0).abs() < 1e-3);
+        assert!(report.max_abs_z() < 5.0, "{report}");
+        assert_eq!(report, super::simulate::<Loot>(50_000, 1337).unwrap());
+    }
+}
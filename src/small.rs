@@ -0,0 +1,121 @@
+use rand::{Rng, RngCore};
+
+use crate::IndexSampler;
+use crate::error::ProbError;
+use crate::walker::validate_weights;
+
+/// Sampler for tables of at most [`MAX_LEN`](Self::MAX_LEN) entries, such
+/// as the two-outcome "1/128 to roll the rare table" gates that dominate
+/// real loot data.
+///
+/// The cumulative distribution is stored as three 53-bit cut points, and a
+/// draw is one `next_u64()` plus a branchless sum of three comparisons:
+/// no second random number, no bucket lookup, no alias indirection. Unused
+/// cut points sit at `2^53`, which no draw reaches, so two-entry tables pay
+/// for a single effective comparison.
+///
+/// ```
+/// use droptables::{IndexSampler, SmallSampler};
+///
+/// let gate = SmallSampler::new(&[127.0, 1.0]).unwrap();
+/// assert!(gate.sample_index(&mut rand::rng()) < 2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SmallSampler {
+    cuts: [u64; 3],
+    len: u8,
+}
+
+const ONE: u64 = 1 << 53;
+
+impl SmallSampler {
+    /// Most entries a `SmallSampler` holds.
+    pub const MAX_LEN: usize = 4;
+
+    /// Build from non-negative weights.
+    ///
+    /// # Errors
+    /// * Anything [`WeightedSampler::new`](crate::WeightedSampler::new)
+    ///   rejects
+    /// * [`ProbError::TooLarge`] for more than [`MAX_LEN`](Self::MAX_LEN)
+    ///   weights
+    pub fn new(weights: &[f32]) -> Result<Self, ProbError> {
+        if weights.len() > Self::MAX_LEN {
+            return Err(ProbError::TooLarge {
+                len: weights.len(),
+                max: Self::MAX_LEN,
+            });
+        }
+        let sum = validate_weights(weights)?;
+        let mut cuts = [ONE; 3];
+        let mut acc = 0.0f64;
+        for (cut, &w) in cuts.iter_mut().zip(&weights[..weights.len() - 1]) {
+            acc += f64::from(w);
+            *cut = ((acc / sum).min(1.0) * ONE as f64).round() as u64;
+        }
+        Ok(Self {
+            cuts,
+            len: weights.len() as u8,
+        })
+    }
+}
+
+impl IndexSampler for SmallSampler {
+    #[inline]
+    fn len(&self) -> usize {
+        usize::from(self.len)
+    }
+
+    #[inline]
+    fn sample_index<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let u = rng.next_u64() >> 11;
+        usize::from(u >= self.cuts[0])
+            + usize::from(u >= self.cuts[1])
+            + usize::from(u >= self.cuts[2])
+    }
+
+    #[inline]
+    fn sample_index_dyn(&self, rng: &mut dyn RngCore) -> usize {
+        self.sample_index(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn cut_points_follow_the_weights() {
+        let s = SmallSampler::new(&[1.0, 0.0, 2.0, 1.0]).unwrap();
+        let mut rng = StdRng::seed_from_u64(1388);
+        let mut counts = [0usize; 4];
+        for _ in 0..40_000 {
+            counts[s.sample_index(&mut rng)] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        assert!(
+            (counts[2] as f64 / 40_000.0 - 0.5).abs() < 0.01,
+            "{counts:?}"
+        );
+
+        let trailing_zero = SmallSampler::new(&[3.0, 0.0]).unwrap();
+        assert_eq!(trailing_zero.sample_index(&mut rng), 0);
+        assert_eq!(SmallSampler::new(&[1.0]).unwrap().sample_index(&mut rng), 0);
+        assert!(matches!(
+            SmallSampler::new(&[1.0; 5]),
+            Err(ProbError::TooLarge { len: 5, max: 4 })
+        ));
+        assert!(matches!(SmallSampler::new(&[]), Err(ProbError::Empty)));
+    }
+
+    #[test]
+    fn tables_opt_in() {
+        use crate::{DropTable, SamplerKind};
+        let gate = [("rare table", 1.0), ("nothing", 127.0)];
+        let default = DropTable::from_pairs(gate).unwrap();
+        assert_eq!(default.sampler_kind(), SamplerKind::Alias);
+        let small = DropTable::from_pairs_using(gate, SamplerKind::Small).unwrap();
+        assert_eq!(small.sampler_kind(), SamplerKind::Small);
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use rand::Rng;
+
+use crate::error::ProbError;
+use crate::walker::WeightedSampler;
+
+/// A drop table keyed by arbitrary (sparse) IDs.
+///
+/// Stores each item alongside its key, so sampling yields `(key, &item)`
+/// without a parallel `Vec` of IDs on the caller side. Keys can also be
+/// used for direct lookup via [`SparseDropTable::get`].
+#[derive(Debug, Clone)]
+pub struct SparseDropTable<K: Hash + Eq, T> {
+    alias: WeightedSampler,
+    keys: Vec<K>,
+    items: Vec<T>,
+    index: HashMap<K, usize>,
+}
+
+impl<K: Hash + Eq + Clone, T> SparseDropTable<K, T> {
+    /// Build from any `(key, item, weight)` iterator.
+    ///
+    /// # Errors
+    /// * [`ProbError::DuplicateKey`] if the same key appears twice.
+    /// * Anything [`WeightedSampler::new`] rejects.
+    pub fn from_triples<I>(triples: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = (K, T, f32)>,
+    {
+        let mut keys = Vec::new();
+        let mut items = Vec::new();
+        let mut weights = Vec::new();
+        let mut index = HashMap::new();
+        for (i, (k, t, w)) in triples.into_iter().enumerate() {
+            if index.insert(k.clone(), i).is_some() {
+                return Err(ProbError::DuplicateKey { index: i });
+            }
+            keys.push(k);
+            items.push(t);
+            weights.push(w);
+        }
+        let alias = WeightedSampler::new(&weights)?;
+        Ok(Self {
+            alias,
+            keys,
+            items,
+            index,
+        })
+    }
+}
+
+impl<K: Hash + Eq, T> SparseDropTable<K, T> {
+    /// Sample a `(key, &item)` pair.
+    pub fn sample<'a, R: Rng + ?Sized>(&'a self, rng: &mut R) -> (K, &'a T)
+    where
+        K: Copy,
+    {
+        let i = self.alias.sample_index(rng);
+        (self.keys[i], &self.items[i])
+    }
+
+    /// Sample a `(&key, &item)` pair (no `Copy` bound on the key).
+    pub fn sample_ref<'a, R: Rng + ?Sized>(&'a self, rng: &mut R) -> (&'a K, &'a T) {
+        let i = self.alias.sample_index(rng);
+        (&self.keys[i], &self.items[i])
+    }
+
+    /// Look up an item by key.
+    pub fn get(&self, key: &K) -> Option<&T> {
+        self.index.get(key).map(|&i| &self.items[i])
+    }
+
+    /// Whether `key` is present in the table.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// Keys in insertion order.
+    pub fn keys(&self) -> &[K] {
+        &self.keys
+    }
+
+    /// Number of entries in the table.
+    pub fn len(&self) -> usize {
+        self.alias.len()
+    }
+
+    /// Whether the table is empty.
+    pub fn is_empty(&self) -> bool {
+        self.alias.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn samples_keys_with_items() {
+        let table = SparseDropTable::from_triples([
+            (9_000_000_000_000u128, "sword", 1.0),
+            (42u128, "shield", 0.0),
+        ])
+        .unwrap();
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..100 {
+            assert_eq!(table.sample(&mut rng), (9_000_000_000_000, &"sword"));
+        }
+        assert_eq!(table.get(&42), Some(&"shield"));
+    }
+
+    #[test]
+    fn rejects_duplicate_keys() {
+        let err = SparseDropTable::from_triples([(1u32, 'a', 1.0), (1u32, 'b', 1.0)]);
+        assert!(matches!(err, Err(ProbError::DuplicateKey { index: 1 })));
+    }
+}
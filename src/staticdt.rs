@@ -19,11 +19,15 @@ impl<S: IndexSampler, T> StaticDropTable<S, T> {
     }
 
     #[inline]
-    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         self.sampler.len()
     }
 
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.sampler.is_empty()
+    }
+
     /// Borrowed sample (zero clone).
     #[inline]
     pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &'static T {
@@ -41,6 +45,12 @@ impl<S: IndexSampler, T> StaticDropTable<S, T> {
         self.items[i]
     }
 
+    /// The index sampler.
+    #[inline]
+    pub const fn sampler(&self) -> &S {
+        &self.sampler
+    }
+
     /// Access the backing slice.
     #[inline]
     pub const fn items(&self) -> &'static [T] {
@@ -0,0 +1,600 @@
+//! Closed-form statistics over tables, for balancing without simulation.
+
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::DropTable;
+
+/// Exact supports larger than this switch [`convolve_n`] to the
+/// approximation.
+pub const MAX_EXACT_SUPPORT: usize = 100_000;
+
+/// Distribution of the sum of values over `n` independent draws; see
+/// [`convolve_n`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SumDistribution {
+    rolls: usize,
+    mean: f64,
+    variance: f64,
+    skewness: f64,
+    /// Sorted `(sum, probability)` pairs, when computed exactly.
+    exact: Option<Vec<(f64, f64)>>,
+}
+
+/// Moments of one draw: mean, variance and third central moment.
+fn moments(pmf: &[(f64, f64)]) -> (f64, f64, f64) {
+    let mean: f64 = pmf.iter().map(|&(v, p)| v * p).sum();
+    let var = pmf.iter().map(|&(v, p)| p * (v - mean).powi(2)).sum();
+    let m3 = pmf.iter().map(|&(v, p)| p * (v - mean).powi(3)).sum();
+    (mean, var, m3)
+}
+
+/// Sort and merge equal values; drop zero probabilities.
+fn normalize(mut pmf: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    pmf.retain(|&(_, p)| p > 0.0);
+    pmf.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut out: Vec<(f64, f64)> = Vec::with_capacity(pmf.len());
+    for (v, p) in pmf {
+        match out.last_mut() {
+            Some(last) if last.0 == v => last.1 += p,
+            _ => out.push((v, p)),
+        }
+    }
+    out
+}
+
+/// Distribution of `a + b`, or `None` if its support would be too large.
+fn convolve(a: &[(f64, f64)], b: &[(f64, f64)]) -> Option<Vec<(f64, f64)>> {
+    if a.len().saturating_mul(b.len()) > MAX_EXACT_SUPPORT.saturating_mul(10) {
+        return None;
+    }
+    let mut sums: HashMap<u64, f64> = HashMap::new();
+    for &(x, p) in a {
+        for &(y, q) in b {
+            // + 0.0 folds -0.0 into 0.0
+            *sums.entry((x + y + 0.0).to_bits()).or_default() += p * q;
+            if sums.len() > MAX_EXACT_SUPPORT {
+                return None;
+            }
+        }
+    }
+    Some(normalize(
+        sums.into_iter()
+            .map(|(v, p)| (f64::from_bits(v), p))
+            .collect(),
+    ))
+}
+
+/// Shortest operand [`convolve_dense`] hands to the FFT; below it the
+/// direct product is cheaper.
+const FFT_MIN_LEN: usize = 64;
+
+/// FFT results below this are rounding noise (the inputs sum to 1) and
+/// are cleared, so tails rarer than this drop out of FFT-built sums.
+const FFT_NOISE: f64 = 1e-14;
+
+/// Dense convolution of probability arrays indexed by offset: directly
+/// for short operands, by FFT in O(n log n) otherwise.
+fn convolve_dense(a: &[f64], b: &[f64]) -> Vec<f64> {
+    if a.len().min(b.len()) > FFT_MIN_LEN {
+        return convolve_fft(a, b);
+    }
+    let mut out = vec![0.0; a.len() + b.len() - 1];
+    for (i, &p) in a.iter().enumerate() {
+        if p == 0.0 {
+            continue;
+        }
+        for (o, &q) in out[i..].iter_mut().zip(b) {
+            *o += p * q;
+        }
+    }
+    out
+}
+
+/// `a * b` through one forward and one inverse FFT: with `z = a + ib`,
+/// `z * z = a * a - b * b + 2i (a * b)`, so the product is half the
+/// imaginary part.
+fn convolve_fft(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let len = a.len() + b.len() - 1;
+    let size = len.next_power_of_two();
+    let mut z: Vec<(f64, f64)> = (0..size)
+        .map(|i| {
+            (
+                a.get(i).copied().unwrap_or(0.0),
+                b.get(i).copied().unwrap_or(0.0),
+            )
+        })
+        .collect();
+    fft(&mut z, false);
+    for c in &mut z {
+        *c = (c.0 * c.0 - c.1 * c.1, 2.0 * c.0 * c.1);
+    }
+    fft(&mut z, true);
+    z[..len]
+        .iter()
+        .map(|&(_, im)| {
+            let p = im / 2.0;
+            if p < FFT_NOISE { 0.0 } else { p }
+        })
+        .collect()
+}
+
+/// In-place iterative radix-2 FFT over `(re, im)` pairs; `x.len()` must be
+/// a power of two. The inverse includes the `1 / n` scaling.
+fn fft(x: &mut [(f64, f64)], inverse: bool) {
+    let n = x.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            x.swap(i, j);
+        }
+    }
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut half = 1;
+    while half < n {
+        let step = sign * std::f64::consts::PI / half as f64;
+        let twiddles: Vec<(f64, f64)> = (0..half)
+            .map(|k| {
+                let (s, c) = (step * k as f64).sin_cos();
+                (c, s)
+            })
+            .collect();
+        for block in x.chunks_exact_mut(2 * half) {
+            let (lo, hi) = block.split_at_mut(half);
+            for ((u, v), &(wr, wi)) in lo.iter_mut().zip(hi).zip(&twiddles) {
+                let t = (v.0 * wr - v.1 * wi, v.0 * wi + v.1 * wr);
+                *v = (u.0 - t.0, u.1 - t.1);
+                *u = (u.0 + t.0, u.1 + t.1);
+            }
+        }
+        half *= 2;
+    }
+    if inverse {
+        let scale = 1.0 / n as f64;
+        for c in x {
+            *c = (c.0 * scale, c.1 * scale);
+        }
+    }
+}
+
+/// `n`-fold convolution of integer values spanning `lo..=hi`, as offsets
+/// from `n * lo`, by repeated doubling.
+fn convolve_pow_dense(pmf: &[(f64, f64)], lo: f64, hi: f64, mut n: usize) -> Vec<(f64, f64)> {
+    let mut base = vec![0.0; (hi - lo) as usize + 1];
+    for &(v, p) in pmf {
+        base[(v - lo) as usize] += p;
+    }
+    let start = lo * n as f64;
+    let mut acc = vec![1.0];
+    while n > 0 {
+        if n & 1 == 1 {
+            acc = convolve_dense(&acc, &base);
+        }
+        n >>= 1;
+        if n > 0 {
+            base = convolve_dense(&base, &base);
+        }
+    }
+    acc.into_iter()
+        .enumerate()
+        .filter(|&(_, p)| p > 0.0)
+        .map(|(i, p)| (start + i as f64, p))
+        .collect()
+}
+
+/// `n`-fold convolution by repeated doubling.
+fn convolve_pow(pmf: &[(f64, f64)], mut n: usize) -> Option<Vec<(f64, f64)>> {
+    let (lo, hi) = (pmf[0].0, pmf[pmf.len() - 1].0);
+    let integral = pmf.iter().all(|&(v, _)| v.fract() == 0.0 && v.abs() < 1e15);
+    if integral && (hi - lo) * (n as f64) < MAX_EXACT_SUPPORT as f64 {
+        return Some(convolve_pow_dense(pmf, lo, hi, n));
+    }
+    let mut acc = vec![(0.0, 1.0)];
+    let mut base = pmf.to_vec();
+    while n > 0 {
+        if n & 1 == 1 {
+            acc = convolve(&acc, &base)?;
+        }
+        n >>= 1;
+        if n > 0 {
+            base = convolve(&base, &base)?;
+        }
+    }
+    Some(acc)
+}
+
+/// Distribution of the total of `value(item)` over `n` independent draws
+/// from `table`, such as gold per `n` kills.
+///
+/// The sum is computed exactly by convolution while its support stays under
+/// [`MAX_EXACT_SUPPORT`] distinct values. That covers integer values over
+/// many rolls (the support grows linearly, and the convolution is a dense
+/// array product, by FFT once the arrays are long) but not arbitrary reals
+/// (it grows combinatorially). FFT rounding clears probabilities below
+/// about `1e-14`. Beyond that support, [`quantile`](SumDistribution::quantile)
+/// uses a normal approximation with a Cornish–Fisher skew correction; the
+/// mean and variance are always exact.
+///
+/// ```
+/// use droptables::DropTable;
+/// use droptables::stats::convolve_n;
+///
+/// let goblin = DropTable::from_pairs([(0u32, 50.0), (10, 45.0), (500, 5.0)]).unwrap();
+/// let hour = convolve_n(&goblin, |&gold| f64::from(gold), 120);
+/// assert!(hour.is_exact());
+/// assert_eq!(hour.mean().round(), 3540.0);
+/// let (p5, p95) = (hour.quantile(0.05), hour.quantile(0.95));
+/// assert!(p5 < hour.mean() && hour.mean() < p95);
+/// ```
+pub fn convolve_n<T, F>(table: &DropTable<T>, value: F, n: usize) -> SumDistribution
+where
+    F: Fn(&T) -> f64,
+{
+    let total: f64 = table.weights().iter().map(|&w| f64::from(w)).sum();
+    let pmf = normalize(
+        table
+            .iter()
+            .map(|(item, w)| (value(item), f64::from(w) / total))
+            .collect(),
+    );
+    let (mean, var, m3) = moments(&pmf);
+    let k = n as f64;
+    let skewness = if var > 0.0 && n > 0 {
+        m3 / (var.powf(1.5) * k.sqrt())
+    } else {
+        0.0
+    };
+    SumDistribution {
+        rolls: n,
+        mean: mean * k,
+        variance: var * k,
+        skewness,
+        exact: convolve_pow(&pmf, n),
+    }
+}
+
+/// Inverse of the standard normal CDF (Acklam's rational approximation,
+/// relative error below 1.2e-9).
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const LOW: f64 = 0.024_25;
+    let tail = |q: f64| {
+        let q = (-2.0 * q.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < LOW {
+        tail(p)
+    } else if p > 1.0 - LOW {
+        -tail(1.0 - p)
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+impl SumDistribution {
+    pub fn rolls(&self) -> usize {
+        self.rolls
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn variance(&self) -> f64 {
+        self.variance
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance.sqrt()
+    }
+
+    /// Whether [`quantile`](Self::quantile) and [`cdf`](Self::cdf) are exact.
+    pub fn is_exact(&self) -> bool {
+        self.exact.is_some()
+    }
+
+    /// The exact `(sum, probability)` pairs in increasing order of sum, if
+    /// computed.
+    pub fn support(&self) -> Option<&[(f64, f64)]> {
+        self.exact.as_deref()
+    }
+
+    /// Smallest sum `s` with `P(sum <= s) >= p`; approximate (and not
+    /// necessarily an attainable sum) when not [`is_exact`](Self::is_exact).
+    ///
+    /// # Panics
+    /// If `p` is not in `[0, 1]`.
+    pub fn quantile(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p), "quantile {p} is outside [0, 1]");
+        if let Some(pmf) = &self.exact {
+            let mut acc = 0.0;
+            for &(v, q) in pmf {
+                acc += q;
+                // tolerate rounding in the running sum
+                if acc >= p - 1e-12 {
+                    return v;
+                }
+            }
+            return pmf.last().map_or(0.0, |&(v, _)| v);
+        }
+        if p == 0.0 || p == 1.0 {
+            return if p == 0.0 {
+                f64::NEG_INFINITY
+            } else {
+                f64::INFINITY
+            };
+        }
+        let z = normal_quantile(p);
+        let z = z + (z * z - 1.0) * self.skewness / 6.0;
+        self.mean + z * self.std_dev()
+    }
+
+    /// `P(sum <= x)`; exact only when [`is_exact`](Self::is_exact), and
+    /// `None` otherwise.
+    pub fn cdf(&self, x: f64) -> Option<f64> {
+        let pmf = self.exact.as_ref()?;
+        Some(
+            pmf.iter()
+                .take_while(|&&(v, _)| v <= x)
+                .map(|&(_, p)| p)
+                .sum(),
+        )
+    }
+}
+
+/// How many kills it takes to see a drop, e.g. "median kills for the pet";
+/// see [`kills_until`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeometricSummary {
+    /// Chance of the drop per kill.
+    pub p: f64,
+    /// Expected kills, `1 / p`.
+    pub mean: f64,
+    /// Kills by which half of players have the drop.
+    pub median: u64,
+    pub p90: u64,
+    pub p99: u64,
+}
+
+impl GeometricSummary {
+    /// Summary for a drop with chance `p` per kill. A `p` of zero gives an
+    /// infinite mean and `u64::MAX` quantiles.
+    pub fn from_probability(p: f64) -> Self {
+        let mut summary = Self {
+            p,
+            mean: 1.0 / p,
+            median: 0,
+            p90: 0,
+            p99: 0,
+        };
+        summary.median = summary.quantile(0.5);
+        summary.p90 = summary.quantile(0.9);
+        summary.p99 = summary.quantile(0.99);
+        summary
+    }
+
+    /// Fewest kills by which a fraction `q` of players have the drop:
+    /// the smallest `k` with `1 - (1 - p)^k >= q`.
+    pub fn quantile(&self, q: f64) -> u64 {
+        if self.p <= 0.0 {
+            return u64::MAX;
+        }
+        // ln_1p keeps 1/5000-style rates accurate
+        let k = ((-q).ln_1p() / (-self.p).ln_1p()).ceil();
+        if k.is_nan() { 1 } else { (k as u64).max(1) }
+    }
+
+    /// Chance of having the drop within `kills` kills.
+    pub fn chance_within(&self, kills: u64) -> f64 {
+        1.0 - (1.0 - self.p).powf(kills as f64)
+    }
+}
+
+/// Kills until the first drop of `table.items()[index]`, one roll per kill.
+/// The count is geometric, so every figure is exact.
+///
+/// ```
+/// use droptables::DropTable;
+/// use droptables::stats::kills_until;
+///
+/// let boss = DropTable::from_pairs([("bones", 127.0), ("pet", 1.0)]).unwrap();
+/// let pet = kills_until(&boss, 1);
+/// assert_eq!(pet.mean, 128.0);
+/// assert_eq!((pet.median, pet.p90, pet.p99), (89, 294, 588));
+/// ```
+///
+/// # Panics
+/// If `index` is out of bounds.
+pub fn kills_until<T>(table: &DropTable<T>, index: usize) -> GeometricSummary {
+    let total: f64 = table.weights().iter().map(|&w| f64::from(w)).sum();
+    GeometricSummary::from_probability(f64::from(table.weights()[index]) / total)
+}
+
+/// Kills until the first drop, by simulation, for setups with no closed
+/// form: nested tables, several rolls per kill, pity timers. `kill` runs
+/// one kill and reports whether the drop came.
+///
+/// Each of the `trials` runs stops at `max_kills`, so an unreachable drop
+/// can't loop forever; a capped run counts as `max_kills`. The summary's
+/// quantiles and mean are those of the simulated counts, and its `p` is
+/// `1 / mean`, the per-kill rate of a plain table with the same mean.
+///
+/// ```
+/// use droptables::{NestedEntry, NestedTable};
+/// use droptables::stats::simulate_kills_until;
+///
+/// let rdt = NestedTable::new("rdt", [(NestedEntry::Item("shield half"), 1.0), (NestedEntry::Item("law rune"), 9.0)]).unwrap();
+/// let main = NestedTable::new("dragon", [(NestedEntry::Item("bones"), 15.0), (rdt.into(), 1.0)]).unwrap();
+/// let mut rng = rand::rng();
+/// let half = simulate_kills_until(&mut rng, 2000, 100_000, |rng| *main.sample(rng) == "shield half");
+/// assert!(half.median > 50 && half.median < 160);
+/// ```
+///
+/// # Panics
+/// If `trials` is zero.
+pub fn simulate_kills_until<R, F>(
+    rng: &mut R,
+    trials: usize,
+    max_kills: u64,
+    mut kill: F,
+) -> GeometricSummary
+where
+    R: Rng + ?Sized,
+    F: FnMut(&mut R) -> bool,
+{
+    assert!(trials > 0, "no trials to summarize");
+    let mut counts: Vec<u64> = (0..trials)
+        .map(|_| {
+            let mut kills = 1;
+            while kills < max_kills && !kill(rng) {
+                kills += 1;
+            }
+            kills
+        })
+        .collect();
+    counts.sort_unstable();
+    let at = |q: f64| counts[((q * trials as f64).ceil() as usize).clamp(1, trials) - 1];
+    let mean = counts.iter().map(|&k| k as f64).sum::<f64>() / trials as f64;
+    GeometricSummary {
+        p: 1.0 / mean,
+        mean,
+        median: at(0.5),
+        p90: at(0.9),
+        p99: at(0.99),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn exact_sums_match_hand_counts() {
+        // two fair coins worth 0 or 1
+        let coin = DropTable::from_pairs([(0, 1.0), (1, 1.0)]).unwrap();
+        let two = convolve_n(&coin, |&v| f64::from(v), 2);
+        assert_eq!(
+            two.support().unwrap(),
+            [(0.0, 0.25), (1.0, 0.5), (2.0, 0.25)]
+        );
+        assert_eq!(two.quantile(0.5), 1.0);
+        assert_eq!(two.cdf(0.5), Some(0.25));
+        assert_eq!(convolve_n(&coin, |&v| f64::from(v), 0).quantile(1.0), 0.0);
+
+        let d6 = DropTable::from_pairs((1..=6).map(|v| (v, 1.0))).unwrap();
+        let three = convolve_n(&d6, |&v| f64::from(v), 3);
+        let p10 = three
+            .support()
+            .unwrap()
+            .iter()
+            .find(|e| e.0 == 10.0)
+            .unwrap()
+            .1;
+        assert!((p10 - 27.0 / 216.0).abs() < 1e-12);
+        assert!((three.variance() - 3.0 * 35.0 / 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fft_matches_the_direct_product() {
+        let mut rng = StdRng::seed_from_u64(1381);
+        let mut pmf = |n: usize| {
+            let raw: Vec<f64> = (0..n).map(|_| rng.random::<f64>()).collect();
+            let total: f64 = raw.iter().sum();
+            raw.into_iter().map(|p| p / total).collect::<Vec<_>>()
+        };
+        let (a, b) = (pmf(700), pmf(300));
+        let mut direct = vec![0.0; a.len() + b.len() - 1];
+        for (i, &p) in a.iter().enumerate() {
+            for (j, &q) in b.iter().enumerate() {
+                direct[i + j] += p * q;
+            }
+        }
+        let fast = convolve_dense(&a, &b);
+        assert_eq!(fast.len(), direct.len());
+        for (x, y) in fast.iter().zip(&direct) {
+            assert!((x - y).abs() < 1e-14, "{x} vs {y}");
+        }
+
+        // 400 rolls of 0..=250 gold: a 100k-wide support, exact and quick
+        let gold = DropTable::from_pairs((0..=250).map(|v| (v, 1.0))).unwrap();
+        let sum = convolve_n(&gold, |&v| f64::from(v), 399);
+        let support = sum.support().unwrap();
+        let total: f64 = support.iter().map(|e| e.1).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        let mean: f64 = support.iter().map(|&(v, p)| v * p).sum();
+        assert!((mean - sum.mean()).abs() < 1e-6 * sum.mean());
+    }
+
+    #[test]
+    fn kill_counts_match_the_geometric_law() {
+        let boss = DropTable::from_pairs([("bones", 127.0), ("pet", 1.0)]).unwrap();
+        let exact = kills_until(&boss, 1);
+        assert_eq!(exact.median, 89);
+        assert!((exact.chance_within(89) - 0.5).abs() < 0.01);
+        assert_eq!(kills_until(&boss, 0).median, 1);
+        assert_eq!(GeometricSummary::from_probability(1.0).p99, 1);
+        assert_eq!(GeometricSummary::from_probability(0.0).median, u64::MAX);
+
+        let mut rng = StdRng::seed_from_u64(1400);
+        let sim =
+            simulate_kills_until(&mut rng, 20_000, u64::MAX, |rng| *boss.sample(rng) == "pet");
+        assert!((sim.mean - exact.mean).abs() < 4.0, "{sim:?}");
+        assert!(sim.median.abs_diff(exact.median) <= 4, "{sim:?}");
+        assert!(sim.p90.abs_diff(exact.p90) <= 12, "{sim:?}");
+
+        let capped = simulate_kills_until(&mut rng, 10, 7, |_| false);
+        assert_eq!((capped.median, capped.mean), (7, 7.0));
+    }
+
+    #[test]
+    fn large_real_valued_sums_are_approximated() {
+        let t =
+            DropTable::from_pairs([0.1, 0.37, 2.9, 0.011, 1.7, 0.23, 5.3, 0.61].map(|v| (v, 1.0)))
+                .unwrap();
+        let big = convolve_n(&t, |&v| v, 400);
+        assert!(!big.is_exact());
+        assert!((big.quantile(0.5) - big.mean()).abs() < 0.1 * big.std_dev());
+        assert!((normal_quantile(0.975) - 1.959_963_985).abs() < 1e-8);
+        assert!(big.quantile(0.99) > big.quantile(0.01));
+    }
+}
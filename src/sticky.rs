@@ -0,0 +1,149 @@
+use std::hash::{Hash, Hasher};
+
+use crate::DropTable;
+use crate::version::Fnv1a;
+
+/// Deterministic, weighted assignment of keys to items, for experiment
+/// bucketing and traffic splits.
+///
+/// Uses weighted rendezvous hashing: every item scores the key with
+/// `-w / ln(u)`, where `u` is a hash of the key and the *item* (not its
+/// index) mapped into `(0, 1)`, and the highest score wins. Over many keys
+/// each item gets its weight's share, and when weights change only the
+/// keys that must move do:
+///
+/// - raising an item's weight only moves keys *to* that item;
+/// - lowering it only moves keys *away* from it;
+/// - adding or removing an item only moves keys to or from that item;
+/// - reordering items moves nothing.
+///
+/// Hashing uses a fixed algorithm that writes integers as little-endian
+/// bytes and `usize` as 64 bits, so assignments are stable across
+/// processes, platforms and releases of this crate as long as the key's and
+/// items' `Hash` impls are.
+///
+/// ```
+/// use droptables::{DropTable, StickyTable};
+///
+/// let split = StickyTable::new(DropTable::from_pairs([("control", 90.0), ("variant", 10.0)]).unwrap());
+/// let arm = split.assign(&"user-1234");
+/// assert_eq!(arm, split.assign(&"user-1234"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct StickyTable<T> {
+    table: DropTable<T>,
+    item_hashes: Vec<u64>,
+}
+
+fn fnv<H: Hash + ?Sized>(value: &H) -> u64 {
+    let mut h = Fnv1a::new();
+    value.hash(&mut h);
+    h.finish()
+}
+
+/// SplitMix64 finalizer, to spread FNV's weak low bits.
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl<T: Hash> StickyTable<T> {
+    pub fn new(table: DropTable<T>) -> Self {
+        let item_hashes = table.items().iter().map(|item| fnv(item)).collect();
+        Self { table, item_hashes }
+    }
+}
+
+impl<T> StickyTable<T> {
+    /// Index of the item `key` is assigned to.
+    pub fn assign_index<K: Hash + ?Sized>(&self, key: &K) -> usize {
+        let key = fnv(key);
+        let mut best = (f64::NEG_INFINITY, 0);
+        for (i, (&w, &item)) in self
+            .table
+            .weights()
+            .iter()
+            .zip(&self.item_hashes)
+            .enumerate()
+        {
+            if w <= 0.0 {
+                continue;
+            }
+            let bits = mix(key ^ mix(item));
+            // top 53 bits, centered: u in (0, 1)
+            let u = ((bits >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+            let score = -f64::from(w) / u.ln();
+            if score > best.0 {
+                best = (score, i);
+            }
+        }
+        best.1
+    }
+
+    /// The item `key` is assigned to; the same key always gets the same
+    /// item for the same weights.
+    pub fn assign<K: Hash + ?Sized>(&self, key: &K) -> &T {
+        &self.table.items()[self.assign_index(key)]
+    }
+
+    pub fn table(&self) -> &DropTable<T> {
+        &self.table
+    }
+
+    pub fn into_inner(self) -> DropTable<T> {
+        self.table
+    }
+}
+
+impl<T: Hash> DropTable<T> {
+    /// Use this table for sticky key assignment; see [`StickyTable`].
+    pub fn sticky(self) -> StickyTable<T> {
+        StickyTable::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_follow_weights_and_changes_move_few_keys() {
+        let before = DropTable::from_pairs([("a", 1.0), ("b", 1.0), ("off", 0.0), ("c", 2.0)])
+            .unwrap()
+            .sticky();
+        let after = DropTable::from_pairs([("c", 2.0), ("b", 2.0), ("a", 1.0)])
+            .unwrap()
+            .sticky();
+        let keys = 40_000u32;
+        let (mut b_share, mut moved) = (0, 0);
+        for k in 0..keys {
+            let (x, y) = (before.assign(&k), after.assign(&k));
+            assert_ne!(*x, "off");
+            b_share += usize::from(*x == "b");
+            if x != y {
+                // b's weight went up, so keys only ever move to b
+                assert_eq!(*y, "b", "key {k} moved {x} -> {y}");
+                moved += 1;
+            }
+        }
+        assert!((b_share as f64 / keys as f64 - 0.25).abs() < 0.01);
+        // b goes from 1/4 to 2/5 of the keys
+        assert!((moved as f64 / keys as f64 - 0.15).abs() < 0.01, "{moved}");
+        // pinned: stable across releases
+        let pinned: Vec<usize> = ["user-1", "user-2", "user-3", "user-4"]
+            .iter()
+            .map(|k| before.assign_index(k))
+            .collect();
+        assert_eq!(pinned, [0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn integers_hash_the_same_on_every_platform() {
+        assert_eq!(fnv(&7usize), fnv(&7u64));
+        assert_eq!(fnv(&-1isize), fnv(&-1i64));
+        let mut bytes = Fnv1a::new();
+        bytes.write(&0x0102_0304u32.to_le_bytes());
+        assert_eq!(fnv(&0x0102_0304u32), bytes.finish());
+    }
+}
@@ -0,0 +1,120 @@
+//! Weight-vector generators for property testing (feature `proptest-support`).
+//!
+//! Each generator is driven by a caller-supplied [`Rng`], so it plugs into
+//! any harness: seed it from a proptest/quickcheck input, from a fuzzer's
+//! byte stream via a seedable RNG, or just loop over seeds.
+//!
+//! Pair these with [`WeightedSampler::verify_invariants`](crate::WeightedSampler::verify_invariants)
+//! to check your own wrappers.
+//!
+//! The feature doesn't pull in `proptest` itself, so these aren't
+//! `Strategy` values; in a proptest suite, map a seed onto one:
+//!
+//! ```ignore
+//! let tables = any::<u64>().prop_map(|seed| valid_table(&mut StdRng::seed_from_u64(seed), 32));
+//! ```
+
+use rand::Rng;
+
+use crate::DropTable;
+
+/// How an [`invalid_weights`] vector is broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidKind {
+    Empty,
+    Negative,
+    AllZero,
+    NonFinite,
+}
+
+/// A non-empty weight vector that [`WeightedSampler::new`](crate::WeightedSampler::new)
+/// accepts, with length in `1..=max_len`.
+///
+/// Mixes zeros, tiny and huge magnitudes so edge buckets get exercised.
+///
+/// # Panics
+/// If `max_len == 0`.
+pub fn valid_weights<R: Rng + ?Sized>(rng: &mut R, max_len: usize) -> Vec<f32> {
+    assert!(max_len > 0, "max_len must be positive");
+    let n = rng.random_range(1..=max_len);
+    let mut weights: Vec<f32> = (0..n)
+        .map(|_| match rng.random_range(0..4) {
+            0 => 0.0,
+            1 => rng.random::<f32>() * 1e-6,
+            2 => rng.random::<f32>() * 1e6,
+            _ => rng.random::<f32>(),
+        })
+        .collect();
+    // guarantee a positive total
+    let i = rng.random_range(0..n);
+    weights[i] += 1.0;
+    weights
+}
+
+/// A [`DropTable`] over indices `0..n` with [`valid_weights`], so
+/// `table.items()[i] == i`.
+///
+/// # Panics
+/// If `max_len == 0`.
+pub fn valid_table<R: Rng + ?Sized>(rng: &mut R, max_len: usize) -> DropTable<usize> {
+    let weights = valid_weights(rng, max_len);
+    DropTable::from_pairs(weights.into_iter().enumerate()).expect("valid_weights always builds")
+}
+
+/// A weight vector that [`WeightedSampler::new`](crate::WeightedSampler::new)
+/// must reject, together with the reason it is invalid.
+pub fn invalid_weights<R: Rng + ?Sized>(rng: &mut R, max_len: usize) -> (Vec<f32>, InvalidKind) {
+    if max_len == 0 {
+        return (Vec::new(), InvalidKind::Empty);
+    }
+    let kind = match rng.random_range(0..4) {
+        0 => InvalidKind::Empty,
+        1 => InvalidKind::Negative,
+        2 => InvalidKind::AllZero,
+        _ => InvalidKind::NonFinite,
+    };
+    let weights = match kind {
+        InvalidKind::Empty => Vec::new(),
+        InvalidKind::AllZero => vec![0.0; rng.random_range(1..=max_len)],
+        InvalidKind::Negative => {
+            let mut w = valid_weights(rng, max_len);
+            let i = rng.random_range(0..w.len());
+            w[i] = -(rng.random::<f32>() + f32::MIN_POSITIVE);
+            w
+        }
+        InvalidKind::NonFinite => {
+            let mut w = valid_weights(rng, max_len);
+            let i = rng.random_range(0..w.len());
+            w[i] = if rng.random() {
+                f32::NAN
+            } else {
+                f32::INFINITY
+            };
+            w
+        }
+    };
+    (weights, kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WeightedSampler;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn generators_agree_with_constructor() {
+        let mut rng = StdRng::seed_from_u64(9);
+        for _ in 0..500 {
+            let w = valid_weights(&mut rng, 32);
+            let alias = WeightedSampler::new(&w).unwrap();
+            assert_eq!(alias.verify_invariants(), Ok(()));
+
+            let (w, kind) = invalid_weights(&mut rng, 32);
+            assert!(WeightedSampler::new(&w).is_err(), "{kind:?}: {w:?}");
+
+            let table = valid_table(&mut rng, 32);
+            assert!(table.items().iter().enumerate().all(|(i, &v)| i == v));
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use rand::Rng;
+
+use crate::error::ProbError;
+use crate::walker::WeightedSampler;
+
+/// Table over **borrowed** items: a `&'a [T]` plus one weight per item.
+///
+/// Like [`StaticDropTable`](crate::StaticDropTable) but for any lifetime, so
+/// large item structs living in an arena or a loaded asset bundle can be
+/// sampled without cloning them into a [`DropTable`](crate::DropTable).
+/// Samples borrow from the slice, not from the table.
+#[derive(Debug, Clone)]
+pub struct DropTableRef<'a, T> {
+    alias: WeightedSampler,
+    items: &'a [T],
+    weights: Vec<f32>,
+}
+
+impl<'a, T> DropTableRef<'a, T> {
+    /// Pair `items[i]` with `weights[i]`.
+    ///
+    /// # Errors
+    /// [`ProbError::Shape`] if the lengths differ, otherwise as
+    /// [`WeightedSampler::new`].
+    pub fn new(items: &'a [T], weights: &[f32]) -> Result<Self, ProbError> {
+        if items.len() != weights.len() {
+            return Err(ProbError::Shape {
+                expected: items.len(),
+                found: weights.len(),
+            });
+        }
+        Ok(Self {
+            alias: WeightedSampler::new(weights)?,
+            items,
+            weights: weights.to_vec(),
+        })
+    }
+
+    /// Build with weights computed from each item.
+    ///
+    /// # Errors
+    /// As [`WeightedSampler::new`].
+    pub fn from_fn<F: FnMut(&T) -> f32>(items: &'a [T], weight: F) -> Result<Self, ProbError> {
+        let weights: Vec<f32> = items.iter().map(weight).collect();
+        Self::new(items, &weights)
+    }
+
+    /// Sample a reference that outlives the table itself.
+    #[inline]
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &'a T {
+        &self.items[self.alias.sample_index(rng)]
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The borrowed items, in the order given.
+    pub fn items(&self) -> &'a [T] {
+        self.items
+    }
+
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct ItemDef {
+        name: String,
+        weight: f32,
+    }
+
+    #[test]
+    fn borrows_outlive_table() {
+        let arena = vec![
+            ItemDef {
+                name: "sword".into(),
+                weight: 0.0,
+            },
+            ItemDef {
+                name: "shield".into(),
+                weight: 2.0,
+            },
+        ];
+        let mut rng = rand::rng();
+        let got = {
+            let table = DropTableRef::from_fn(&arena, |d| d.weight).unwrap();
+            table.sample(&mut rng)
+        };
+        assert_eq!(got.name, "shield");
+
+        assert_eq!(
+            DropTableRef::new(&arena, &[1.0]).unwrap_err(),
+            ProbError::Shape {
+                expected: 2,
+                found: 1
+            }
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Sampling and build metrics (feature `telemetry`).
+//!
+//! The crate doesn't depend on a metrics library. Install a [`Recorder`]
+//! once at startup that forwards to whatever your service uses (`metrics`
+//! counters, `tracing` events, a StatsD socket); every
+//! [`InstrumentedTable`] then reports through it:
+//!
+//! - [`SAMPLES`]: counter, incremented per draw.
+//! - [`BUILD_SECONDS`]: histogram, the time taken to build the table.
+//! - [`BUILD_SPAN`]: a span around each build, through
+//!   [`Recorder::in_span`]; a `tracing` bridge opens an
+//!   `info_span!("droptables.build", table)` there and runs the build in it.
+//!
+//! All carry the table's name as a label. Without a recorder installed,
+//! reporting is a single atomic load.
+//!
+//! ```
+//! use droptables::telemetry::{self, InstrumentedTable, Recorder};
+//!
+//! struct Log;
+//! impl Recorder for Log {
+//!     fn increment_counter(&self, name: &'static str, table: &str, value: u64) {
+//!         println!("{name}{{table={table}}} += {value}");
+//!     }
+//!     fn record_histogram(&self, name: &'static str, table: &str, value: f64) {
+//!         println!("{name}{{table={table}}} <- {value}");
+//!     }
+//! }
+//!
+//! telemetry::set_recorder(&Log);
+//! let split = InstrumentedTable::build("checkout", [("old", 95.0), ("new", 5.0)]).unwrap();
+//! let arm = split.sample(&mut rand::rng());
+//! ```
+
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use rand::Rng;
+
+use crate::DropTable;
+use crate::error::ProbError;
+
+/// Counter: draws from an [`InstrumentedTable`].
+pub const SAMPLES: &str = "droptables.samples";
+/// Histogram: seconds spent building an [`InstrumentedTable`].
+pub const BUILD_SECONDS: &str = "droptables.build_seconds";
+/// Span: building an [`InstrumentedTable`].
+pub const BUILD_SPAN: &str = "droptables.build";
+
+/// Where metrics go; see the [module docs](self).
+pub trait Recorder: Send + Sync {
+    fn increment_counter(&self, name: &'static str, table: &str, value: u64);
+    fn record_histogram(&self, name: &'static str, table: &str, value: f64);
+
+    /// Run `f` inside the span `name`, labelled with `table`. The default
+    /// just runs `f`.
+    fn in_span(&self, name: &'static str, table: &str, f: &mut dyn FnMut()) {
+        let _ = (name, table);
+        f();
+    }
+}
+
+static RECORDER: OnceLock<&'static dyn Recorder> = OnceLock::new();
+
+/// Install the process-wide recorder. Returns `false` (and changes nothing)
+/// if one was already installed.
+pub fn set_recorder(recorder: &'static dyn Recorder) -> bool {
+    RECORDER.set(recorder).is_ok()
+}
+
+fn recorder() -> Option<&'static dyn Recorder> {
+    RECORDER.get().copied()
+}
+
+/// A named [`DropTable`] that reports to the installed [`Recorder`].
+#[derive(Debug, Clone)]
+pub struct InstrumentedTable<T> {
+    name: String,
+    table: DropTable<T>,
+}
+
+impl<T> InstrumentedTable<T> {
+    /// Build from `(item, weight)` pairs like [`DropTable::from_pairs`],
+    /// inside a [`BUILD_SPAN`] and recording the build time.
+    ///
+    /// # Errors
+    /// Whatever [`DropTable::from_pairs`] reports.
+    pub fn build<I>(name: impl Into<String>, pairs: I) -> Result<Self, ProbError>
+    where
+        I: IntoIterator<Item = (T, f32)>,
+    {
+        let name = name.into();
+        let Some(r) = recorder() else {
+            let table = DropTable::from_pairs(pairs)?;
+            return Ok(Self { name, table });
+        };
+        let mut pairs = Some(pairs);
+        let mut built = None;
+        r.in_span(BUILD_SPAN, &name, &mut || {
+            if let Some(pairs) = pairs.take() {
+                let start = Instant::now();
+                built = Some((DropTable::from_pairs(pairs), start.elapsed()));
+            }
+        });
+        // a recorder that never ran the closure still gets a table built
+        let (table, elapsed) = match built {
+            Some(b) => b,
+            None => {
+                let start = Instant::now();
+                let pairs = pairs.take().expect("closure not run, pairs unused");
+                (DropTable::from_pairs(pairs), start.elapsed())
+            }
+        };
+        let table = table?;
+        r.record_histogram(BUILD_SECONDS, &name, elapsed.as_secs_f64());
+        Ok(Self { name, table })
+    }
+
+    /// Wrap an already built table; no build time is recorded.
+    pub fn new(name: impl Into<String>, table: DropTable<T>) -> Self {
+        Self {
+            name: name.into(),
+            table,
+        }
+    }
+
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &T {
+        if let Some(r) = recorder() {
+            r.increment_counter(SAMPLES, &self.name, 1);
+        }
+        self.table.sample(rng)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn table(&self) -> &DropTable<T> {
+        &self.table
+    }
+
+    pub fn into_inner(self) -> DropTable<T> {
+        self.table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Collect(Mutex<Vec<(&'static str, String, f64)>>);
+
+    impl Recorder for Collect {
+        fn increment_counter(&self, name: &'static str, table: &str, value: u64) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((name, table.to_string(), value as f64));
+        }
+        fn record_histogram(&self, name: &'static str, table: &str, value: f64) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((name, table.to_string(), value));
+        }
+        fn in_span(&self, name: &'static str, table: &str, f: &mut dyn FnMut()) {
+            let record = |event: &'static str| {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((event, format!("{name}:{table}"), 0.0))
+            };
+            record("enter");
+            f();
+            record("exit");
+        }
+    }
+
+    #[test]
+    fn reports_builds_and_samples_with_table_label() {
+        static COLLECT: OnceLock<Collect> = OnceLock::new();
+        let collect = COLLECT.get_or_init(Collect::default);
+        assert!(set_recorder(collect));
+        assert!(!set_recorder(collect));
+
+        let table = InstrumentedTable::build("ab-test", [("a", 1.0), ("b", 1.0)]).unwrap();
+        for _ in 0..3 {
+            table.sample(&mut rand::rng());
+        }
+        let seen = collect.0.lock().unwrap();
+        let span: Vec<_> = seen
+            .iter()
+            .filter(|(_, t, _)| t == "droptables.build:ab-test")
+            .map(|(event, _, _)| *event)
+            .collect();
+        assert_eq!(span, ["enter", "exit"]);
+        let mine: Vec<_> = seen.iter().filter(|(_, t, _)| t == "ab-test").collect();
+        assert_eq!(mine[0].0, BUILD_SECONDS);
+        assert!(mine[0].2 >= 0.0);
+        assert_eq!(mine[1..].len(), 3);
+        assert!(mine[1..].iter().all(|(n, _, v)| *n == SAMPLES && *v == 1.0));
+    }
+}
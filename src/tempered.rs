@@ -0,0 +1,148 @@
+//! Temperature-controlled sampling.
+//!
+//! Temperature `T` reweights each entry to `w^(1/T)` (a softmax over
+//! `ln w / T`): `T = 1` is the original distribution, `T → 0` approaches
+//! argmax and `T → ∞` approaches uniform over the non-zero entries.
+//! Zero-weight entries stay impossible at every temperature.
+
+use rand::Rng;
+
+use crate::DropTable;
+use crate::error::ProbError;
+use crate::walker::WeightedSampler;
+
+/// Reweight `weights` for temperature `t`.
+///
+/// `t <= 0` selects only the maximal entries; `t = ∞` makes every non-zero
+/// entry equally likely.
+fn temper(weights: &[f32], t: f32) -> Vec<f32> {
+    let max = weights.iter().copied().fold(0.0f32, f32::max);
+    if t <= 0.0 {
+        return weights
+            .iter()
+            .map(|&w| if w == max && w > 0.0 { 1.0 } else { 0.0 })
+            .collect();
+    }
+    let inv_t = 1.0 / t as f64;
+    let ln_max = (max as f64).ln();
+    weights
+        .iter()
+        .map(|&w| {
+            if w > 0.0 {
+                // shift by the max so the largest entry is exactly 1
+                (((w as f64).ln() - ln_max) * inv_t).exp() as f32
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+fn tempered_sampler(weights: &[f32], t: f32) -> Result<WeightedSampler, ProbError> {
+    assert!(!t.is_nan(), "temperature must not be NaN");
+    WeightedSampler::new(&temper(weights, t))
+}
+
+impl<T> DropTable<T> {
+    /// Sample at the given temperature, building a reweighted alias table
+    /// for this call. **O(n)**.
+    ///
+    /// Use [`with_temperatures`](Self::with_temperatures) when the same
+    /// temperatures are used repeatedly.
+    ///
+    /// # Panics
+    /// If `temperature` is `NaN`.
+    pub fn sample_tempered<'a, R: Rng + ?Sized>(&'a self, rng: &mut R, temperature: f32) -> &'a T {
+        let sampler = tempered_sampler(&self.weights, temperature)
+            .expect("tempering a valid table yields valid weights");
+        &self.items[sampler.sample_index(rng)]
+    }
+
+    /// Precompute alias tables for a fixed set of temperatures.
+    ///
+    /// # Panics
+    /// If any temperature is `NaN`.
+    pub fn with_temperatures(self, temperatures: &[f32]) -> TemperedDropTable<T> {
+        let cache = temperatures
+            .iter()
+            .map(|&t| {
+                let s = tempered_sampler(&self.weights, t)
+                    .expect("tempering a valid table yields valid weights");
+                (t, s)
+            })
+            .collect();
+        TemperedDropTable { table: self, cache }
+    }
+}
+
+/// A [`DropTable`] with a cached family of tempered alias tables.
+#[derive(Debug, Clone)]
+pub struct TemperedDropTable<T> {
+    table: DropTable<T>,
+    cache: Vec<(f32, WeightedSampler)>,
+}
+
+impl<T> TemperedDropTable<T> {
+    /// Sample at `temperature`: **O(1)** for a cached temperature, otherwise
+    /// falls back to [`DropTable::sample_tempered`].
+    ///
+    /// # Panics
+    /// If `temperature` is `NaN`.
+    pub fn sample_tempered<'a, R: Rng + ?Sized>(&'a self, rng: &mut R, temperature: f32) -> &'a T {
+        match self.cache.iter().find(|(t, _)| *t == temperature) {
+            Some((_, s)) => &self.table.items[s.sample_index(rng)],
+            None => self.table.sample_tempered(rng, temperature),
+        }
+    }
+
+    /// Cached temperatures, in the order given.
+    pub fn temperatures(&self) -> impl Iterator<Item = f32> + '_ {
+        self.cache.iter().map(|(t, _)| *t)
+    }
+
+    /// Number of items in the underlying table.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// The untempered table.
+    pub fn table(&self) -> &DropTable<T> {
+        &self.table
+    }
+
+    /// Drop the cache and return the underlying table.
+    pub fn into_inner(self) -> DropTable<T> {
+        self.table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temper_limits() {
+        let w = [1.0, 4.0, 0.0, 4.0];
+        assert_eq!(temper(&w, 0.0), vec![0.0, 1.0, 0.0, 1.0]);
+        assert_eq!(temper(&w, f32::INFINITY), vec![1.0, 1.0, 0.0, 1.0]);
+        let t1 = temper(&w, 1.0);
+        assert!((t1[0] - 0.25).abs() < 1e-6 && t1[1] == 1.0);
+        let t2 = temper(&w, 0.5);
+        assert!((t2[0] - 0.0625).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cached_argmax() {
+        let table = DropTable::from_pairs([("a", 1.0), ("b", 2.0)])
+            .unwrap()
+            .with_temperatures(&[0.0, 1.0]);
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            assert_eq!(*table.sample_tempered(&mut rng, 0.0), "b");
+        }
+    }
+}
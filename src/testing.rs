@@ -0,0 +1,108 @@
+//! Forced outcomes for tests.
+//!
+//! [`ForcedSampler`] ignores the RNG and returns a fixed index or a scripted
+//! sequence, so integration tests can make "the boss drops the legendary"
+//! happen on demand:
+//!
+//! ```
+//! use droptables::DropTable;
+//! use droptables::testing::ForcedSampler;
+//!
+//! let table = DropTable::from_pairs([("junk", 999.0), ("legendary", 1.0)])
+//!     .unwrap()
+//!     .with_sampler(ForcedSampler::always(2, 1))
+//!     .unwrap();
+//! assert_eq!(*table.sample(&mut rand::rng()), "legendary");
+//! ```
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rand::{Rng, RngCore};
+
+use crate::IndexSampler;
+
+/// An [`IndexSampler`] that plays back a script of indices, cycling when it
+/// reaches the end. The RNG is never touched.
+#[derive(Debug)]
+pub struct ForcedSampler {
+    len: usize,
+    script: Vec<usize>,
+    next: AtomicUsize,
+}
+
+impl ForcedSampler {
+    /// Always return `index`.
+    ///
+    /// # Panics
+    /// If `index >= len`.
+    pub fn always(len: usize, index: usize) -> Self {
+        Self::sequence(len, [index])
+    }
+
+    /// Return `indices` in order, then start over.
+    ///
+    /// # Panics
+    /// If `indices` is empty or any index is `>= len`.
+    pub fn sequence<I: IntoIterator<Item = usize>>(len: usize, indices: I) -> Self {
+        let script: Vec<usize> = indices.into_iter().collect();
+        assert!(!script.is_empty(), "ForcedSampler needs at least one index");
+        if let Some(&bad) = script.iter().find(|&&i| i >= len) {
+            panic!("forced index {bad} out of range for {len} items");
+        }
+        Self {
+            len,
+            script,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// How many draws have been made so far.
+    pub fn draws(&self) -> usize {
+        self.next.load(Ordering::Relaxed)
+    }
+}
+
+impl IndexSampler for ForcedSampler {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+    #[inline]
+    fn sample_index<R: Rng + ?Sized>(&self, _rng: &mut R) -> usize {
+        let n = self.next.fetch_add(1, Ordering::Relaxed);
+        self.script[n % self.script.len()]
+    }
+    #[inline]
+    fn sample_index_dyn(&self, rng: &mut dyn RngCore) -> usize {
+        self.sample_index(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DropTable, ProbError};
+
+    #[test]
+    fn scripted_sequence_cycles() {
+        let table = DropTable::from_pairs([('a', 1.0), ('b', 1.0), ('c', 0.0)])
+            .unwrap()
+            .with_sampler(ForcedSampler::sequence(3, [2, 0]))
+            .unwrap();
+        let mut rng = rand::rng();
+        let got: String = (0..5).map(|_| *table.sample(&mut rng)).collect();
+        assert_eq!(got, "cacac");
+
+        let err = DropTable::from_pairs([('a', 1.0)])
+            .unwrap()
+            .with_sampler(ForcedSampler::always(2, 0))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ProbError::Shape {
+                expected: 1,
+                found: 2
+            }
+        );
+    }
+}
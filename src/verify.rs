@@ -0,0 +1,226 @@
+//! Differential checks for [`IndexSampler`] implementations.
+//!
+//! [`compare`] draws from a sampler and lines the counts up against the
+//! exact probabilities of the weights it was built from (computed in `f64`
+//! from a plain running sum, with no tables involved). Use it to check forks
+//! of the alias build or custom samplers:
+//!
+//! ```
+//! use droptables::WeightedSampler;
+//! use droptables::verify::assert_distributions_match;
+//!
+//! let weights = [5.0, 0.0, 1.0, 94.0];
+//! let sampler = WeightedSampler::new(&weights).unwrap();
+//! assert_distributions_match(&sampler, &weights, 200_000, 1e-3);
+//! ```
+
+use std::fmt;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::IndexSampler;
+
+/// Half-width of the confidence interval, in standard errors (two-sided
+/// coverage ≈ 99.994%).
+pub const CONFIDENCE_Z: f64 = 4.0;
+
+/// Seed used by [`assert_distributions_match`].
+pub const DEFAULT_SEED: u64 = 0x5eed_1359;
+
+/// One index's row in a [`Comparison`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Deviation {
+    pub index: usize,
+    /// Exact probability from the weights.
+    pub expected: f64,
+    /// Observed frequency.
+    pub observed: f64,
+    /// `sqrt(p * (1 - p) / draws)` for the expected `p`.
+    pub std_error: f64,
+    /// `expected ± CONFIDENCE_Z * std_error`, clamped to `[0, 1]`.
+    pub interval: (f64, f64),
+}
+
+impl Deviation {
+    /// `observed - expected`.
+    pub fn deviation(&self) -> f64 {
+        self.observed - self.expected
+    }
+
+    /// Whether the observed frequency lies within the interval widened by
+    /// `tolerance` on each side.
+    pub fn within(&self, tolerance: f64) -> bool {
+        self.observed >= self.interval.0 - tolerance && self.observed <= self.interval.1 + tolerance
+    }
+}
+
+/// Per-index comparison of a sampler against its weights.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub draws: u64,
+    pub rows: Vec<Deviation>,
+    /// Draws that returned an index `>= weights.len()`.
+    pub out_of_range: u64,
+}
+
+impl Comparison {
+    /// Rows outside their interval widened by `tolerance`.
+    pub fn failures(&self, tolerance: f64) -> impl Iterator<Item = &Deviation> {
+        self.rows.iter().filter(move |r| !r.within(tolerance))
+    }
+
+    /// No out-of-range draws and every row within tolerance.
+    pub fn passes(&self, tolerance: f64) -> bool {
+        self.out_of_range == 0 && self.failures(tolerance).next().is_none()
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} draws", self.draws)?;
+        if self.out_of_range > 0 {
+            writeln!(f, "{} draws out of range", self.out_of_range)?;
+        }
+        writeln!(
+            f,
+            "{:>6} {:>10} {:>10} {:>11} {:>23}",
+            "index", "expected", "observed", "deviation", "interval"
+        )?;
+        for r in &self.rows {
+            writeln!(
+                f,
+                "{:>6} {:>10.6} {:>10.6} {:>+11.6} [{:>10.6}, {:>10.6}]",
+                r.index,
+                r.expected,
+                r.observed,
+                r.deviation(),
+                r.interval.0,
+                r.interval.1
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Exact probabilities for `weights`: each weight over the `f64` sum.
+fn reference(weights: &[f32]) -> Vec<f64> {
+    let total: f64 = weights.iter().map(|&w| f64::from(w)).sum();
+    weights.iter().map(|&w| f64::from(w) / total).collect()
+}
+
+/// Draw `draws` indices from `sampler` using `rng` and compare them with the
+/// exact distribution of `weights`.
+///
+/// # Panics
+/// If `sampler.len() != weights.len()`, `draws == 0`, or the weights do not
+/// have a positive finite sum.
+pub fn compare<S, R>(sampler: &S, weights: &[f32], draws: u64, rng: &mut R) -> Comparison
+where
+    S: IndexSampler + ?Sized,
+    R: rand::RngCore,
+{
+    assert_eq!(
+        sampler.len(),
+        weights.len(),
+        "sampler and weights disagree on length"
+    );
+    assert!(draws > 0, "need at least one draw");
+    let expected = reference(weights);
+    assert!(
+        expected.iter().all(|p| p.is_finite()),
+        "weights must have a positive finite sum"
+    );
+
+    let mut counts = vec![0u64; weights.len()];
+    let mut out_of_range = 0;
+    for _ in 0..draws {
+        match counts.get_mut(sampler.sample_index_dyn(rng)) {
+            Some(c) => *c += 1,
+            None => out_of_range += 1,
+        }
+    }
+
+    let n = draws as f64;
+    let rows = expected
+        .iter()
+        .zip(&counts)
+        .enumerate()
+        .map(|(index, (&p, &c))| {
+            let std_error = (p * (1.0 - p) / n).sqrt();
+            let half = CONFIDENCE_Z * std_error;
+            Deviation {
+                index,
+                expected: p,
+                observed: c as f64 / n,
+                std_error,
+                interval: ((p - half).max(0.0), (p + half).min(1.0)),
+            }
+        })
+        .collect();
+    Comparison {
+        draws,
+        rows,
+        out_of_range,
+    }
+}
+
+/// Panic with a per-index report unless `sampler` matches `weights`.
+///
+/// Each index passes when its observed frequency is within
+/// [`CONFIDENCE_Z`] standard errors of the exact probability, plus
+/// `tolerance` of absolute slack. Zero-weight indices therefore must never be
+/// drawn when `tolerance` is `0.0`. Draws come from a [`StdRng`] seeded with
+/// [`DEFAULT_SEED`], so a failure reproduces.
+///
+/// # Panics
+/// On a mismatch, or for the reasons listed on [`compare`].
+pub fn assert_distributions_match<S: IndexSampler + ?Sized>(
+    sampler: &S,
+    weights: &[f32],
+    draws: u64,
+    tolerance: f64,
+) {
+    let mut rng = StdRng::seed_from_u64(DEFAULT_SEED);
+    let report = compare(sampler, weights, draws, &mut rng);
+    if !report.passes(tolerance) {
+        let bad: Vec<usize> = report.failures(tolerance).map(|r| r.index).collect();
+        panic!(
+            "sampler does not match weights (tolerance {tolerance}); failing indices {bad:?}\n{report}"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ForcedSampler;
+    use crate::{CdfSampler, WeightedSampler};
+
+    #[test]
+    fn built_in_samplers_match() {
+        let weights = [0.1, 7.0, 0.0, 2.5, 40.0, 0.4];
+        assert_distributions_match(
+            &WeightedSampler::new(&weights).unwrap(),
+            &weights,
+            100_000,
+            0.0,
+        );
+        assert_distributions_match(&CdfSampler::new(&weights).unwrap(), &weights, 100_000, 0.0);
+    }
+
+    #[test]
+    fn biased_sampler_is_reported() {
+        let weights = [1.0, 1.0];
+        let report = compare(
+            &ForcedSampler::sequence(2, [0, 0, 1]),
+            &weights,
+            3_000,
+            &mut StdRng::seed_from_u64(1),
+        );
+        assert!(!report.passes(0.1));
+        assert!(report.passes(0.2));
+        assert_eq!(report.failures(0.0).count(), 2);
+        assert!(report.to_string().contains("+0.166667"), "{report}");
+    }
+}
@@ -0,0 +1,158 @@
+//! Table identity: a caller-assigned [`version`](DropTable::version) and a
+//! [`content_hash`](DropTable::content_hash) of what the table actually
+//! contains, so a server can check that a client loaded the same table.
+//!
+//! The text and CSV formats carry the version as a pragma line,
+//! `#! version = 3`; readers that predate it see an ordinary comment.
+
+use std::fmt::Display;
+use std::hash::Hasher;
+
+use crate::DropTable;
+
+/// Parse a `#! version = N` pragma. `None` if `line` is not a pragma at all,
+/// `Some(None)` if it is one but malformed.
+pub(crate) fn version_pragma(line: &str) -> Option<Option<u32>> {
+    let rest = line.trim().strip_prefix("#!")?;
+    let value = rest
+        .trim()
+        .strip_prefix("version")
+        .and_then(|r| r.trim_start().strip_prefix('='))
+        .and_then(|v| v.trim().parse().ok());
+    Some(value)
+}
+
+/// 64-bit FNV-1a; fixed here rather than borrowed from `std::hash`, whose
+/// algorithms may change between releases.
+///
+/// Integers are hashed as little-endian bytes, and `usize`/`isize` as 64
+/// bits, where `Hasher`'s defaults use native-endian, pointer-width bytes;
+/// so `Hash` impls built from integers and strings hash the same on every
+/// platform.
+pub(crate) struct Fnv1a(u64);
+
+impl Fnv1a {
+    pub(crate) fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl std::hash::Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= u64::from(b);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_u64(i as i64 as u64);
+    }
+}
+
+impl<T> DropTable<T> {
+    /// Caller-assigned version, `0` unless set with
+    /// [`with_version`](Self::with_version) or read from a `#! version`
+    /// pragma.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Tag the table with `version`.
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+}
+
+impl<T: Display> DropTable<T> {
+    /// A hash of the items' `Display` form and the normalized weights, in
+    /// order. Stable across platforms and releases of this crate, and
+    /// independent of the weights' scale (`a:1, b:3` hashes like
+    /// `a:2, b:6`), the sampler kind and the [`version`](Self::version).
+    pub fn content_hash(&self) -> u64 {
+        let total: f64 = self.weights.iter().map(|&w| f64::from(w)).sum();
+        let mut h = Fnv1a::new();
+        for (item, &w) in self.items.iter().zip(&self.weights) {
+            let name = item.to_string();
+            h.write(&(name.len() as u64).to_le_bytes());
+            h.write(name.as_bytes());
+            // + 0.0 folds -0.0 into 0.0
+            h.write(&(f64::from(w) / total + 0.0).to_bits().to_le_bytes());
+        }
+        h.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_ignores_scale_but_not_content() {
+        let a = DropTable::from_pairs([("bones", 1.0), ("pet", 3.0)]).unwrap();
+        let b = DropTable::from_pairs([("bones", 2.0), ("pet", 6.0)])
+            .unwrap()
+            .with_version(7);
+        assert_eq!(a.content_hash(), b.content_hash());
+        assert_eq!(b.version(), 7);
+        // pinned: the hash must not change between releases
+        assert_eq!(a.content_hash(), 0x8dd2_d2f2_b134_12e7);
+
+        let renamed = DropTable::from_pairs([("bone", 1.0), ("pet", 3.0)]).unwrap();
+        let swapped = DropTable::from_pairs([("pet", 3.0), ("bones", 1.0)]).unwrap();
+        let reweighted = DropTable::from_pairs([("bones", 1.0), ("pet", 4.0)]).unwrap();
+        for other in [renamed, swapped, reweighted] {
+            assert_ne!(a.content_hash(), other.content_hash());
+        }
+    }
+
+    #[test]
+    fn version_pragma_forms() {
+        assert_eq!(version_pragma("#! version = 3"), Some(Some(3)));
+        assert_eq!(version_pragma("  #!version=12  "), Some(Some(12)));
+        assert_eq!(version_pragma("#! version = x"), Some(None));
+        assert_eq!(version_pragma("# version = 3"), None);
+        assert_eq!(version_pragma("a: 1"), None);
+    }
+}
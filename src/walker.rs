@@ -4,19 +4,27 @@
 //! and a fallback index `alias[i]`. A single uniform draw over buckets, plus
 //! a single uniform draw in `[0,1)`, yields an index in O(1).
 //!
+//! `prob[i]` is kept as a 53-bit fixed-point threshold and compared against
+//! the top 53 bits of a `u64` draw, so probabilities far below `f32`'s
+//! 2⁻²⁴ step (very rare drops) are still sampled accurately.
+//!
 //! ## Construction
 //! Given non-negative weights `w`, we scale them so that the **average** is 1.
+//! The sum, the scaling and the pairing all run in `f64` (the sum with
+//! compensated summation); only the final thresholds are rounded to
+//! fixed point.
 //! Buckets with `p < 1` are matched with buckets with `p >= 1` until all are
 //! resolved; remaining buckets get probability 1 and alias to themselves.
 //!
 //! ## Properties
 //! * **Build**: O(n)
 //! * **Sample**: O(1)
-//! * **Space**: ~`(f32 + usize) * n`
+//! * **Space**: 16 bytes per bucket
 //!
-//! See [`WeightedSampler::new`] for input validation.
+//! See [`WeightedSampler::new`] for input validation, and
+//! [`WeightedSampler::with_report`] for what the build had to correct.
 
-use crate::error::ProbError;
+use crate::error::{InvariantError, ProbError};
 use rand::Rng;
 
 /// Alias table for discrete distribution sampling.
@@ -26,13 +34,231 @@ use rand::Rng;
 #[derive(Debug, Clone)]
 pub struct WeightedSampler {
     probs: Vec<Bucket>,
+    #[cfg(feature = "debug-metrics")]
+    counters: crate::metrics::BranchCounters,
 }
 
+/// Alternative name for [`WeightedSampler`], matching the literature.
+pub type AliasTable = WeightedSampler;
+
+/// One column of the alias table: keep bucket `i` with probability
+/// [`prob`](Bucket::prob), otherwise jump to [`alias`](Bucket::alias).
 #[repr(C)]
-#[derive(Default, Debug, Clone)]
-struct Bucket {
-    prob: f32,  // f32 is typically plenty here
-    alias: u32, // if n <= u32::MAX
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Bucket {
+    threshold: u64, // prob * 2^53
+    alias: AliasIndex,
+}
+
+/// Bucket alias type: `u32` by default, `u64` with feature `u64-alias`.
+/// `Bucket` is 16 bytes either way; the `u32` default only keeps the
+/// construction worklists half the size.
+#[cfg(not(feature = "u64-alias"))]
+type AliasIndex = u32;
+#[cfg(feature = "u64-alias")]
+type AliasIndex = u64;
+
+/// Reject tables with more entries than an alias index can address.
+pub(crate) fn check_len(len: usize, max: usize) -> Result<(), ProbError> {
+    if len > max {
+        return Err(ProbError::TooLarge { len, max });
+    }
+    Ok(())
+}
+
+impl Bucket {
+    /// The threshold of a bucket that always keeps its own index (`2^53`).
+    pub const ONE: u64 = 1 << 53;
+
+    fn threshold_of(prob: f64) -> u64 {
+        (prob.clamp(0.0, 1.0) * Self::ONE as f64).round() as u64
+    }
+
+    /// Probability of keeping this bucket's own index, in `[0, 1]`, rounded
+    /// to `f32`; see [`threshold`](Bucket::threshold) for the exact value.
+    #[inline]
+    pub fn prob(&self) -> f32 {
+        self.prob_f64() as f32
+    }
+
+    /// Keep-probability as a fixed-point fraction of [`Bucket::ONE`]: a draw
+    /// keeps index `i` when its top 53 bits are below this.
+    #[inline]
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+
+    #[inline]
+    fn prob_f64(&self) -> f64 {
+        self.threshold as f64 / Self::ONE as f64
+    }
+
+    /// Fallback index taken when the primary draw fails.
+    #[inline]
+    pub fn alias(&self) -> usize {
+        self.alias as usize
+    }
+}
+
+/// What to do with a `-0.0` weight.
+///
+/// Computed configs produce `-0.0` easily (`0.0 * -1.0`, `x - x` under some
+/// rounding modes), so by default it is treated as an ordinary zero. Strict
+/// pipelines can opt into rejecting it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NegativeZero {
+    /// Treat `-0.0` as `0.0`.
+    #[default]
+    AsZero,
+    /// Reject it with [`ProbError::Negative`], like any other negative.
+    Reject,
+}
+
+impl NegativeZero {
+    /// Check one weight; `Ok(true)` means it was a `-0.0` let through.
+    pub(crate) fn check(self, index: usize, w: f32) -> Result<bool, ProbError> {
+        let neg_zero = w == 0.0 && w.is_sign_negative();
+        if w < 0.0 || (neg_zero && self == NegativeZero::Reject) {
+            return Err(ProbError::Negative { index, value: w });
+        }
+        Ok(neg_zero)
+    }
+}
+
+/// Shared input validation for every weight-based sampler; returns the sum,
+/// accumulated in `f64` with Neumaier's compensated summation so that tables
+/// mixing huge and tiny weights don't drift.
+pub(crate) fn validate_weights(weights: &[f32]) -> Result<f64, ProbError> {
+    validate_weights_with(weights, NegativeZero::default(), &mut Vec::new())
+}
+
+/// [`validate_weights`] under an explicit policy, recording the indices of
+/// accepted `-0.0` weights in `negative_zeros`.
+fn validate_weights_with(
+    weights: &[f32],
+    policy: NegativeZero,
+    negative_zeros: &mut Vec<usize>,
+) -> Result<f64, ProbError> {
+    if weights.is_empty() {
+        return Err(ProbError::Empty);
+    }
+    let (mut sum, mut comp) = (0.0f64, 0.0f64);
+    for (i, &w) in weights.iter().enumerate() {
+        if policy.check(i, w)? {
+            negative_zeros.push(i);
+        }
+        let w = f64::from(w);
+        let t = sum + w;
+        comp += if sum.abs() >= w.abs() {
+            (sum - t) + w
+        } else {
+            (w - t) + sum
+        };
+        sum = t;
+    }
+    let sum = sum + comp;
+    if !sum.is_finite() || sum == 0.0 {
+        return Err(ProbError::ZeroSum);
+    }
+    Ok(sum)
+}
+
+/// A bucket that the pairing loop left unresolved and the build forced to
+/// `prob = 1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BucketCorrection {
+    pub index: usize,
+    /// Its scaled weight before the correction; exactly `1.0` in exact
+    /// arithmetic, off by accumulated rounding in practice.
+    pub scaled: f64,
+}
+
+/// Numerical corrections applied while building an alias table, as returned
+/// by [`WeightedSampler::with_report`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BuildReport {
+    /// Indices of `-0.0` weights treated as zero.
+    pub negative_zeros: Vec<usize>,
+    /// Leftover buckets whose scaled weight was not exactly `1.0`.
+    pub corrections: Vec<BucketCorrection>,
+}
+
+impl BuildReport {
+    /// Largest `|scaled - 1|` over the corrected buckets (`0.0` if none).
+    pub fn max_correction(&self) -> f64 {
+        self.corrections
+            .iter()
+            .map(|c| (c.scaled - 1.0).abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// Whether the build needed no corrections at all.
+    pub fn is_clean(&self) -> bool {
+        self.negative_zeros.is_empty() && self.corrections.is_empty()
+    }
+}
+
+/// Partition indices (offset by `base`) into below-average and
+/// at-or-above-average buckets.
+fn split_small_large(scaled: &[f64], base: AliasIndex) -> (Vec<AliasIndex>, Vec<AliasIndex>) {
+    let mut small: Vec<AliasIndex> = Vec::with_capacity(scaled.len());
+    let mut large: Vec<AliasIndex> = Vec::with_capacity(scaled.len());
+    for (i, &p) in scaled.iter().enumerate() {
+        if p < 1.0 {
+            small.push(base + i as AliasIndex);
+        } else {
+            large.push(base + i as AliasIndex);
+        }
+    }
+    (small, large)
+}
+
+/// Vose's pairing loop: fill each small bucket from a large one until one
+/// worklist runs out. Indices are into `scaled`/`probs`; whatever is left
+/// unpaired is returned.
+fn pair_buckets(
+    scaled: &mut [f64],
+    probs: &mut [Bucket],
+    mut small: Vec<AliasIndex>,
+    mut large: Vec<AliasIndex>,
+) -> (Vec<AliasIndex>, Vec<AliasIndex>) {
+    while let (Some(s), Some(l)) = (small.pop(), large.pop()) {
+        probs[s as usize].threshold = Bucket::threshold_of(scaled[s as usize]); // in [0,1)
+        probs[s as usize].alias = l;
+
+        scaled[l as usize] = (scaled[l as usize] + scaled[s as usize]) - 1.0;
+
+        if scaled[l as usize] < 1.0 - 1e-15 {
+            small.push(l);
+        } else {
+            large.push(l);
+        }
+    }
+    (small, large)
+}
+
+/// Give every bucket still on a worklist `prob = 1.0` (it aliases itself),
+/// returning those whose scaled weight had drifted from `1.0`.
+fn finalize_leftovers(
+    scaled: &[f64],
+    probs: &mut [Bucket],
+    small: Vec<AliasIndex>,
+    large: Vec<AliasIndex>,
+) -> Vec<BucketCorrection> {
+    let mut corrections = Vec::new();
+    for i in small.into_iter().chain(large) {
+        let s = scaled[i as usize];
+        if s != 1.0 {
+            corrections.push(BucketCorrection {
+                index: i as usize,
+                scaled: s,
+            });
+        }
+        probs[i as usize].threshold = Bucket::ONE;
+        probs[i as usize].alias = i;
+    }
+    corrections.sort_by_key(|c| c.index);
+    corrections
 }
 
 impl WeightedSampler {
@@ -40,70 +266,164 @@ impl WeightedSampler {
     ///
     /// # Errors
     /// * [`ProbError::Empty`] if `weights` is empty
-    /// * [`ProbError::Negative`] if any weight is negative (includes `-0.0`)
+    /// * [`ProbError::Negative`] if any weight is negative (`-0.0` is
+    ///   accepted as zero; see [`NegativeZero`])
     /// * [`ProbError::ZeroSum`] if the sum is zero or not finite (`NaN`/∞)
+    /// * [`ProbError::TooLarge`] if there are more than
+    ///   [`MAX_LEN`](Self::MAX_LEN) weights
     ///
     /// # Notes
     /// * Inputs are normalized internally; original scale doesn’t matter.
     /// * We apply a small tolerance (`1e-15`) to avoid numerical flip-flops.
     pub fn new(weights: &[f32]) -> Result<Self, ProbError> {
-        let n = weights.len();
-        if n == 0 {
-            return Err(ProbError::Empty);
-        }
+        Self::with_report(weights, NegativeZero::default()).map(|(table, _)| table)
+    }
 
-        let mut sum = 0.0f32;
-        for (i, &w) in weights.iter().enumerate() {
-            if w.is_sign_negative() {
-                return Err(ProbError::Negative { index: i, value: w });
-            }
-            sum += w;
-        }
-        if !sum.is_finite() || sum == 0.0 {
-            return Err(ProbError::ZeroSum);
-        }
+    /// [`new`](Self::new) under an explicit `-0.0` policy, also returning a
+    /// [`BuildReport`] of every numerical correction the build applied.
+    ///
+    /// # Errors
+    /// As [`new`](Self::new); with [`NegativeZero::Reject`], `-0.0` is
+    /// reported as [`ProbError::Negative`].
+    pub fn with_report(
+        weights: &[f32],
+        policy: NegativeZero,
+    ) -> Result<(Self, BuildReport), ProbError> {
+        let n = weights.len();
+        check_len(n, Self::MAX_LEN)?;
+        let mut negative_zeros = Vec::new();
+        let sum = validate_weights_with(weights, policy, &mut negative_zeros)?;
 
         // Scale so average is 1.
-        let mut scaled: Vec<f32> = weights.iter().map(|&w| w * n as f32 / sum).collect();
+        let mut scaled: Vec<f64> = weights
+            .iter()
+            .map(|&w| f64::from(w) * n as f64 / sum)
+            .collect();
 
         let mut probs = Vec::with_capacity(n);
         for i in 0..n {
             probs.push(Bucket {
-                prob: 0.0,
-                alias: i as u32,
+                threshold: 0,
+                alias: i as AliasIndex,
             });
         }
 
-        let mut small: Vec<u32> = Vec::with_capacity(n);
-        let mut large: Vec<u32> = Vec::with_capacity(n);
+        let (small, large) = split_small_large(&scaled, 0);
+        let (small, large) = pair_buckets(&mut scaled, &mut probs, small, large);
+        let corrections = finalize_leftovers(&scaled, &mut probs, small, large);
 
-        for (i, &p) in scaled.iter().enumerate() {
-            if p < 1.0 {
-                small.push(i as u32);
-            } else {
-                large.push(i as u32);
-            }
+        let table = Self {
+            #[cfg(feature = "debug-metrics")]
+            counters: crate::metrics::BranchCounters::new(probs.len()),
+            probs,
+        };
+        Ok((
+            table,
+            BuildReport {
+                negative_zeros,
+                corrections,
+            },
+        ))
+    }
+
+    /// [`new`](Self::new), with the pairing work spread over the rayon
+    /// pool (feature `rayon`).
+    ///
+    /// Each chunk of [`PARALLEL_CHUNK`](Self::PARALLEL_CHUNK) buckets is
+    /// paired locally, which leaves it with only small or only large
+    /// leftovers; those are then stitched together sequentially. The result
+    /// describes the same distribution as [`new`](Self::new), but buckets
+    /// may alias different partners, so draws for a given seed differ.
+    ///
+    /// # Errors
+    /// As [`new`](Self::new).
+    #[cfg(feature = "rayon")]
+    pub fn new_parallel(weights: &[f32]) -> Result<Self, ProbError> {
+        use rayon::prelude::*;
+
+        let n = weights.len();
+        check_len(n, Self::MAX_LEN)?;
+        let sum = validate_weights(weights)?;
+        if n <= Self::PARALLEL_CHUNK {
+            return Self::new(weights);
         }
 
-        while let (Some(s), Some(l)) = (small.pop(), large.pop()) {
-            probs[s as usize].prob = scaled[s as usize]; // in [0,1)
-            probs[s as usize].alias = l;
+        let mut scaled: Vec<f64> = weights
+            .par_iter()
+            .map(|&w| f64::from(w) * n as f64 / sum)
+            .collect();
+        let mut probs = vec![Bucket::default(); n];
 
-            scaled[l as usize] = (scaled[l as usize] + scaled[s as usize]) - 1.0;
+        let leftovers: Vec<(Vec<AliasIndex>, Vec<AliasIndex>)> = scaled
+            .par_chunks_mut(Self::PARALLEL_CHUNK)
+            .zip(probs.par_chunks_mut(Self::PARALLEL_CHUNK))
+            .enumerate()
+            .map(|(c, (scaled, probs))| {
+                let base = (c * Self::PARALLEL_CHUNK) as AliasIndex;
+                let (small, large) = split_small_large(scaled, 0);
+                let (small, large) = pair_buckets(scaled, probs, small, large);
+                // Pairing wrote chunk-local aliases; leftovers are
+                // overwritten once stitched below.
+                for b in probs.iter_mut() {
+                    b.alias += base;
+                }
+                let globalize = |v: Vec<AliasIndex>| v.into_iter().map(|i| i + base).collect();
+                (globalize(small), globalize(large))
+            })
+            .collect();
 
-            if scaled[l as usize] < 1.0 - 1e-15 {
-                small.push(l);
-            } else {
-                large.push(l);
-            }
-        }
+        let (small, large) =
+            leftovers
+                .into_iter()
+                .fold((Vec::new(), Vec::new()), |(mut s, mut l), (cs, cl)| {
+                    s.extend(cs);
+                    l.extend(cl);
+                    (s, l)
+                });
+        let (small, large) = pair_buckets(&mut scaled, &mut probs, small, large);
+        finalize_leftovers(&scaled, &mut probs, small, large);
 
-        for i in small.into_iter().chain(large.into_iter()) {
-            probs[i as usize].prob = 1.0;
-            probs[i as usize].alias = i;
-        }
+        Ok(Self {
+            #[cfg(feature = "debug-metrics")]
+            counters: crate::metrics::BranchCounters::new(probs.len()),
+            probs,
+        })
+    }
+
+    /// Chunk size used by [`new_parallel`](Self::new_parallel); tables this
+    /// size or smaller are built sequentially.
+    #[cfg(feature = "rayon")]
+    pub const PARALLEL_CHUNK: usize = 1 << 16;
+
+    /// Most weights a table can hold: `2^32` by default, or all of `usize`
+    /// with feature `u64-alias` (on 64-bit targets).
+    pub const MAX_LEN: usize = (AliasIndex::MAX as usize).saturating_add(1);
+
+    /// Default tolerance used by [`from_probabilities`](Self::from_probabilities),
+    /// matching the one the `WeightedEnum` derive enforces.
+    pub const DEFAULT_EPSILON: f64 = 1e-6;
+
+    /// Construct from probabilities that must already sum to 1 (within
+    /// [`DEFAULT_EPSILON`](Self::DEFAULT_EPSILON)).
+    ///
+    /// Unlike [`new`](Self::new), which silently renormalizes any scale,
+    /// this surfaces configuration mistakes.
+    ///
+    /// # Errors
+    /// * [`ProbError::NotNormalized`] if the sum deviates from 1 by more than the tolerance
+    /// * Anything [`new`](Self::new) rejects
+    pub fn from_probabilities(probs: &[f32]) -> Result<Self, ProbError> {
+        Self::from_probabilities_eps(probs, Self::DEFAULT_EPSILON)
+    }
 
-        Ok(Self { probs })
+    /// [`from_probabilities`](Self::from_probabilities) with an explicit tolerance.
+    pub fn from_probabilities_eps(probs: &[f32], epsilon: f64) -> Result<Self, ProbError> {
+        let sum: f64 = probs.iter().map(|&p| p as f64).sum();
+        // NaN sums fall through to `new`, which reports them precisely
+        if sum.is_finite() && (sum - 1.0).abs() > epsilon {
+            return Err(ProbError::NotNormalized { sum, epsilon });
+        }
+        Self::new(probs)
     }
 
     /// Draw a single sample **index** in O(1).
@@ -120,14 +440,30 @@ impl WeightedSampler {
     pub fn sample_index<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
         let n = self.probs.len();
         let i = rng.random_range(0..n); // replaces deprecated gen_range
-        let u: f32 = rng.random(); // replaces deprecated r#gen()/gen()
-        if u < self.probs[i].prob {
+        let u = rng.next_u64() >> 11; // 53 bits, uniform in [0, Bucket::ONE)
+        let keep = u < self.probs[i].threshold;
+        #[cfg(feature = "debug-metrics")]
+        self.counters.record(i, !keep);
+        if keep {
             i
         } else {
             self.probs[i].alias as usize
         }
     }
 
+    /// Primary vs alias branch counts since construction (or the last
+    /// [`reset_metrics`](Self::reset_metrics)).
+    #[cfg(feature = "debug-metrics")]
+    pub fn metrics(&self) -> crate::metrics::AliasMetrics {
+        self.counters.snapshot()
+    }
+
+    /// Zero the branch counters.
+    #[cfg(feature = "debug-metrics")]
+    pub fn reset_metrics(&self) {
+        self.counters.reset();
+    }
+
     /// Draw k samples, returning counts per index (useful for checks).
     #[cfg(test)]
     pub fn sample_counts<R: Rng + ?Sized>(&self, rng: &mut R, draws: usize) -> Vec<usize> {
@@ -144,6 +480,136 @@ impl WeightedSampler {
         self.probs.len()
     }
 
+    /// Primary probability of bucket `i`.
+    ///
+    /// # Panics
+    /// If `i >= self.len()`.
+    pub fn prob(&self, i: usize) -> f32 {
+        self.probs[i].prob()
+    }
+
+    /// Alias index of bucket `i`.
+    ///
+    /// # Panics
+    /// If `i >= self.len()`.
+    pub fn alias(&self, i: usize) -> usize {
+        self.probs[i].alias as usize
+    }
+
+    /// The raw buckets, for inspection or visualization.
+    pub fn as_buckets(&self) -> &[Bucket] {
+        &self.probs
+    }
+
+    /// The distribution this table actually samples, reconstructed from the
+    /// buckets (in index order). Differs from the input weights only by
+    /// rounding of the thresholds to multiples of 2⁻⁵³.
+    pub fn probabilities(&self) -> Vec<f64> {
+        let n = self.probs.len() as f64;
+        let mut mass = vec![0.0f64; self.probs.len()];
+        for (i, b) in self.probs.iter().enumerate() {
+            mass[i] += b.prob_f64() / n;
+            mass[b.alias as usize] += (1.0 - b.prob_f64()) / n;
+        }
+        mass
+    }
+
+    /// Check the structural invariants every sampler relies on: at least one
+    /// bucket, every threshold at most [`Bucket::ONE`], every alias in range.
+    ///
+    /// Tables built by [`WeightedSampler::new`] always pass; this is exposed
+    /// for fuzzing wrappers and for tables imported from elsewhere.
+    pub fn verify_invariants(&self) -> Result<(), InvariantError> {
+        let n = self.probs.len();
+        if n == 0 {
+            return Err(InvariantError::Empty);
+        }
+        for (index, b) in self.probs.iter().enumerate() {
+            if b.threshold > Bucket::ONE {
+                return Err(InvariantError::ProbOutOfRange {
+                    index,
+                    prob: b.prob(),
+                });
+            }
+            if b.alias as usize >= n {
+                return Err(InvariantError::AliasOutOfRange {
+                    index,
+                    alias: b.alias as usize,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Flatten into parallel `prob` and `alias` arrays, e.g. to upload as GPU
+    /// buffers for compute-shader sampling or to hand to another language.
+    ///
+    /// Layout: both arrays have one entry per bucket, in index order. To
+    /// draw, pick `i` uniformly from `0..len`, then a uniform `u` in
+    /// `[0, 1)`; the result is `i` if `u < prob[i]`, otherwise `alias[i]`.
+    /// Every `prob` is in `[0, 1]` and every alias is below `len`. Buckets
+    /// that never alias hold `prob = 1.0` and alias themselves.
+    ///
+    /// Thresholds are rounded from 53 to 24 bits, so each bucket's split may
+    /// move by up to 2⁻²⁵. [`from_raw_parts`](Self::from_raw_parts) rebuilds
+    /// a table that samples exactly what the exported arrays describe.
+    ///
+    /// # Panics
+    /// With feature `u64-alias`, if the table has more than `2^32` buckets.
+    pub fn into_raw_parts(self) -> (Vec<f32>, Vec<u32>) {
+        assert!(
+            u32::try_from(self.probs.len().saturating_sub(1)).is_ok(),
+            "alias indices don't fit in u32"
+        );
+        self.probs
+            .iter()
+            .map(|b| (b.prob(), b.alias() as u32))
+            .unzip()
+    }
+
+    /// Rebuild a table from arrays laid out as by
+    /// [`into_raw_parts`](Self::into_raw_parts).
+    ///
+    /// # Errors
+    /// * [`InvariantError::LengthMismatch`] if the arrays differ in length
+    /// * [`InvariantError::Empty`] if they are empty
+    /// * [`InvariantError::ProbOutOfRange`] for a probability outside
+    ///   `[0, 1]` (or `NaN`)
+    /// * [`InvariantError::AliasOutOfRange`] for an alias `>= len`
+    pub fn from_raw_parts(probs: &[f32], aliases: &[u32]) -> Result<Self, InvariantError> {
+        if probs.len() != aliases.len() {
+            return Err(InvariantError::LengthMismatch {
+                probs: probs.len(),
+                aliases: aliases.len(),
+            });
+        }
+        let n = probs.len();
+        if n == 0 {
+            return Err(InvariantError::Empty);
+        }
+        let mut buckets = Vec::with_capacity(n);
+        for (index, (&prob, &alias)) in probs.iter().zip(aliases).enumerate() {
+            if !(0.0..=1.0).contains(&prob) {
+                return Err(InvariantError::ProbOutOfRange { index, prob });
+            }
+            if alias as usize >= n {
+                return Err(InvariantError::AliasOutOfRange {
+                    index,
+                    alias: alias as usize,
+                });
+            }
+            buckets.push(Bucket {
+                threshold: Bucket::threshold_of(f64::from(prob)),
+                alias: alias as AliasIndex,
+            });
+        }
+        Ok(Self {
+            #[cfg(feature = "debug-metrics")]
+            counters: crate::metrics::BranchCounters::new(n),
+            probs: buckets,
+        })
+    }
+
     /// Whether the table is empty.
     pub fn is_empty(&self) -> bool {
         self.probs.is_empty()
@@ -168,13 +634,99 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn negative_zero_policy_and_report() {
+        let weights = [1.0, -0.0, 2.0];
+        let (alias, report) = WeightedSampler::with_report(&weights, NegativeZero::AsZero).unwrap();
+        assert_eq!(report.negative_zeros, vec![1]);
+        assert_eq!(alias.probabilities()[1], 0.0);
+        assert!(WeightedSampler::new(&weights).is_ok());
+        assert_eq!(
+            WeightedSampler::with_report(&weights, NegativeZero::Reject).unwrap_err(),
+            ProbError::Negative {
+                index: 1,
+                value: -0.0
+            }
+        );
+
+        let (_, report) = WeightedSampler::with_report(&[1.0, 1.0], NegativeZero::Reject).unwrap();
+        assert!(report.is_clean());
+        // 0.1 * 7 / 0.7 rounds, so leftover buckets are off from 1.0 by
+        // an ulp or so; the report records them
+        let (_, report) = WeightedSampler::with_report(&[0.1; 7], NegativeZero::default()).unwrap();
+        assert!(report.max_correction() < 1e-5, "{report:?}");
+    }
+
+    #[test]
+    fn accumulation_survives_wide_weight_ranges() {
+        // One weight at 2^24 followed by 100k ones: an f32 running sum never
+        // moves past 2^24 (each +1.0 rounds back to even), so the naive build
+        // underestimated the total by 100k.
+        let mut weights = vec![16_777_216.0f32];
+        weights.extend(std::iter::repeat_n(1.0, 100_000));
+        let exact = |w: f64| w / (16_777_216.0 + 100_000.0);
+
+        let alias = WeightedSampler::new(&weights).unwrap();
+        let probs = alias.probabilities();
+        assert!(
+            (probs[0] - exact(16_777_216.0)).abs() < 1e-6,
+            "{}",
+            probs[0]
+        );
+        let tail: f64 = probs[1..].iter().sum();
+        assert!((tail - exact(100_000.0)).abs() < 1e-6, "{tail}");
+    }
+
+    #[test]
+    fn rare_drops_keep_full_threshold_precision() {
+        // 2e-8 is a third of f32's 2^-24 step: a float compare would have
+        // kept bucket 1 with probability 0 or 2^-24, never 2e-8
+        let rare = 1e-8f32;
+        let alias = WeightedSampler::new(&[1.0, rare]).unwrap();
+        let w = f64::from(rare);
+        let exact = 2.0 * w / (1.0 + w);
+        assert_eq!(
+            alias.as_buckets()[1].threshold(),
+            (exact * Bucket::ONE as f64).round() as u64
+        );
+        let p = alias.probabilities()[1];
+        assert!((p - w / (1.0 + w)).abs() < 1e-15, "{p}");
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn oversized_tables_are_an_error() {
+        // allocating 2^32 weights is out of the question in a unit test, so
+        // exercise the check on its own
+        let max = u32::MAX as usize + 1;
+        assert_eq!(check_len(max, max), Ok(()));
+        assert_eq!(
+            check_len(max + 1, max),
+            Err(ProbError::TooLarge { len: max + 1, max })
+        );
+        #[cfg(not(feature = "u64-alias"))]
+        assert_eq!(WeightedSampler::MAX_LEN, max);
+        #[cfg(feature = "u64-alias")]
+        assert_eq!(WeightedSampler::MAX_LEN, usize::MAX);
+    }
+
+    #[test]
+    fn from_probabilities_requires_unit_sum() {
+        assert!(WeightedSampler::from_probabilities(&[0.25, 0.75]).is_ok());
+        assert!(matches!(
+            WeightedSampler::from_probabilities(&[0.25, 0.7]),
+            Err(ProbError::NotNormalized { .. })
+        ));
+        assert!(WeightedSampler::from_probabilities_eps(&[0.25, 0.7], 0.1).is_ok());
+    }
+
     #[test]
     fn roughly_matches_distribution() {
         let weights = [1.0, 2.0, 3.0, 4.0];
         let alias = WeightedSampler::new(&weights).unwrap();
 
         let mut rng = StdRng::seed_from_u64(42);
-        let draws = 2_000_0usize; // keep test light; raise locally if you like
+        let draws = 20_000usize; // keep test light; raise locally if you like
         let counts = alias.sample_counts(&mut rng, draws);
 
         let sum_w: f32 = weights.iter().sum();
@@ -185,6 +737,62 @@ mod tests {
         }
     }
 
+    #[test]
+    fn buckets_reconstruct_weights() {
+        let weights = [1.0, 2.0, 3.0, 4.0];
+        let alias = AliasTable::new(&weights).unwrap();
+        let n = alias.len() as f32;
+        let mut mass = [0.0f32; 4];
+        for (i, b) in alias.as_buckets().iter().enumerate() {
+            assert_eq!(b.prob(), alias.prob(i));
+            assert_eq!(b.alias(), alias.alias(i));
+            mass[i] += b.prob() / n;
+            mass[b.alias()] += (1.0 - b.prob()) / n;
+        }
+        for (i, &m) in mass.iter().enumerate() {
+            assert!((m - weights[i] / 10.0).abs() < 1e-5, "i={i} m={m}");
+        }
+    }
+
+    #[test]
+    fn random_tables_satisfy_invariants() {
+        let mut rng = StdRng::seed_from_u64(1309);
+        for n in 1..64 {
+            let weights: Vec<f32> = (0..n).map(|_| rng.random::<f32>() * 100.0).collect();
+            let alias = WeightedSampler::new(&weights).unwrap();
+            assert_eq!(alias.verify_invariants(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn raw_parts_round_trip_and_validate() {
+        let table = WeightedSampler::new(&[1.0, 6.0, 0.0, 3.0]).unwrap();
+        let expected = table.probabilities();
+        let (probs, aliases) = table.into_raw_parts();
+        assert_eq!(probs.len(), 4);
+        assert_eq!(probs[2], 0.0);
+        let back = WeightedSampler::from_raw_parts(&probs, &aliases).unwrap();
+        for (a, b) in back.probabilities().iter().zip(&expected) {
+            assert!((a - b).abs() < 1e-7, "{a} vs {b}");
+        }
+
+        assert_eq!(
+            WeightedSampler::from_raw_parts(&probs, &aliases[..3]).unwrap_err(),
+            InvariantError::LengthMismatch {
+                probs: 4,
+                aliases: 3
+            }
+        );
+        assert!(matches!(
+            WeightedSampler::from_raw_parts(&[f32::NAN], &[0]),
+            Err(InvariantError::ProbOutOfRange { index: 0, .. })
+        ));
+        assert_eq!(
+            WeightedSampler::from_raw_parts(&[0.5, 1.0], &[2, 1]).unwrap_err(),
+            InvariantError::AliasOutOfRange { index: 0, alias: 2 }
+        );
+    }
+
     #[test]
     fn degenerate_singleton() {
         let alias = WeightedSampler::new(&[5.0]).unwrap();
@@ -193,4 +801,28 @@ mod tests {
             assert_eq!(alias.sample_index(&mut rng), 0);
         }
     }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_build_matches_sequential() {
+        let mut rng = StdRng::seed_from_u64(1348);
+        let n = 5 * WeightedSampler::PARALLEL_CHUNK + 123;
+        // skewed so chunks end up with very different leftovers
+        let weights: Vec<f32> = (0..n)
+            .map(|i| rng.random::<f32>() * if i < n / 3 { 50.0 } else { 1.0 })
+            .collect();
+        let seq = WeightedSampler::new(&weights).unwrap();
+        let par = WeightedSampler::new_parallel(&weights).unwrap();
+        assert_eq!(par.verify_invariants(), Ok(()));
+        // f32 rounding lands in whichever buckets are finalized last, so
+        // compare whole distributions (total variation) rather than entries
+        let tv: f64 = seq
+            .probabilities()
+            .iter()
+            .zip(par.probabilities())
+            .map(|(a, b)| (a - b).abs())
+            .sum::<f64>()
+            / 2.0;
+        assert!(tv < 1e-4, "total variation {tv}");
+    }
 }
@@ -0,0 +1,109 @@
+//! JavaScript bindings (feature `wasm`).
+//!
+//! Build with `wasm-pack build --features wasm` and use from JS:
+//!
+//! ```js
+//! import { JsDropTable } from "droptables";
+//!
+//! const table = JsDropTable.fromText("common:60, rare:9, legendary:1", 42n);
+//! table.sample(); // "common"
+//! ```
+//!
+//! Tables go through the same validation and the same text format as the
+//! Rust API, and sampling uses [`DropRng`], so a seed gives the same drops in
+//! the browser as on a Rust server.
+//!
+//! `rand` still links `getrandom`, which on `wasm32-unknown-unknown` needs
+//! its `wasm_js` backend selected (`RUSTFLAGS='--cfg getrandom_backend="wasm_js"'`
+//! plus `getrandom = { version = "0.3", features = ["wasm_js"] }` in the
+//! final crate). These bindings only use explicitly seeded RNGs.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{DropRng, DropTable, IndexSampler};
+
+/// A string-keyed drop table with its own seeded RNG.
+#[wasm_bindgen]
+pub struct JsDropTable {
+    table: DropTable<String>,
+    rng: DropRng,
+}
+
+#[wasm_bindgen]
+impl JsDropTable {
+    /// `names[i]` drops with weight `weights[i]`. Throws on mismatched
+    /// lengths or invalid weights.
+    #[wasm_bindgen(js_name = fromPairs)]
+    pub fn from_pairs(
+        names: Vec<String>,
+        weights: Vec<f32>,
+        seed: u64,
+    ) -> Result<JsDropTable, String> {
+        if names.len() != weights.len() {
+            return Err(format!(
+                "{} names but {} weights",
+                names.len(),
+                weights.len()
+            ));
+        }
+        let table =
+            DropTable::from_pairs(names.into_iter().zip(weights)).map_err(|e| e.to_string())?;
+        Ok(Self {
+            table,
+            rng: DropRng::seeded(seed),
+        })
+    }
+
+    /// Parse the `name:weight` text format (see [`DropTable::parse`]).
+    #[wasm_bindgen(js_name = fromText)]
+    pub fn from_text(src: &str, seed: u64) -> Result<JsDropTable, String> {
+        let table = DropTable::parse(src).map_err(|e| e.to_string())?;
+        Ok(Self {
+            table,
+            rng: DropRng::seeded(seed),
+        })
+    }
+
+    /// Restart the RNG stream.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = DropRng::seeded(seed);
+    }
+
+    pub fn sample(&mut self) -> String {
+        self.table.sample(&mut self.rng).clone()
+    }
+
+    #[wasm_bindgen(js_name = sampleIndex)]
+    pub fn sample_index(&mut self) -> usize {
+        self.table.sampler.sample_index(&mut self.rng)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.table.items().to_vec()
+    }
+
+    pub fn probabilities(&self) -> Vec<f64> {
+        self.table.probabilities()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.table.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_native_table_for_same_seed() {
+        let mut js = JsDropTable::from_text("a:1, b:3", 9).unwrap();
+        let native = DropTable::parse("a:1, b:3").unwrap();
+        let mut rng = DropRng::seeded(9);
+        for _ in 0..50 {
+            assert_eq!(js.sample(), *native.sample(&mut rng));
+        }
+        assert!(JsDropTable::from_pairs(vec!["a".into()], vec![], 0).is_err());
+    }
+}
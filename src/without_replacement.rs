@@ -0,0 +1,156 @@
+//! Weighted sampling **without replacement**.
+//!
+//! Uses the Gumbel-max trick: perturb each `ln w` with independent Gumbel
+//! noise and keep the largest keys. Ordering by key is distributed exactly
+//! like successive draws where each pick is removed before the next.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::DropTable;
+
+/// An index with its perturbed log-weight, ordered by key.
+#[derive(Debug, Clone, Copy)]
+struct Keyed {
+    key: f64,
+    index: usize,
+}
+
+impl PartialEq for Keyed {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for Keyed {}
+impl PartialOrd for Keyed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Keyed {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.total_cmp(&other.key)
+    }
+}
+
+/// `ln w + Gumbel(0, 1)`, or `None` for zero weights.
+fn gumbel_key<R: Rng + ?Sized>(rng: &mut R, w: f32) -> Option<f64> {
+    if w <= 0.0 {
+        return None;
+    }
+    // u in (0, 1]: avoids ln(0)
+    let u = 1.0 - rng.random::<f64>();
+    Some((w as f64).ln() - (-u.ln()).ln())
+}
+
+/// Indices of the `k` largest Gumbel keys, largest first. Zero weights never
+/// appear. **O(n log k)**.
+pub(crate) fn topk_indices<R: Rng + ?Sized>(rng: &mut R, weights: &[f32], k: usize) -> Vec<usize> {
+    if k == 0 {
+        return Vec::new();
+    }
+    let mut heap: BinaryHeap<Reverse<Keyed>> = BinaryHeap::with_capacity(k + 1);
+    for (index, &w) in weights.iter().enumerate() {
+        let Some(key) = gumbel_key(rng, w) else {
+            continue;
+        };
+        if heap.len() < k {
+            heap.push(Reverse(Keyed { key, index }));
+        } else if heap.peek().is_some_and(|Reverse(min)| key > min.key) {
+            heap.pop();
+            heap.push(Reverse(Keyed { key, index }));
+        }
+    }
+    let mut out: Vec<Keyed> = heap.into_iter().map(|Reverse(k)| k).collect();
+    out.sort_unstable_by(|a, b| b.cmp(a));
+    out.into_iter().map(|k| k.index).collect()
+}
+
+impl<T> DropTable<T> {
+    /// Draw `k` **distinct** items, weighted without replacement, in draw order.
+    ///
+    /// Returns fewer than `k` items if the table has fewer non-zero entries.
+    ///
+    /// # Complexity
+    /// A single O(n log k) pass.
+    pub fn sample_topk_gumbel<'a, R: Rng + ?Sized>(&'a self, rng: &mut R, k: usize) -> Vec<&'a T> {
+        topk_indices(rng, &self.weights, k)
+            .into_iter()
+            .map(|i| &self.items[i])
+            .collect()
+    }
+}
+
+impl<T> DropTable<T> {
+    /// A full weighted-random permutation: equivalent to drawing without
+    /// replacement until the table is empty.
+    ///
+    /// Zero-weight items can never be drawn, so they come last, in uniformly
+    /// random order.
+    ///
+    /// # Complexity
+    /// O(n log n).
+    pub fn shuffle_weighted<'a, R: Rng + ?Sized>(&'a self, rng: &mut R) -> Vec<&'a T> {
+        let mut order = topk_indices(rng, &self.weights, self.items.len());
+        let mut zeros: Vec<usize> = (0..self.items.len())
+            .filter(|&i| self.weights[i] <= 0.0)
+            .collect();
+        zeros.shuffle(rng);
+        order.extend(zeros);
+        order.into_iter().map(|i| &self.items[i]).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn distinct_and_skips_zero_weights() {
+        let table = DropTable::from_pairs([(0, 1.0), (1, 0.0), (2, 5.0), (3, 2.0)]).unwrap();
+        let mut rng = StdRng::seed_from_u64(14);
+        for _ in 0..200 {
+            let mut picked: Vec<i32> = table
+                .sample_topk_gumbel(&mut rng, 10)
+                .into_iter()
+                .copied()
+                .collect();
+            picked.sort();
+            assert_eq!(picked, vec![0, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn shuffle_is_a_permutation_with_zeros_last() {
+        let table = DropTable::from_pairs([(0, 0.0), (1, 1.0), (2, 0.0), (3, 9.0)]).unwrap();
+        let mut rng = StdRng::seed_from_u64(20);
+        for _ in 0..100 {
+            let order: Vec<i32> = table
+                .shuffle_weighted(&mut rng)
+                .into_iter()
+                .copied()
+                .collect();
+            let mut head = order[..2].to_vec();
+            let mut tail = order[2..].to_vec();
+            head.sort();
+            tail.sort();
+            assert_eq!((head, tail), (vec![1, 3], vec![0, 2]));
+        }
+    }
+
+    #[test]
+    fn first_pick_follows_weights() {
+        let table = DropTable::from_pairs([(0, 1.0), (1, 3.0)]).unwrap();
+        let mut rng = StdRng::seed_from_u64(15);
+        let draws = 20_000;
+        let ones = (0..draws)
+            .filter(|_| *table.sample_topk_gumbel(&mut rng, 1)[0] == 1)
+            .count();
+        let p = ones as f64 / draws as f64;
+        assert!((p - 0.75).abs() < 0.02, "p={p}");
+    }
+}